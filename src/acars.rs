@@ -0,0 +1,376 @@
+use anyhow::{Result, anyhow, bail};
+
+use crate::utils;
+
+pub const HOPPIE_URL: &str = "http://www.hoppie.nl/acars/system/connect.html";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageType {
+    Ping,
+    Poll,
+    Peek,
+    Telex,
+    Cpdlc,
+    Progress,
+    InfoRequest,
+}
+
+impl MessageType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MessageType::Ping => "ping",
+            MessageType::Poll => "poll",
+            MessageType::Peek => "peek",
+            MessageType::Telex => "telex",
+            MessageType::Cpdlc => "cpdlc",
+            MessageType::Progress => "progress",
+            MessageType::InfoRequest => "inforeq",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AcarsMessage {
+    pub from: String,
+    pub message_type: String,
+    pub packet: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PdcRequest {
+    pub callsign: String,
+    pub aircraft_type: String,
+    pub departure: String,
+    pub destination: String,
+    pub stand: String,
+    pub atis: String,
+}
+
+impl PdcRequest {
+    // The free text format most VATSIM clearance delivery stations expect
+    pub fn to_telex(&self) -> String {
+        let mut telex = format!(
+            "REQUEST PREDEP CLEARANCE {} {} TO {} AT {}",
+            self.callsign, self.aircraft_type, self.destination, self.departure
+        );
+
+        if !self.stand.is_empty() {
+            telex.push_str(&format!(" STAND {}", self.stand));
+        }
+        if !self.atis.is_empty() {
+            telex.push_str(&format!(" ATIS {}", self.atis));
+        }
+
+        telex.to_uppercase()
+    }
+}
+
+// The Hoppie network is a single HTTP endpoint. Every request carries the logon code, our own
+// callsign, the receiving station and the message type, and each response starts with either
+// "ok" or "error".
+#[derive(Debug, Clone)]
+pub struct AcarsClient {
+    url: String,
+    logon: String,
+    callsign: String,
+}
+
+impl AcarsClient {
+    pub fn new(logon: &str, callsign: &str) -> Self {
+        Self::with_url(HOPPIE_URL, logon, callsign)
+    }
+
+    pub fn with_url(url: &str, logon: &str, callsign: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            logon: logon.to_string(),
+            callsign: callsign.to_uppercase(),
+        }
+    }
+
+    pub async fn send(&self, to: &str, message_type: MessageType, packet: &str) -> Result<String> {
+        if self.logon.is_empty() {
+            bail!("no Hoppie logon code configured");
+        }
+        if self.callsign.is_empty() {
+            bail!("no callsign available, fetch a flight plan first");
+        }
+
+        let params = [
+            ("logon", self.logon.as_str()),
+            ("from", self.callsign.as_str()),
+            ("to", to),
+            ("type", message_type.as_str()),
+            ("packet", packet),
+        ];
+        let body = utils::post_form_data(&self.url, &params).await?;
+
+        parse_response(&body)
+    }
+
+    pub async fn ping(&self) -> Result<String> {
+        self.send("SERVER", MessageType::Ping, "").await
+    }
+
+    pub async fn telex(&self, to: &str, message: &str) -> Result<String> {
+        self.send(to, MessageType::Telex, &message.to_uppercase())
+            .await
+    }
+
+    pub async fn request_pdc(&self, station: &str, request: &PdcRequest) -> Result<String> {
+        self.telex(station, &request.to_telex()).await
+    }
+
+    pub async fn request_metar(&self, icao: &str) -> Result<String> {
+        self.request_information("metar", icao).await
+    }
+
+    pub async fn request_taf(&self, icao: &str) -> Result<String> {
+        self.request_information("taf", icao).await
+    }
+
+    pub async fn request_atis(&self, icao: &str) -> Result<String> {
+        self.request_information("vatatis", icao).await
+    }
+
+    // CPDLC packets are formatted as /data2/<message id>/<reply to id>/<response type>/<message>
+    pub async fn cpdlc_logon(&self, station: &str, message_id: u32) -> Result<String> {
+        let packet = format!("/data2/{message_id}//Y/REQUEST LOGON");
+        self.send(station, MessageType::Cpdlc, &packet).await
+    }
+
+    pub async fn poll(&self) -> Result<Vec<AcarsMessage>> {
+        let body = self.send("SERVER", MessageType::Poll, "").await?;
+
+        Ok(parse_messages(&body))
+    }
+
+    async fn request_information(&self, kind: &str, icao: &str) -> Result<String> {
+        let body = self
+            .send(
+                "SERVER",
+                MessageType::InfoRequest,
+                &format!("{kind} {}", icao.to_uppercase()),
+            )
+            .await?;
+
+        // Information requests are answered with a single message from the server
+        Ok(parse_messages(&body)
+            .into_iter()
+            .next()
+            .map(|message| message.packet)
+            .unwrap_or(body))
+    }
+}
+
+fn parse_response(body: &str) -> Result<String> {
+    let body = body.trim();
+
+    if let Some(rest) = body.strip_prefix("ok") {
+        Ok(rest.trim().to_string())
+    } else if let Some(rest) = body.strip_prefix("error") {
+        Err(anyhow!(
+            "Hoppie returned an error: {}",
+            rest.trim().trim_start_matches('{').trim_end_matches('}')
+        ))
+    } else {
+        Err(anyhow!("unexpected Hoppie response: {body}"))
+    }
+}
+
+// Messages are returned as `{FROM TYPE {PACKET}} {FROM TYPE {PACKET}}`
+fn parse_messages(body: &str) -> Vec<AcarsMessage> {
+    let mut messages = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (index, c) in body.char_indices() {
+        match c {
+            '{' => {
+                if depth == 0 {
+                    start = index + 1;
+                }
+                depth += 1;
+            }
+            '}' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    messages.extend(parse_message(&body[start..index]));
+                }
+            }
+            _ => (),
+        }
+    }
+
+    messages
+}
+
+fn parse_message(message: &str) -> Option<AcarsMessage> {
+    let mut parts = message.trim().splitn(3, ' ');
+    let from = parts.next()?.to_string();
+    let message_type = parts.next()?.to_string();
+    let packet = parts
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .trim_start_matches('{')
+        .trim_end_matches('}')
+        .to_string();
+
+    Some(AcarsMessage {
+        from,
+        message_type,
+        packet,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        task::JoinHandle,
+    };
+
+    use super::*;
+
+    // Stand-in for the Hoppie server which answers a single request with `response` and hands
+    // back the form body it received
+    async fn serve_once(response: &'static str) -> anyhow::Result<(String, JoinHandle<String>)> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!(
+            "http://{}/acars/system/connect.html",
+            listener.local_addr()?
+        );
+
+        let handle = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0; 1024];
+
+            loop {
+                let read = stream.read(&mut buffer).await.unwrap();
+                request.extend_from_slice(&buffer[..read]);
+
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some((headers, body)) = text.split_once("\r\n\r\n") {
+                    let length = headers
+                        .lines()
+                        .find_map(|line| {
+                            line.to_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|v| v.trim().parse::<usize>().unwrap())
+                        })
+                        .unwrap_or_default();
+                    if body.len() >= length || read == 0 {
+                        break;
+                    }
+                }
+            }
+
+            let reply = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+                response.len()
+            );
+            stream.write_all(reply.as_bytes()).await.unwrap();
+
+            let text = String::from_utf8_lossy(&request).to_string();
+            text.split_once("\r\n\r\n")
+                .map(|(_, body)| body.to_string())
+                .unwrap_or_default()
+        });
+
+        Ok((url, handle))
+    }
+
+    #[tokio::test]
+    pub async fn test_request_pdc() -> anyhow::Result<()> {
+        let (url, server) = serve_once("ok").await?;
+        let client = AcarsClient::with_url(&url, "secret", "dlh4ck");
+        let request = PdcRequest {
+            callsign: "DLH4CK".to_string(),
+            aircraft_type: "A320".to_string(),
+            departure: "EDDM".to_string(),
+            destination: "EDDF".to_string(),
+            stand: "G12".to_string(),
+            atis: "A".to_string(),
+        };
+
+        client.request_pdc("EDDM", &request).await?;
+
+        let body = server.await?;
+        assert!(body.contains("logon=secret"));
+        assert!(body.contains("from=DLH4CK"));
+        assert!(body.contains("to=EDDM"));
+        assert!(body.contains("type=telex"));
+        assert!(body.contains(
+            "packet=REQUEST+PREDEP+CLEARANCE+DLH4CK+A320+TO+EDDF+AT+EDDM+STAND+G12+ATIS+A"
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn test_request_metar() -> anyhow::Result<()> {
+        let (url, server) =
+            serve_once("ok {server info {EDDM 181020Z 25008KT CAVOK 12/04 Q1021}}").await?;
+        let client = AcarsClient::with_url(&url, "secret", "DLH4CK");
+
+        let metar = client.request_metar("eddm").await?;
+
+        assert_eq!(metar, "EDDM 181020Z 25008KT CAVOK 12/04 Q1021");
+        assert!(server.await?.contains("packet=metar+EDDM"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn test_cpdlc_logon_and_poll() -> anyhow::Result<()> {
+        let (url, server) = serve_once("ok").await?;
+        let client = AcarsClient::with_url(&url, "secret", "DLH4CK");
+        client.cpdlc_logon("EDGG", 1).await?;
+        assert!(
+            server
+                .await?
+                .contains("packet=%2Fdata2%2F1%2F%2FY%2FREQUEST+LOGON")
+        );
+
+        let (url, server) = serve_once(
+            "ok {EDGG cpdlc {/data2/1/1/NE/LOGON ACCEPTED}} {EDDM telex {CLEARED TO EDDF}}",
+        )
+        .await?;
+        let client = AcarsClient::with_url(&url, "secret", "DLH4CK");
+        let messages = client.poll().await?;
+        server.await?;
+
+        assert_eq!(
+            messages,
+            vec![
+                AcarsMessage {
+                    from: "EDGG".to_string(),
+                    message_type: "cpdlc".to_string(),
+                    packet: "/data2/1/1/NE/LOGON ACCEPTED".to_string(),
+                },
+                AcarsMessage {
+                    from: "EDDM".to_string(),
+                    message_type: "telex".to_string(),
+                    packet: "CLEARED TO EDDF".to_string(),
+                },
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn test_error_response() -> anyhow::Result<()> {
+        let (url, server) = serve_once("error {illegal logon code}").await?;
+        let client = AcarsClient::with_url(&url, "wrong", "DLH4CK");
+
+        let error = client.ping().await.unwrap_err();
+        server.await?;
+
+        assert_eq!(
+            error.to_string(),
+            "Hoppie returned an error: illegal logon code"
+        );
+        Ok(())
+    }
+}
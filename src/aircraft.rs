@@ -0,0 +1,220 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::{
+    airport::Airport, flightplan::FlightPlan, performance::PerformanceProfile, wind::RunwayWind,
+};
+
+// Every aircraft has its own JSON or TOML file in here, see the README for the format
+pub const PROFILES_DIRECTORY: &str = "data/aircraft";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AircraftProfile {
+    // Matched against the type of the OFP, like A20N or B738
+    pub icao_type: String,
+    pub name: String,
+    #[serde(default)]
+    pub limits: Limits,
+    // The performance tables are written next to the other keys
+    #[serde(flatten)]
+    pub performance: PerformanceProfile,
+}
+
+// Weights and fuel in kilograms, winds in knots. Limits that are left out aren't checked.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Limits {
+    pub max_takeoff_weight: Option<f32>,
+    pub max_landing_weight: Option<f32>,
+    pub max_zero_fuel_weight: Option<f32>,
+    pub max_crosswind: Option<f32>,
+    pub max_tailwind: Option<f32>,
+    pub fuel_capacity: Option<f32>,
+}
+
+impl AircraftProfile {
+    // Compares the planned weights and fuel of the OFP against the limits of the profile
+    pub fn weight_warnings(&self, flightplan: &FlightPlan) -> Vec<String> {
+        let weights = &flightplan.weights;
+        let checks = [
+            (
+                "Zero fuel weight",
+                &weights.est_zfw,
+                self.limits.max_zero_fuel_weight,
+            ),
+            (
+                "Takeoff weight",
+                &weights.est_tow,
+                self.limits.max_takeoff_weight,
+            ),
+            (
+                "Landing weight",
+                &weights.est_ldw,
+                self.limits.max_landing_weight,
+            ),
+            (
+                "Ramp fuel",
+                &flightplan.fuel.plan_ramp,
+                self.limits.fuel_capacity,
+            ),
+        ];
+
+        checks
+            .into_iter()
+            .filter_map(|(label, planned, limit)| {
+                let planned = flightplan.weight_kg(planned)?;
+                let limit = limit?;
+
+                (planned > limit).then(|| {
+                    format!(
+                        "{label} of {planned:.0} kg exceeds the {limit:.0} kg limit of the {}",
+                        self.name
+                    )
+                })
+            })
+            .collect()
+    }
+
+    // Checks the reported wind on the planned runway, gusts included
+    pub fn wind_warnings(&self, airport: &Airport) -> Vec<String> {
+        let Some(wind) = RunwayWind::from_weather(&airport.runway, &airport.weather) else {
+            return Vec::new();
+        };
+        let checks = [
+            ("Crosswind", wind.max_crosswind(), self.limits.max_crosswind),
+            ("Tailwind", wind.tailwind(), self.limits.max_tailwind),
+        ];
+
+        checks
+            .into_iter()
+            .filter_map(|(label, component, limit)| {
+                let limit = limit?;
+
+                (component > limit).then(|| {
+                    format!(
+                        "{label} of {component:.0} kt on {} {} exceeds the {limit:.0} kt limit",
+                        airport.icao, wind.runway
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+// SimBrief and the profiles don't always agree on variants, so only the type code is compared
+pub fn find_profile<'a>(
+    profiles: &'a [AircraftProfile],
+    icao_type: &str,
+) -> Option<&'a AircraftProfile> {
+    profiles
+        .iter()
+        .find(|profile| profile.icao_type.eq_ignore_ascii_case(icao_type.trim()))
+}
+
+pub async fn load_profiles() -> Result<Vec<AircraftProfile>> {
+    let mut entries = tokio::fs::read_dir(PROFILES_DIRECTORY)
+        .await
+        .context("unable to read the aircraft profiles")?;
+    let mut profiles = Vec::new();
+
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        let contents = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json" | "toml") => tokio::fs::read_to_string(&path)
+                .await
+                .with_context(|| format!("failed to read {}", path.display()))?,
+            _ => continue,
+        };
+        profiles.push(parse_profile(&path, &contents)?);
+    }
+    profiles.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(profiles)
+}
+
+fn parse_profile(path: &Path, contents: &str) -> Result<AircraftProfile> {
+    let profile = if path
+        .extension()
+        .is_some_and(|extension| extension == "toml")
+    {
+        toml::from_str(contents).map_err(anyhow::Error::from)
+    } else {
+        serde_json::from_str(contents).map_err(anyhow::Error::from)
+    };
+
+    profile.with_context(|| format!("failed to parse {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_limits() -> anyhow::Result<()> {
+        let profiles = vec![
+            parse_profile(
+                Path::new("a20n.toml"),
+                r#"
+icao_type = "A20N"
+name = "A320neo"
+flat_rating_temperature = 30
+max_flex_temperature = 68
+
+[limits]
+max_takeoff_weight = 79000
+max_landing_weight = 67400
+max_crosswind = 20
+max_tailwind = 10
+
+[[takeoff]]
+flaps = "1+F"
+rows = [{ weight = 50000, v1 = 120, vr = 122, v2 = 127, distance = 1300 }]
+"#,
+            )?,
+            parse_profile(
+                Path::new("b738.json"),
+                r#"{"icao_type": "B738", "name": "737-800", "limits": {"max_tailwind": 15}}"#,
+            )?,
+        ];
+
+        let profile = find_profile(&profiles, "a20n").unwrap();
+        assert_eq!(profile.performance.flat_rating_temperature, 30.0);
+        assert_eq!(profile.performance.max_flex_temperature, 68.0);
+        assert_eq!(profile.performance.takeoff.len(), 1);
+        assert!(find_profile(&profiles, "A319").is_none());
+
+        let flightplan: FlightPlan = serde_json::from_str(
+            r#"{
+                "origin": {"icao_code": "EDDF"},
+                "destination": {"icao_code": "EGLL"},
+                "general": {"max_tanks": "18700"},
+                "fuel": {"plan_ramp": "9000"},
+                "weights": {"est_tow": "177000", "est_ldw": "145000"},
+                "params": {"units": "lbs"}
+            }"#,
+        )?;
+        assert_eq!(
+            profile.weight_warnings(&flightplan),
+            vec!["Takeoff weight of 80286 kg exceeds the 79000 kg limit of the A320neo"]
+        );
+
+        let mut airport = Airport {
+            icao: "EGLL".to_string(),
+            runway: "09L".to_string(),
+            ..Default::default()
+        };
+        airport.set_weather(serde_json::from_str(
+            r#"{"wdir": 270, "wspd": 12, "wgst": 25, "visib": "6+", "rawOb": ""}"#,
+        )?);
+        assert_eq!(
+            profile.wind_warnings(&airport),
+            vec!["Tailwind of 12 kt on EGLL 09L exceeds the 10 kt limit"]
+        );
+        assert!(profiles[1].wind_warnings(&airport).is_empty());
+        assert!(profiles[1].wind_warnings(&Airport::default()).is_empty());
+
+        Ok(())
+    }
+}
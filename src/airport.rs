@@ -1,9 +1,101 @@
-use crate::weather::Weather;
+use crate::{
+    database::{AirportDatabase, AirportRecord, RunwayRecord},
+    flightplan,
+    frequency::Frequency,
+    notam::Notam,
+    runway_condition::{self, RunwayCondition},
+    transition,
+    weather::Weather,
+};
 
 #[derive(Default)]
 pub struct Airport {
     pub icao: String,
-    pub transition_level: String,
+    pub transition_altitude: Option<u32>,
+    pub transition_level: Option<u32>,
     pub runway: String,
     pub weather: Weather,
+    pub frequencies: Vec<Frequency>,
+    pub notams: Vec<Notam>,
+    // Name, city and elevation from the airport database, if the ICAO code is known
+    pub info: Option<AirportRecord>,
+    pub runways: Vec<RunwayRecord>,
+    // Latitude and longitude from the OFP
+    pub position: Option<(f64, f64)>,
+}
+
+impl Airport {
+    pub fn set_icao(&mut self, icao: String) {
+        let icao = icao.trim().to_uppercase();
+        if self.icao == icao {
+            return;
+        }
+
+        // The OFP values only apply to the planned airport, so fall back to the country rules
+        self.transition_altitude = transition::transition_altitude(&icao, None);
+        self.transition_level = None;
+        self.position = None;
+        // Neither the METAR nor the runways of the previous airport apply until they are fetched
+        self.weather = Weather::default();
+        self.runways = Vec::new();
+        self.icao = icao;
+        self.update_transition_level();
+    }
+
+    pub fn set_flight_plan_data(&mut self, airport: &flightplan::Airport) {
+        self.icao = airport.icao_code.clone();
+        self.runway = airport.plan_rwy.clone();
+        self.transition_altitude =
+            transition::transition_altitude(&self.icao, airport.trans_alt.parse().ok());
+        self.transition_level = airport.trans_level.parse().ok();
+        self.position = airport
+            .pos_lat
+            .parse()
+            .ok()
+            .zip(airport.pos_long.parse().ok());
+        self.update_transition_level();
+    }
+
+    pub fn lookup(&mut self, database: &AirportDatabase) {
+        self.info = database.get(&self.icao).cloned();
+    }
+
+    pub fn planned_runway(&self) -> Option<&RunwayRecord> {
+        self.runways
+            .iter()
+            .find(|runway| runway.ident.eq_ignore_ascii_case(self.runway.trim()))
+    }
+
+    // The runway end is more precise than the airport reference point
+    pub fn elevation_ft(&self) -> Option<i32> {
+        self.planned_runway()
+            .and_then(|runway| runway.elevation_ft)
+            .or(self.info.as_ref().and_then(|info| info.elevation_ft))
+    }
+
+    // Falls back to the airport database when the airport isn't part of the OFP
+    pub fn coordinates(&self) -> Option<(f64, f64)> {
+        self.position.or(self
+            .info
+            .as_ref()
+            .map(|info| (info.latitude, info.longitude)))
+    }
+
+    pub fn runway_condition(&self) -> RunwayCondition {
+        runway_condition::from_metar(&self.weather.metar, &self.runway)
+    }
+
+    pub fn set_weather(&mut self, weather: Weather) {
+        self.weather = weather;
+        self.update_transition_level();
+    }
+
+    fn update_transition_level(&mut self) {
+        self.transition_level = transition::transition_level(
+            &self.icao,
+            self.transition_altitude,
+            self.weather.altimeter(),
+            self.transition_level,
+        );
+    }
 }
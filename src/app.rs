@@ -1,8 +1,8 @@
 use iced::{
-    Element, Length, Task,
+    Element, Font, Length, Subscription, Task, clipboard, task, time,
     widget::{
-        Button, Column, Container, button, center_x, column, container, row, text, text_editor,
-        text_input,
+        Button, Column, Container, button, canvas, center_x, column, container, row, scrollable,
+        text, text_editor, text_input,
     },
 };
 use tokio::{
@@ -10,131 +10,609 @@ use tokio::{
     io::{AsyncReadExt, AsyncWriteExt},
 };
 
+use std::{collections::HashMap, path::PathBuf, sync::Arc, time::Duration};
+
+use anyhow::Context;
+use chrono::{DateTime, TimeDelta, Utc};
+
 use crate::{
+    acars::{AcarsClient, AcarsMessage, PdcRequest},
+    aircraft::{self, AircraftProfile},
     airport::Airport,
-    flightplan::{FlightPlan, User},
+    briefing::{self, AirportBriefing, Briefing, BriefingFormat},
+    cache::Freshness,
+    chart::TrendChart,
+    clearance::{Clearance, ClearanceField},
+    database::{self, AirportDatabase, AirportRecord, RunwayRecord},
+    flightplan::FlightPlan,
+    frequency::{self, Frequency},
+    fuel::FuelPlan,
+    icao_fpl::{FplItem, IcaoFlightPlan},
+    kneeboard::{self, PageSize},
+    logbook::{self, LogbookEntry, Totals},
+    notam::{self, Notam},
+    ofp::{self, Download},
+    performance::{PerformanceInputs, PerformanceProfile},
+    plan_file::{self, PlanFormat},
+    runway_condition::RunwayCondition,
+    schedule::{self, BlockTimes, Milestone, Schedule},
+    settings::Settings,
     styles,
-    weather::Weather,
+    sun::{Daylight, SunTimes},
+    transition,
+    trend::{self, Trend},
+    utils,
+    vatsim::VatsimData,
+    watchlist::{self, SortColumn, Watchlist},
+    weather::{StationWeather, Weather},
+    wind::RunwayWind,
 };
 
 const USER_SAVE_PATH: &str = "user.json";
 
 #[derive(Debug, Clone)]
 pub enum UserEvent {
-    LoadUserId,
-    UserIdLoaded(Option<String>),
+    LoadSettings,
+    SettingsLoaded(Option<Settings>),
     SetUserId(String),
-    SaveUserId,
-    UserIdSaved(Result<(), String>),
+    SetHoppieLogon(String),
+    SaveSettings,
+    SettingsSaved(Result<(), String>),
+}
+
+#[derive(Debug, Clone)]
+pub enum ClearanceEvent {
+    Edit(ClearanceField, String),
+    ToggleEmergencySquawk,
+}
+
+#[derive(Debug, Clone)]
+pub enum FplEvent {
+    Edit(FplItem, String),
+    Copy,
+    CopySupplementary,
+}
+
+#[derive(Debug, Clone)]
+pub enum AcarsEvent {
+    EditStation(String),
+    EditAircraftType(String),
+    EditStand(String),
+    EditAtis(String),
+    RequestPdc,
+    RequestMetar,
+    RequestTaf,
+    Logon,
+    Poll,
+    Sent(Result<String, String>),
+    Received(Result<Vec<AcarsMessage>, String>),
+    LogAction(text_editor::Action),
+}
+
+#[derive(Debug, Clone)]
+pub enum WatchlistEvent {
+    EditStation(String),
+    Add,
+    Remove(String),
+    Sort(SortColumn),
+    Refresh,
+    Fetched(Result<Vec<Weather>, String>),
+}
+
+#[derive(Debug, Clone)]
+pub enum TrendEvent {
+    Fetch(String),
+    SetHours(u32),
+    Fetched(Result<Trend, String>),
+}
+
+#[derive(Debug, Clone)]
+pub enum OfpEvent {
+    Action(text_editor::Action),
+    Search(String),
+    GoToLine(usize),
+    SetDirectory(String),
+    Download(Download),
+    Downloaded(Result<PathBuf, String>),
+}
+
+#[derive(Debug, Clone)]
+pub enum LogbookEvent {
+    Loaded(Result<(Vec<LogbookEntry>, Vec<String>), String>),
+    EditOnBlock(String),
+    EditFuelUsed(String),
+    Log,
+    Logged(Box<Result<LogbookEntry, String>>),
+    Search(String),
+    ExportCsv,
+    Exported(Result<PathBuf, String>),
+}
+
+#[derive(Debug, Clone)]
+pub enum AircraftEvent {
+    ProfilesLoaded(Result<Vec<AircraftProfile>, String>),
+    SelectProfile(String),
 }
 
 #[derive(Debug, Clone)]
 pub enum Event {
     UserEvent(UserEvent),
+    ClearanceEvent(ClearanceEvent),
+    FplEvent(FplEvent),
+    AcarsEvent(AcarsEvent),
+    WatchlistEvent(WatchlistEvent),
+    TrendEvent(TrendEvent),
+    AircraftEvent(AircraftEvent),
+    LogbookEvent(LogbookEvent),
+    OfpEvent(OfpEvent),
+    AirportDatabaseLoaded(Result<Arc<AirportDatabase>, String>),
     FetchSimbrief,
     FlightPlanFetched(Box<Option<FlightPlan>>),
+    EditPlanImport(String),
+    ImportPlan,
+    PlanImported(Box<Result<FlightPlan, String>>),
     RefreshWeather,
+    // Weather, frequencies and runways of a single airport after its code was edited
+    RefreshAirport(String),
     EditDepartureIcao(String),
-    EditDepartureWeather(Option<Weather>),
     EditArrivalIcao(String),
-    EditArrivalWeather(Option<Weather>),
+    WeatherFetched(Result<StationWeather, String>),
+    // Keyed by ICAO code, so results for a code that was edited since are ignored
+    FrequenciesFetched(Vec<(String, Vec<Frequency>)>),
+    RunwaysFetched(String, Vec<RunwayRecord>),
+    CopyFrequency(String),
+    ExportBriefing(BriefingFormat),
+    ExportKneeboard(PageSize),
+    ExportPlan(PlanFormat),
+    BriefingExported(Result<PathBuf, String>),
     EditDepartureNotes(text_editor::Action),
     EditArrivalNotes(text_editor::Action),
     DepartureMetarAction(text_editor::Action),
     ArrivalMetarAction(text_editor::Action),
     RouteAction(text_editor::Action),
+    EditDiscretionaryFuel(String),
+    EditActualOffBlock(String),
+    SetActualOffBlockNow,
+    Tick,
 }
 
 pub struct App {
-    user_id: String,
+    settings: Settings,
     departure_airport: Airport,
     arrival_airport: Airport,
+    // Airports matching what was typed into the ICAO inputs
+    departure_suggestions: Vec<AirportRecord>,
+    arrival_suggestions: Vec<AirportRecord>,
     departure_notes: text_editor::Content,
     arrival_notes: text_editor::Content,
     departure_metar: text_editor::Content,
     arrival_metar: text_editor::Content,
     flightplan: Option<FlightPlan>,
     route: text_editor::Content,
+    clearance: Clearance,
+    icao_fpl: IcaoFlightPlan,
+    // ICAO code, planned runway and NOTAMs of each alternate
+    alternate_notams: Vec<(String, String, Vec<Notam>)>,
+    alternate_weather: HashMap<String, Weather>,
+    weather_status: String,
+    acars_station: String,
+    pdc_request: PdcRequest,
+    acars_log: text_editor::Content,
+    cpdlc_message_id: u32,
+    export_status: String,
+    watchlist: Watchlist,
+    watchlist_station: String,
+    airport_database: Arc<AirportDatabase>,
+    trend: Option<Trend>,
+    trend_hours: u32,
+    trend_status: String,
+    aircraft_profiles: Vec<AircraftProfile>,
+    // Name of the selected profile
+    aircraft_profile: Option<String>,
+    aircraft_status: String,
+    // Kilograms on top of the OFP block fuel
+    discretionary_fuel: String,
+    actual_off_block: String,
+    logbook: Vec<LogbookEntry>,
+    logbook_search: String,
+    // The flights matching the search, newest first, and their totals
+    logbook_found: Vec<LogbookEntry>,
+    logbook_totals: Totals,
+    actual_on_block: String,
+    // Kilograms, the planned taxi and trip fuel when left empty
+    fuel_used: String,
+    logbook_status: String,
+    // The OFP as text, read only like the route
+    ofp: text_editor::Content,
+    ofp_text: String,
+    ofp_search: String,
+    // Line numbers and lines of the OFP matching the search
+    ofp_matches: Vec<(usize, String)>,
+    download_status: String,
+    // A plan file path or a pasted ICAO flight plan message
+    plan_import: String,
+    import_status: String,
+    // Replacing a handle drops it, which aborts a fetch that is still running so a slow older
+    // response can't overwrite a newer one
+    simbrief_fetch: Option<task::Handle>,
+    weather_fetch: Option<task::Handle>,
 }
 
 impl App {
     pub fn new() -> (Self, Task<Event>) {
         (
             Self {
-                user_id: String::new(),
+                settings: Settings::default(),
                 departure_airport: Airport::default(),
                 arrival_airport: Airport::default(),
+                departure_suggestions: Vec::new(),
+                arrival_suggestions: Vec::new(),
                 departure_notes: text_editor::Content::new(),
                 arrival_notes: text_editor::Content::new(),
                 departure_metar: text_editor::Content::new(),
                 arrival_metar: text_editor::Content::new(),
                 flightplan: None,
                 route: text_editor::Content::new(),
+                clearance: Clearance::default(),
+                icao_fpl: IcaoFlightPlan::default(),
+                alternate_notams: Vec::new(),
+                alternate_weather: HashMap::new(),
+                weather_status: String::new(),
+                acars_station: String::new(),
+                pdc_request: PdcRequest::default(),
+                acars_log: text_editor::Content::new(),
+                cpdlc_message_id: 1,
+                export_status: String::new(),
+                watchlist: Watchlist::default(),
+                watchlist_station: String::new(),
+                airport_database: Arc::default(),
+                trend: None,
+                trend_hours: 6,
+                trend_status: String::new(),
+                aircraft_profiles: Vec::new(),
+                aircraft_profile: None,
+                aircraft_status: String::new(),
+                discretionary_fuel: String::new(),
+                actual_off_block: String::new(),
+                logbook: Vec::new(),
+                logbook_search: String::new(),
+                logbook_found: Vec::new(),
+                logbook_totals: Totals::default(),
+                actual_on_block: String::new(),
+                fuel_used: String::new(),
+                logbook_status: String::new(),
+                ofp: text_editor::Content::new(),
+                ofp_text: String::new(),
+                ofp_search: String::new(),
+                ofp_matches: Vec::new(),
+                download_status: String::new(),
+                plan_import: String::new(),
+                import_status: String::new(),
+                simbrief_fetch: None,
+                weather_fetch: None,
             },
-            Task::done(Event::UserEvent(UserEvent::LoadUserId)),
+            Task::batch([
+                Task::done(Event::UserEvent(UserEvent::LoadSettings)),
+                Task::perform(AirportDatabase::load(), |result| {
+                    Event::AirportDatabaseLoaded(result.map(Arc::new).map_err(|e| format!("{e:#}")))
+                }),
+                Task::perform(aircraft::load_profiles(), |result| {
+                    Event::AircraftEvent(AircraftEvent::ProfilesLoaded(
+                        result.map_err(|e| format!("{e:#}")),
+                    ))
+                }),
+                Task::perform(
+                    logbook::load(PathBuf::from(logbook::LOGBOOK_PATH)),
+                    |result| {
+                        Event::LogbookEvent(LogbookEvent::Loaded(
+                            result.map_err(|e| format!("{e:#}")),
+                        ))
+                    },
+                ),
+            ]),
         )
     }
 
     pub fn update(&mut self, event: Event) -> Task<Event> {
         match event {
             Event::UserEvent(event) => self.perform_user_event(event),
-            Event::FetchSimbrief => Task::perform(
-                Self::refresh_simbrief_flightplan(self.user_id.clone()),
-                Event::FlightPlanFetched,
-            ),
+            Event::ClearanceEvent(event) => {
+                self.perform_clearance_event(event);
+                Task::none()
+            }
+            Event::FplEvent(event) => self.perform_fpl_event(event),
+            Event::AcarsEvent(event) => self.perform_acars_event(event),
+            Event::WatchlistEvent(event) => self.perform_watchlist_event(event),
+            Event::TrendEvent(event) => self.perform_trend_event(event),
+            Event::AircraftEvent(event) => {
+                self.perform_aircraft_event(event);
+                Task::none()
+            }
+            Event::LogbookEvent(event) => self.perform_logbook_event(event),
+            Event::OfpEvent(event) => self.perform_ofp_event(event),
+            Event::AirportDatabaseLoaded(result) => {
+                match result {
+                    Ok(database) => {
+                        self.airport_database = database;
+                        self.departure_airport.lookup(&self.airport_database);
+                        self.arrival_airport.lookup(&self.airport_database);
+                        self.update_airport_suggestions();
+                    }
+                    Err(e) => eprintln!("{e}"),
+                }
+                Task::none()
+            }
+            Event::FetchSimbrief => {
+                let (task, handle) = Task::perform(
+                    Self::refresh_simbrief_flightplan(self.settings.simbrief_id.clone()),
+                    Event::FlightPlanFetched,
+                )
+                .abortable();
+                self.simbrief_fetch = Some(handle.abort_on_drop());
+                task
+            }
+            Event::EditPlanImport(input) => {
+                self.plan_import = input;
+                Task::none()
+            }
+            Event::ImportPlan => {
+                Task::perform(plan_file::import(self.plan_import.clone()), |result| {
+                    Event::PlanImported(Box::new(result.map_err(|e| format!("{e:#}"))))
+                })
+            }
+            Event::PlanImported(result) => match *result {
+                Ok(flightplan) => {
+                    self.import_status = format!(
+                        "Imported {} to {}",
+                        flightplan.origin.icao_code, flightplan.destination.icao_code
+                    );
+                    // A SimBrief fetch still running would replace the imported plan
+                    self.simbrief_fetch = None;
+                    Task::done(Event::FlightPlanFetched(Box::new(Some(flightplan))))
+                }
+                Err(e) => {
+                    self.import_status = e;
+                    Task::none()
+                }
+            },
             Event::FlightPlanFetched(option) => {
                 self.flightplan = *option;
+                self.match_aircraft_profile();
                 if let Some(flightplan) = &self.flightplan {
-                    let departure_icao = flightplan.origin.icao_code.clone();
-                    let arrival_icao = flightplan.destination.icao_code.clone();
+                    self.departure_airport
+                        .set_flight_plan_data(&flightplan.origin);
+                    self.arrival_airport
+                        .set_flight_plan_data(&flightplan.destination);
+                    self.departure_airport.lookup(&self.airport_database);
+                    self.arrival_airport.lookup(&self.airport_database);
+                    self.departure_suggestions =
+                        Self::airport_suggestions(&self.airport_database, &self.departure_airport);
+                    self.arrival_suggestions =
+                        Self::airport_suggestions(&self.airport_database, &self.arrival_airport);
                     self.route = text_editor::Content::with_text(
                         &flightplan.flight_information.route_navigraph,
                     );
-                    Task::batch([
-                        Task::done(Event::EditDepartureIcao(departure_icao)),
-                        Task::done(Event::EditArrivalIcao(arrival_icao)),
-                    ])
+                    self.clearance = Clearance::from_flight_plan(flightplan);
+                    self.icao_fpl = IcaoFlightPlan::from_flight_plan(flightplan);
+                    self.ofp_text = ofp::plan_text(&flightplan.text.plan_html);
+                    self.ofp = text_editor::Content::with_text(&self.ofp_text);
+                    self.ofp_matches = Self::ofp_matches(&self.ofp_text, &self.ofp_search);
+
+                    let times = &flightplan.times;
+                    self.departure_airport.notams = notam::relevant_notams(
+                        &flightplan.origin.notam,
+                        times.scheduled_out(),
+                        times.scheduled_off(),
+                    );
+                    self.arrival_airport.notams = notam::relevant_notams(
+                        &flightplan.destination.notam,
+                        times.scheduled_on(),
+                        times.scheduled_in(),
+                    );
+                    self.alternate_notams = flightplan
+                        .alternate
+                        .iter()
+                        .map(|alternate| {
+                            (
+                                alternate.icao_code.clone(),
+                                alternate.plan_rwy.clone(),
+                                notam::relevant_notams(
+                                    &alternate.notam,
+                                    times.scheduled_on(),
+                                    times.scheduled_in(),
+                                ),
+                            )
+                        })
+                        .collect();
+
+                    self.acars_station = flightplan.origin.icao_code.clone();
+                    self.pdc_request.callsign = flightplan.callsign();
+                    self.pdc_request.departure = flightplan.origin.icao_code.clone();
+                    self.pdc_request.destination = flightplan.destination.icao_code.clone();
+                    Task::none()
                 } else {
                     println!("No flight plan fetched");
                     Task::none()
                 }
             }
-            Event::RefreshWeather => Task::batch([
+            Event::RefreshWeather => {
+                let (task, handle) = Task::batch([
+                    Task::perform(
+                        Self::refresh_airport_weather(self.weather_stations()),
+                        |result| Event::WeatherFetched(result.map_err(|e| format!("{e:#}"))),
+                    ),
+                    Task::perform(
+                        Self::refresh_frequencies(vec![
+                            self.departure_airport.icao.clone(),
+                            self.arrival_airport.icao.clone(),
+                        ]),
+                        Event::FrequenciesFetched,
+                    ),
+                    Task::perform(
+                        Self::refresh_runways(self.departure_airport.icao.clone()),
+                        |(icao, runways)| Event::RunwaysFetched(icao, runways),
+                    ),
+                    Task::perform(
+                        Self::refresh_runways(self.arrival_airport.icao.clone()),
+                        |(icao, runways)| Event::RunwaysFetched(icao, runways),
+                    ),
+                    self.refresh_watchlist(),
+                ])
+                .abortable();
+                self.weather_fetch = Some(handle.abort_on_drop());
+                task
+            }
+            Event::RefreshAirport(icao) => Task::batch([
                 Task::perform(
-                    Self::refresh_airport_weather(self.departure_airport.icao.clone()),
-                    Event::EditDepartureWeather,
+                    Self::refresh_airport_weather(vec![icao.clone()]),
+                    |result| Event::WeatherFetched(result.map_err(|e| format!("{e:#}"))),
                 ),
                 Task::perform(
-                    Self::refresh_airport_weather(self.arrival_airport.icao.clone()),
-                    Event::EditArrivalWeather,
+                    Self::refresh_frequencies(vec![icao.clone()]),
+                    Event::FrequenciesFetched,
                 ),
+                Task::perform(Self::refresh_runways(icao), |(icao, runways)| {
+                    Event::RunwaysFetched(icao, runways)
+                }),
             ]),
             Event::EditDepartureIcao(icao) => {
-                Self::set_icao(&mut self.departure_airport, icao);
-                Task::none()
+                let changed = Self::edit_icao(
+                    &mut self.departure_airport,
+                    &mut self.departure_metar,
+                    &self.airport_database,
+                    icao,
+                );
+                self.update_airport_suggestions();
+                if changed {
+                    Task::done(Event::RefreshAirport(self.departure_airport.icao.clone()))
+                } else {
+                    Task::none()
+                }
+            }
+            Event::EditArrivalIcao(icao) => {
+                let changed = Self::edit_icao(
+                    &mut self.arrival_airport,
+                    &mut self.arrival_metar,
+                    &self.airport_database,
+                    icao,
+                );
+                self.update_airport_suggestions();
+                if changed {
+                    Task::done(Event::RefreshAirport(self.arrival_airport.icao.clone()))
+                } else {
+                    Task::none()
+                }
             }
-            Event::EditDepartureWeather(weather) => {
-                if let Some(weather) = weather {
+            Event::WeatherFetched(Ok(station_weather)) => {
+                let departure = station_weather
+                    .reports
+                    .get(&self.departure_airport.icao.to_uppercase())
+                    .cloned();
+                if let Some(weather) = departure {
                     Self::set_current_weather(
                         &mut self.departure_airport,
                         &mut self.departure_metar,
                         weather,
                     );
                 }
-                Task::none()
-            }
-            Event::EditArrivalIcao(icao) => {
-                Self::set_icao(&mut self.arrival_airport, icao);
-                Task::none()
-            }
-            Event::EditArrivalWeather(weather) => {
-                if let Some(weather) = weather {
+                let arrival = station_weather
+                    .reports
+                    .get(&self.arrival_airport.icao.to_uppercase())
+                    .cloned();
+                if let Some(weather) = arrival {
                     Self::set_current_weather(
                         &mut self.arrival_airport,
                         &mut self.arrival_metar,
                         weather,
                     );
                 }
+                // An alternate can be the departure airport too, so it keeps its own copy. A
+                // refresh of a single airport leaves the other alternates alone.
+                let is_alternate = |station: &str| {
+                    self.alternate_notams
+                        .iter()
+                        .any(|(icao, _, _)| icao.eq_ignore_ascii_case(station))
+                };
+                self.alternate_weather.retain(|station, _| {
+                    is_alternate(station) && !station_weather.missing.contains(station)
+                });
+                self.alternate_weather.extend(
+                    station_weather
+                        .reports
+                        .iter()
+                        .filter(|(station, _)| is_alternate(station))
+                        .map(|(station, weather)| (station.clone(), weather.clone())),
+                );
+
+                self.weather_status = if station_weather.missing.is_empty() {
+                    String::new()
+                } else {
+                    format!("No weather for {}", station_weather.missing.join(", "))
+                };
+                Task::none()
+            }
+            Event::WeatherFetched(Err(e)) => {
+                self.weather_status = e;
+                Task::none()
+            }
+            Event::FrequenciesFetched(frequencies) => {
+                for (icao, frequencies) in frequencies {
+                    for airport in [&mut self.departure_airport, &mut self.arrival_airport] {
+                        if airport.icao == icao {
+                            airport.frequencies = frequencies.clone();
+                        }
+                    }
+                }
+                Task::none()
+            }
+            Event::RunwaysFetched(icao, runways) => {
+                for airport in [&mut self.departure_airport, &mut self.arrival_airport] {
+                    if airport.icao == icao {
+                        airport.runways = runways.clone();
+                    }
+                }
+                Task::none()
+            }
+            Event::CopyFrequency(frequency) => clipboard::write(frequency),
+            Event::ExportBriefing(format) => {
+                let contents = self.briefing().render(format);
+                Task::perform(
+                    briefing::save(contents, format, self.download_directory()),
+                    |result| Event::BriefingExported(result.map_err(|e| format!("{e:#}"))),
+                )
+            }
+            Event::ExportKneeboard(size) => {
+                let contents = kneeboard::render(&self.briefing(), size);
+                Task::perform(
+                    kneeboard::save(contents, self.download_directory()),
+                    |result| Event::BriefingExported(result.map_err(|e| format!("{e:#}"))),
+                )
+            }
+            Event::ExportPlan(format) => {
+                let Some(flightplan) = &self.flightplan else {
+                    self.export_status = "Fetch the OFP to export the flight plan".to_string();
+                    return Task::none();
+                };
+                let file_stem = format!(
+                    "{}{}",
+                    flightplan.origin.icao_code, flightplan.destination.icao_code
+                );
+                // The ICAO message as it was corrected in the flight plan section
+                let contents = if format == PlanFormat::IcaoFpl {
+                    self.icao_fpl.message() + "\n"
+                } else {
+                    format.render(flightplan)
+                };
+                Task::perform(
+                    plan_file::save(contents, format, file_stem, self.download_directory()),
+                    |result| Event::BriefingExported(result.map_err(|e| format!("{e:#}"))),
+                )
+            }
+            Event::BriefingExported(result) => {
+                self.export_status = match result {
+                    Ok(path) => format!("Saved {}", path.display()),
+                    Err(e) => e,
+                };
                 Task::none()
             }
             Event::EditDepartureNotes(action) => {
@@ -163,11 +641,61 @@ impl App {
                 }
                 Task::none()
             }
+            Event::EditDiscretionaryFuel(fuel) => {
+                self.discretionary_fuel = fuel;
+                Task::none()
+            }
+            Event::EditActualOffBlock(time) => {
+                self.actual_off_block = time;
+                Task::none()
+            }
+            Event::SetActualOffBlockNow => {
+                self.actual_off_block = Utc::now().format("%H%M").to_string();
+                Task::none()
+            }
+            // Nothing to update, the countdowns are worked out when the view is drawn
+            Event::Tick => Task::none(),
+        }
+    }
+
+    // Returns true once a new and valid code has been entered, so the weather can be fetched
+    // without waiting for the refresh button
+    fn edit_icao(
+        airport: &mut Airport,
+        metar: &mut text_editor::Content,
+        database: &AirportDatabase,
+        icao: String,
+    ) -> bool {
+        let previous = airport.icao.clone();
+        airport.set_icao(icao);
+        airport.lookup(database);
+        if airport.icao == previous {
+            return false;
         }
+
+        // The weather of the previous airport was cleared along with the code
+        *metar = text_editor::Content::new();
+        database.is_valid_icao(&airport.icao)
+    }
+
+    fn update_airport_suggestions(&mut self) {
+        self.departure_suggestions =
+            Self::airport_suggestions(&self.airport_database, &self.departure_airport);
+        self.arrival_suggestions =
+            Self::airport_suggestions(&self.airport_database, &self.arrival_airport);
     }
 
-    fn set_icao(airport: &mut Airport, icao: String) {
-        airport.icao = icao;
+    // Suggestions are only shown while the code doesn't match a known airport
+    fn airport_suggestions(database: &AirportDatabase, airport: &Airport) -> Vec<AirportRecord> {
+        if airport.info.is_some() {
+            return Vec::new();
+        }
+
+        database
+            .search(&airport.icao, 5)
+            .into_iter()
+            .cloned()
+            .collect()
     }
 
     fn set_current_weather(
@@ -176,27 +704,52 @@ impl App {
         weather: Weather,
     ) {
         *metar = text_editor::Content::with_text(&weather.metar);
-        airport.weather = weather;
+        airport.set_weather(weather);
+    }
+
+    fn briefing(&self) -> Briefing<'_> {
+        Briefing {
+            flightplan: self.flightplan.as_ref(),
+            departure: AirportBriefing {
+                role: "Departure",
+                airport: &self.departure_airport,
+                notes: self.departure_notes.text(),
+            },
+            arrival: AirportBriefing {
+                role: "Arrival",
+                airport: &self.arrival_airport,
+                notes: self.arrival_notes.text(),
+            },
+        }
     }
 
     fn perform_user_event(&mut self, event: UserEvent) -> Task<Event> {
         match event {
-            UserEvent::LoadUserId => Task::perform(Self::load_user_id(USER_SAVE_PATH), |user_id| {
-                Event::UserEvent(UserEvent::UserIdLoaded(user_id))
-            }),
-            UserEvent::UserIdLoaded(user_id) => match user_id {
-                Some(user_id) => Task::done(Event::UserEvent(UserEvent::SetUserId(user_id))),
-                None => Task::none(),
-            },
+            UserEvent::LoadSettings => {
+                Task::perform(Self::load_settings(USER_SAVE_PATH), |settings| {
+                    Event::UserEvent(UserEvent::SettingsLoaded(settings))
+                })
+            }
+            UserEvent::SettingsLoaded(settings) => {
+                if let Some(settings) = settings {
+                    self.watchlist = Watchlist::new(settings.watchlist.clone());
+                    self.settings = settings;
+                }
+                self.refresh_watchlist()
+            }
             UserEvent::SetUserId(user_id) => {
-                self.user_id = user_id;
+                self.settings.simbrief_id = user_id;
+                Task::none()
+            }
+            UserEvent::SetHoppieLogon(logon) => {
+                self.settings.hoppie_logon = logon;
                 Task::none()
             }
-            UserEvent::SaveUserId => Task::perform(
-                Self::save_user_id(USER_SAVE_PATH, self.user_id.clone()),
-                |result| Event::UserEvent(UserEvent::UserIdSaved(result)),
+            UserEvent::SaveSettings => Task::perform(
+                Self::save_settings(USER_SAVE_PATH, self.settings.clone()),
+                |result| Event::UserEvent(UserEvent::SettingsSaved(result)),
             ),
-            UserEvent::UserIdSaved(result) => {
+            UserEvent::SettingsSaved(result) => {
                 match result {
                     Ok(_) => (),
                     Err(e) => eprintln!("{e}"),
@@ -207,86 +760,831 @@ impl App {
         }
     }
 
-    pub fn view(&self) -> Element<'_, Event> {
-        let user_id_input_field = center_x(
-            container(
-                text_input::TextInput::new("Simbrief", &self.user_id)
-                    .on_input(|input| Event::UserEvent(UserEvent::SetUserId(input)))
-                    .on_submit(Event::UserEvent(UserEvent::SaveUserId))
-                    .width(Length::Fixed(70.0)),
-            )
-            .style(container::bordered_box),
-        );
+    fn perform_clearance_event(&mut self, event: ClearanceEvent) {
+        match event {
+            ClearanceEvent::Edit(field, value) => self.clearance.set(field, value),
+            ClearanceEvent::ToggleEmergencySquawk => {
+                self.clearance.emergency_squawk_intended = !self.clearance.emergency_squawk_intended
+            }
+        }
+    }
 
-        let simbrief_button = button("Fetch Simbrief").on_press(Event::FetchSimbrief);
-        let weather_button = button("Refresh Weather").on_press(Event::RefreshWeather);
+    fn perform_fpl_event(&mut self, event: FplEvent) -> Task<Event> {
+        match event {
+            FplEvent::Edit(item, value) => {
+                self.icao_fpl.set(item, value);
+                Task::none()
+            }
+            FplEvent::Copy => clipboard::write(self.icao_fpl.message()),
+            FplEvent::CopySupplementary => {
+                clipboard::write(self.icao_fpl.supplementary.trim().to_string())
+            }
+        }
+    }
 
-        let departure_column = Self::create_column(
-            simbrief_button,
-            &self.departure_airport,
-            Event::EditDepartureIcao,
-            &self.departure_notes,
-            Event::EditDepartureNotes,
-            &self.departure_metar,
-            Event::DepartureMetarAction,
-        );
+    fn perform_watchlist_event(&mut self, event: WatchlistEvent) -> Task<Event> {
+        match event {
+            WatchlistEvent::EditStation(station) => {
+                self.watchlist_station = station.to_uppercase();
+                Task::none()
+            }
+            WatchlistEvent::Add => {
+                if !self.watchlist.add(&self.watchlist_station) {
+                    return Task::none();
+                }
+                self.watchlist_station.clear();
+                self.settings.watchlist = self.watchlist.stations().to_vec();
+                Task::batch([
+                    Task::done(Event::UserEvent(UserEvent::SaveSettings)),
+                    self.refresh_watchlist(),
+                ])
+            }
+            WatchlistEvent::Remove(station) => {
+                self.watchlist.remove(&station);
+                self.settings.watchlist = self.watchlist.stations().to_vec();
+                Task::done(Event::UserEvent(UserEvent::SaveSettings))
+            }
+            WatchlistEvent::Sort(column) => {
+                self.watchlist.sort_by(column);
+                Task::none()
+            }
+            WatchlistEvent::Refresh => self.refresh_watchlist(),
+            WatchlistEvent::Fetched(result) => {
+                match result {
+                    Ok(weather) => self.watchlist.set_weather(weather),
+                    Err(e) => eprintln!("Unable to fetch the watchlist weather: {e}"),
+                }
+                Task::none()
+            }
+        }
+    }
 
-        let arrival_column = Self::create_column(
-            weather_button,
-            &self.arrival_airport,
-            Event::EditArrivalIcao,
-            &self.arrival_notes,
-            Event::EditArrivalNotes,
-            &self.arrival_metar,
-            Event::ArrivalMetarAction,
-        );
+    fn perform_trend_event(&mut self, event: TrendEvent) -> Task<Event> {
+        match event {
+            TrendEvent::Fetch(icao) => {
+                self.trend_status =
+                    format!("Fetching the last {} hours for {icao}", self.trend_hours);
+                let hours = self.trend_hours;
+                Task::perform(
+                    async move {
+                        let observations = Weather::fetch_history(&icao, hours).await?;
+                        anyhow::Ok(Trend::new(&icao, observations))
+                    },
+                    |result| {
+                        Event::TrendEvent(TrendEvent::Fetched(result.map_err(|e| format!("{e:#}"))))
+                    },
+                )
+            }
+            TrendEvent::SetHours(hours) => {
+                self.trend_hours = hours;
+                match &self.trend {
+                    Some(trend) => {
+                        Task::done(Event::TrendEvent(TrendEvent::Fetch(trend.station.clone())))
+                    }
+                    None => Task::none(),
+                }
+            }
+            TrendEvent::Fetched(result) => {
+                match result {
+                    Ok(trend) => {
+                        self.trend_status = format!(
+                            "{} observations at {}, conditions are {}",
+                            trend.observations.len(),
+                            trend.station,
+                            trend.assessment()
+                        );
+                        self.trend = Some(trend);
+                    }
+                    Err(e) => self.trend_status = e,
+                }
+                Task::none()
+            }
+        }
+    }
 
-        let weather_and_notes_row = row![departure_column, arrival_column].spacing(20);
+    fn perform_aircraft_event(&mut self, event: AircraftEvent) {
+        match event {
+            AircraftEvent::ProfilesLoaded(Ok(profiles)) => {
+                self.aircraft_profiles = profiles;
+                self.aircraft_status = String::new();
+                self.match_aircraft_profile();
+                // Without an OFP there is no type to match, any profile is as good as another
+                if self.flightplan.is_none() {
+                    self.aircraft_profile = self
+                        .aircraft_profiles
+                        .first()
+                        .map(|profile| profile.name.clone());
+                }
+            }
+            AircraftEvent::ProfilesLoaded(Err(e)) => self.aircraft_status = e,
+            AircraftEvent::SelectProfile(name) => self.aircraft_profile = Some(name),
+        }
+    }
 
-        let flight_plan_section = Self::populate_flight_plan_information();
+    fn perform_ofp_event(&mut self, event: OfpEvent) -> Task<Event> {
+        match event {
+            OfpEvent::Action(action) => {
+                if !matches!(action, text_editor::Action::Edit(_)) {
+                    self.ofp.perform(action);
+                }
+                Task::none()
+            }
+            OfpEvent::Search(query) => {
+                self.ofp_matches = Self::ofp_matches(&self.ofp_text, &query);
+                self.ofp_search = query;
+                Task::none()
+            }
+            // The OFP isn't wrapped, so moving down a line is moving to the next line of the text
+            OfpEvent::GoToLine(line) => {
+                self.ofp.perform(text_editor::Action::Move(
+                    text_editor::Motion::DocumentStart,
+                ));
+                for _ in 0..line {
+                    self.ofp
+                        .perform(text_editor::Action::Move(text_editor::Motion::Down));
+                }
+                self.ofp.perform(text_editor::Action::SelectLine);
+                Task::none()
+            }
+            OfpEvent::SetDirectory(directory) => {
+                self.settings.download_directory = directory;
+                Task::none()
+            }
+            OfpEvent::Download(download) => {
+                self.download_status = format!("Downloading {}", download.name);
+                Task::perform(
+                    ofp::download(download, self.download_directory()),
+                    |result| {
+                        Event::OfpEvent(OfpEvent::Downloaded(result.map_err(|e| format!("{e:#}"))))
+                    },
+                )
+            }
+            OfpEvent::Downloaded(result) => {
+                self.download_status = match result {
+                    Ok(path) => format!("Saved {}", path.display()),
+                    Err(e) => e,
+                };
+                Task::none()
+            }
+        }
+    }
 
-        let route_section = Self::create_route_container(&self.route);
+    fn perform_logbook_event(&mut self, event: LogbookEvent) -> Task<Event> {
+        match event {
+            LogbookEvent::Loaded(Ok((entries, errors))) => {
+                for error in &errors {
+                    eprintln!("Skipped a logbook entry: {error}");
+                }
+                if !errors.is_empty() {
+                    self.logbook_status = format!(
+                        "Skipped {} unreadable lines of {}: {}",
+                        errors.len(),
+                        logbook::LOGBOOK_PATH,
+                        errors.join("; ")
+                    );
+                }
+                self.logbook = entries;
+                self.update_logbook_search();
+                Task::none()
+            }
+            LogbookEvent::Loaded(Err(e)) => {
+                self.logbook_status = e;
+                Task::none()
+            }
+            LogbookEvent::EditOnBlock(time) => {
+                self.actual_on_block = time;
+                Task::none()
+            }
+            LogbookEvent::EditFuelUsed(fuel) => {
+                self.fuel_used = fuel;
+                Task::none()
+            }
+            LogbookEvent::Log => match self.logbook_entry() {
+                Ok(entry)
+                    if self
+                        .logbook
+                        .iter()
+                        .any(|logged| logged.is_same_flight(&entry)) =>
+                {
+                    self.logbook_status = format!(
+                        "{} off block at {} is already logged",
+                        entry.callsign,
+                        entry.off_block.format("%Y-%m-%d %H:%MZ")
+                    );
+                    Task::none()
+                }
+                Ok(entry) => Task::perform(
+                    logbook::append(PathBuf::from(logbook::LOGBOOK_PATH), entry),
+                    |result| {
+                        Event::LogbookEvent(LogbookEvent::Logged(Box::new(
+                            result.map_err(|e| format!("{e:#}")),
+                        )))
+                    },
+                ),
+                Err(e) => {
+                    self.logbook_status = format!("{e:#}");
+                    Task::none()
+                }
+            },
+            LogbookEvent::Logged(result) => match *result {
+                Ok(entry) => {
+                    self.logbook_status = format!(
+                        "Logged {} from {} to {}",
+                        entry.callsign, entry.origin, entry.destination
+                    );
+                    self.actual_on_block.clear();
+                    self.fuel_used.clear();
+                    self.logbook.push(entry);
+                    self.update_logbook_search();
+                    Task::none()
+                }
+                Err(e) => {
+                    self.logbook_status = e;
+                    Task::none()
+                }
+            },
+            LogbookEvent::Search(query) => {
+                self.logbook_search = query;
+                self.update_logbook_search();
+                Task::none()
+            }
+            // Only the flights matching the search are exported
+            LogbookEvent::ExportCsv => {
+                let contents = logbook::to_csv(&self.logbook_found);
+                Task::perform(
+                    logbook::export_csv(contents, self.download_directory()),
+                    |result| {
+                        Event::LogbookEvent(LogbookEvent::Exported(
+                            result.map_err(|e| format!("{e:#}")),
+                        ))
+                    },
+                )
+            }
+            LogbookEvent::Exported(result) => {
+                self.logbook_status = match result {
+                    Ok(path) => format!("Saved {}", path.display()),
+                    Err(e) => e,
+                };
+                Task::none()
+            }
+        }
+    }
 
-        column![
-            user_id_input_field,
-            weather_and_notes_row,
-            flight_plan_section,
-            route_section
-        ]
-        .spacing(10)
-        .padding(10)
-        .into()
+    fn ofp_matches(text: &str, search: &str) -> Vec<(usize, String)> {
+        ofp::find_lines(text, search)
+            .into_iter()
+            .map(|(line, contents)| (line, contents.trim().to_string()))
+            .collect()
     }
 
-    fn create_column<'a>(
-        btn: Button<'a, Event>,
-        airport: &'a Airport,
-        icao_action: impl Fn(String) -> Event + 'a,
-        editor_content: &'a text_editor::Content,
-        editor_action: impl Fn(text_editor::Action) -> Event + 'a,
-        metar_content: &'a text_editor::Content,
-        metar_action: impl Fn(text_editor::Action) -> Event + 'a,
-    ) -> Column<'a, Event> {
-        let icao_row = row![
-            styles::label_container("ICAO"),
-            styles::value_row(
-                container(text_input("ICAO", &airport.icao).on_input(icao_action)).into()
-            )
-        ];
+    fn update_logbook_search(&mut self) {
+        let found = logbook::search(&self.logbook, &self.logbook_search);
+        self.logbook_totals = Totals::of(found.iter().copied());
+        self.logbook_found = found.into_iter().cloned().collect();
+    }
 
-        let wind_row = row![
-            styles::label_container("Wind"),
-            styles::value_row(
-                row![
-                    styles::bordered_text_container(airport.weather.wind_direction().to_string()),
-                    styles::label_container("  °"),
-                    styles::bordered_text_container(airport.weather.wind_speed().to_string()),
+    fn download_directory(&self) -> PathBuf {
+        match self.settings.download_directory.trim() {
+            "" => PathBuf::from("."),
+            directory => PathBuf::from(directory),
+        }
+    }
+
+    // The takeoff and landing are the estimates moved along with the actual block times
+    fn logbook_entry(&self) -> anyhow::Result<LogbookEntry> {
+        let flightplan = self
+            .flightplan
+            .as_ref()
+            .context("fetch the OFP of the flight to log it")?;
+        let schedule = Schedule::from_times(&flightplan.times);
+        let expected = schedule.expected(schedule.actual_off_block(&self.actual_off_block));
+
+        let planned_on_block = expected.on_block.context("the OFP has no on block time")?;
+        let on_block = schedule::parse_time_of_day(&self.actual_on_block, planned_on_block)
+            .context("enter the on block time as HHMM")?;
+        let actual = BlockTimes {
+            landing: expected
+                .landing
+                .map(|landing| landing + (on_block - planned_on_block)),
+            on_block: Some(on_block),
+            ..expected
+        };
+
+        let fuel_used = match self.fuel_used.trim() {
+            "" => FuelPlan::from_flight_plan(flightplan).map(|fuel| fuel.taxi + fuel.trip),
+            fuel => Some(fuel.parse().context("the fuel used is not a number")?),
+        };
+
+        LogbookEntry::from_flight(
+            flightplan,
+            actual,
+            fuel_used,
+            self.departure_airport.coordinates(),
+            self.arrival_airport.coordinates(),
+        )
+    }
+
+    // Selects the profile for the aircraft type of the OFP, a manual selection stays until the
+    // next OFP is fetched
+    // Clears the selection when no profile is for the type of the OFP, the limits and the
+    // performance of another aircraft would be wrong. The pilot can still pick one by hand.
+    fn match_aircraft_profile(&mut self) {
+        let Some(flightplan) = &self.flightplan else {
+            return;
+        };
+
+        self.aircraft_profile =
+            aircraft::find_profile(&self.aircraft_profiles, &flightplan.aircraft.icao_code)
+                .map(|profile| profile.name.clone());
+    }
+
+    fn selected_profile(&self) -> Option<&AircraftProfile> {
+        let name = self.aircraft_profile.as_ref()?;
+
+        self.aircraft_profiles
+            .iter()
+            .find(|profile| &profile.name == name)
+    }
+
+    // Weight limits for the OFP and wind limits for both runways
+    fn aircraft_warnings(&self) -> Vec<String> {
+        let Some(profile) = self.selected_profile() else {
+            return Vec::new();
+        };
+
+        let mut warnings = Vec::new();
+        if let Some(flightplan) = &self.flightplan {
+            // Picked by hand for another type
+            if !profile
+                .icao_type
+                .eq_ignore_ascii_case(flightplan.aircraft.icao_code.trim())
+            {
+                warnings.push(format!(
+                    "Profile {} doesn't match the OFP type {}",
+                    profile.name, flightplan.aircraft.icao_code
+                ));
+            }
+            warnings.extend(profile.weight_warnings(flightplan));
+        }
+        warnings.extend(profile.wind_warnings(&self.departure_airport));
+        warnings.extend(profile.wind_warnings(&self.arrival_airport));
+        warnings
+    }
+
+    fn takeoff_inputs(&self) -> anyhow::Result<PerformanceInputs> {
+        let flightplan = self
+            .flightplan
+            .as_ref()
+            .context("fetch the OFP for the takeoff weight")?;
+        let weight = flightplan
+            .weight_kg(&flightplan.weights.est_tow)
+            .context("the OFP has no takeoff weight")?;
+
+        PerformanceInputs::takeoff(&self.departure_airport, weight)
+    }
+
+    fn landing_inputs(&self) -> anyhow::Result<PerformanceInputs> {
+        let flightplan = self
+            .flightplan
+            .as_ref()
+            .context("fetch the OFP for the landing weight")?;
+        let weight = flightplan
+            .weight_kg(&flightplan.weights.est_ldw)
+            .context("the OFP has no landing weight")?;
+
+        PerformanceInputs::landing(&self.arrival_airport, weight)
+    }
+
+    // All watched stations are fetched in one request
+    fn refresh_watchlist(&self) -> Task<Event> {
+        if self.watchlist.stations().is_empty() {
+            return Task::none();
+        }
+
+        let stations = self.watchlist.stations().to_vec();
+        Task::perform(
+            async move { Weather::fetch_many(&stations, false).await },
+            |result| {
+                Event::WatchlistEvent(WatchlistEvent::Fetched(
+                    result
+                        .map(|weather| weather.reports.into_values().collect())
+                        .map_err(|e| format!("{e:#}")),
+                ))
+            },
+        )
+    }
+
+    fn perform_acars_event(&mut self, event: AcarsEvent) -> Task<Event> {
+        let client = AcarsClient::new(&self.settings.hoppie_logon, &self.pdc_request.callsign);
+        let station = self.acars_station.clone();
+
+        match event {
+            AcarsEvent::EditStation(station) => {
+                self.acars_station = station.to_uppercase();
+                Task::none()
+            }
+            AcarsEvent::EditAircraftType(aircraft_type) => {
+                self.pdc_request.aircraft_type = aircraft_type.to_uppercase();
+                Task::none()
+            }
+            AcarsEvent::EditStand(stand) => {
+                self.pdc_request.stand = stand.to_uppercase();
+                Task::none()
+            }
+            AcarsEvent::EditAtis(atis) => {
+                self.pdc_request.atis = atis.to_uppercase();
+                Task::none()
+            }
+            AcarsEvent::RequestPdc => {
+                let request = self.pdc_request.clone();
+                self.log_acars_message(&format!("{station} < {}", request.to_telex()));
+                Task::perform(
+                    async move { client.request_pdc(&station, &request).await },
+                    Self::acars_sent,
+                )
+            }
+            AcarsEvent::RequestMetar => Task::perform(
+                async move { client.request_metar(&station).await },
+                Self::acars_sent,
+            ),
+            AcarsEvent::RequestTaf => Task::perform(
+                async move { client.request_taf(&station).await },
+                Self::acars_sent,
+            ),
+            AcarsEvent::Logon => {
+                let message_id = self.cpdlc_message_id;
+                self.cpdlc_message_id += 1;
+                self.log_acars_message(&format!("{station} < REQUEST LOGON"));
+                Task::perform(
+                    async move { client.cpdlc_logon(&station, message_id).await },
+                    Self::acars_sent,
+                )
+            }
+            AcarsEvent::Poll => Task::perform(async move { client.poll().await }, |result| {
+                Event::AcarsEvent(AcarsEvent::Received(result.map_err(|e| e.to_string())))
+            }),
+            AcarsEvent::Sent(result) => {
+                match result {
+                    Ok(response) if response.is_empty() => (),
+                    Ok(response) => self.log_acars_message(&response),
+                    Err(e) => self.log_acars_message(&format!("ERROR: {e}")),
+                }
+                Task::none()
+            }
+            AcarsEvent::Received(result) => {
+                match result {
+                    Ok(messages) => {
+                        for message in messages {
+                            self.log_acars_message(&format!(
+                                "{} > {} {}",
+                                message.from, message.message_type, message.packet
+                            ));
+                        }
+                    }
+                    Err(e) => self.log_acars_message(&format!("ERROR: {e}")),
+                }
+                Task::none()
+            }
+            AcarsEvent::LogAction(action) => {
+                if !matches!(action, text_editor::Action::Edit(_)) {
+                    self.acars_log.perform(action);
+                }
+                Task::none()
+            }
+        }
+    }
+
+    fn acars_sent(result: anyhow::Result<String>) -> Event {
+        Event::AcarsEvent(AcarsEvent::Sent(result.map_err(|e| e.to_string())))
+    }
+
+    fn log_acars_message(&mut self, message: &str) {
+        let mut log = self.acars_log.text();
+        if !log.trim().is_empty() {
+            log.push('\n');
+        }
+        log.push_str(message);
+        self.acars_log = text_editor::Content::with_text(&log);
+    }
+
+    // The countdowns are shown in minutes and only exist once a flight plan is loaded
+    pub fn subscription(&self) -> Subscription<Event> {
+        if self.flightplan.is_some() {
+            time::every(Duration::from_secs(60)).map(|_| Event::Tick)
+        } else {
+            Subscription::none()
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Event> {
+        let user_id_input_field = center_x(
+            row![
+                container(
+                    text_input::TextInput::new("Simbrief", &self.settings.simbrief_id)
+                        .on_input(|input| Event::UserEvent(UserEvent::SetUserId(input)))
+                        .on_submit(Event::UserEvent(UserEvent::SaveSettings))
+                        .width(Length::Fixed(70.0)),
+                )
+                .style(container::bordered_box),
+                container(
+                    text_input::TextInput::new("Hoppie logon", &self.settings.hoppie_logon)
+                        .on_input(|input| Event::UserEvent(UserEvent::SetHoppieLogon(input)))
+                        .on_submit(Event::UserEvent(UserEvent::SaveSettings))
+                        .secure(true)
+                        .width(Length::Fixed(120.0)),
+                )
+                .style(container::bordered_box)
+            ]
+            .spacing(10),
+        );
+
+        let export_row = center_x(
+            row(BriefingFormat::ALL.into_iter().map(|format| {
+                button(text(format!("Export {}", format.label())))
+                    .on_press(Event::ExportBriefing(format))
+                    .into()
+            }))
+            .extend(PageSize::ALL.into_iter().map(|size| {
+                button(text(format!("Kneeboard {}", size.label())))
+                    .on_press(Event::ExportKneeboard(size))
+                    .into()
+            }))
+            .extend(PlanFormat::ALL.into_iter().map(|format| {
+                button(text(format!("Export {}", format.label())))
+                    .on_press(Event::ExportPlan(format))
+                    .into()
+            }))
+            .push(text(self.export_status.clone()))
+            .spacing(10),
+        );
+
+        let import_row = center_x(
+            row![
+                text_input("Plan file or FPL message", &self.plan_import)
+                    .on_input(Event::EditPlanImport)
+                    .on_submit(Event::ImportPlan)
+                    .width(Length::Fixed(400.0)),
+                button("Import Plan").on_press(Event::ImportPlan),
+                text(self.import_status.clone()),
+            ]
+            .spacing(10),
+        );
+
+        let simbrief_button = button("Fetch Simbrief").on_press(Event::FetchSimbrief);
+        let weather_button = button("Refresh Weather").on_press(Event::RefreshWeather);
+
+        let aircraft_section = Self::create_aircraft_container(
+            self.flightplan.as_ref(),
+            &self.aircraft_profiles,
+            self.selected_profile(),
+            &self.aircraft_status,
+            self.aircraft_warnings(),
+        );
+
+        let fuel_section = Self::create_fuel_container(
+            self.flightplan
+                .as_ref()
+                .and_then(FuelPlan::from_flight_plan),
+            &self.discretionary_fuel,
+        );
+
+        let schedule = self
+            .flightplan
+            .as_ref()
+            .map(|flightplan| Schedule::from_times(&flightplan.times));
+        let expected = schedule
+            .as_ref()
+            .map(|schedule| schedule.expected(schedule.actual_off_block(&self.actual_off_block)));
+        let times_section = Self::create_times_container(schedule.as_ref(), &self.actual_off_block);
+
+        let performance = self.selected_profile().map(|profile| &profile.performance);
+        let takeoff_section = Self::create_takeoff_container(performance, self.takeoff_inputs());
+
+        let departure_column = Self::create_column(
+            simbrief_button,
+            &self.departure_airport,
+            Self::create_icao_input(
+                &self.departure_airport,
+                &self.departure_suggestions,
+                Event::EditDepartureIcao,
+            ),
+            &self.departure_notes,
+            Event::EditDepartureNotes,
+            &self.departure_metar,
+            Event::DepartureMetarAction,
+        )
+        .push(takeoff_section)
+        .push(Self::create_daylight_container(
+            &self.departure_airport,
+            Milestone::Takeoff,
+            schedule.as_ref(),
+            expected.and_then(|expected| expected.takeoff),
+        ));
+
+        let landing_section = Self::create_landing_container(
+            performance,
+            self.landing_inputs(),
+            self.arrival_airport.runway_condition(),
+        );
+
+        let arrival_column = Self::create_column(
+            weather_button,
+            &self.arrival_airport,
+            Self::create_icao_input(
+                &self.arrival_airport,
+                &self.arrival_suggestions,
+                Event::EditArrivalIcao,
+            ),
+            &self.arrival_notes,
+            Event::EditArrivalNotes,
+            &self.arrival_metar,
+            Event::ArrivalMetarAction,
+        )
+        .push(landing_section)
+        .push(Self::create_daylight_container(
+            &self.arrival_airport,
+            Milestone::Landing,
+            schedule.as_ref(),
+            expected.and_then(|expected| expected.landing),
+        ));
+
+        let weather_and_notes_row = column![
+            row![departure_column, arrival_column].spacing(20),
+            text(self.weather_status.clone()).style(text::danger)
+        ];
+
+        let flight_plan_section = Self::populate_flight_plan_information();
+
+        let route_section = Self::create_route_container(
+            &self.route,
+            self.flightplan
+                .as_ref()
+                .and_then(|flightplan| flightplan.freshness),
+        );
+
+        let ofp_section = Self::create_ofp_container(
+            &self.ofp,
+            &self.ofp_search,
+            &self.ofp_matches,
+            self.flightplan
+                .as_ref()
+                .map(ofp::downloads)
+                .unwrap_or_default(),
+            &self.settings.download_directory,
+            &self.download_status,
+        );
+
+        let callsign = self
+            .flightplan
+            .as_ref()
+            .map(FlightPlan::callsign)
+            .unwrap_or_default();
+        let clearance_section = Self::create_clearance_container(&self.clearance, callsign);
+        let fpl_section = Self::create_fpl_container(&self.icao_fpl);
+
+        let alternates_section =
+            Self::create_alternates_container(&self.alternate_notams, &self.alternate_weather);
+
+        let trend_section = Self::create_trend_container(
+            self.trend.as_ref(),
+            self.trend_hours,
+            &self.trend_status,
+            [&self.departure_airport.icao, &self.arrival_airport.icao],
+        );
+
+        let watchlist_section =
+            Self::create_watchlist_container(&self.watchlist, &self.watchlist_station);
+
+        let logbook_section = Self::create_logbook_container(
+            &self.logbook_found,
+            &self.logbook_totals,
+            &self.logbook_search,
+            &self.actual_on_block,
+            &self.fuel_used,
+            &self.logbook_status,
+        );
+
+        let acars_section =
+            Self::create_acars_container(&self.acars_station, &self.pdc_request, &self.acars_log);
+
+        scrollable(
+            column![
+                user_id_input_field,
+                export_row,
+                import_row,
+                aircraft_section,
+                times_section,
+                fuel_section,
+                weather_and_notes_row,
+                flight_plan_section,
+                route_section,
+                ofp_section,
+                alternates_section,
+                trend_section,
+                watchlist_section,
+                fpl_section,
+                clearance_section,
+                acars_section,
+                logbook_section
+            ]
+            .spacing(10)
+            .padding(10),
+        )
+        .into()
+    }
+
+    // Typing an IATA code or part of the name offers the matching airports below the input
+    fn create_icao_input<'a>(
+        airport: &'a Airport,
+        suggestions: &'a [AirportRecord],
+        icao_action: impl Fn(String) -> Event + Copy + 'a,
+    ) -> Element<'a, Event> {
+        let suggestion_rows = suggestions.iter().map(|suggestion| {
+            button(text(format!(
+                "{} {} {}",
+                suggestion.icao,
+                suggestion.iata,
+                suggestion.describe()
+            )))
+            .on_press(icao_action(suggestion.icao.clone()))
+            .style(button::secondary)
+            .width(Length::Fill)
+            .into()
+        });
+
+        column![
+            container(text_input("ICAO", &airport.icao).on_input(icao_action)),
+            Column::with_children(suggestion_rows).spacing(2)
+        ]
+        .into()
+    }
+
+    fn create_column<'a>(
+        btn: Button<'a, Event>,
+        airport: &'a Airport,
+        icao_input: Element<'a, Event>,
+        editor_content: &'a text_editor::Content,
+        editor_action: impl Fn(text_editor::Action) -> Event + 'a,
+        metar_content: &'a text_editor::Content,
+        metar_action: impl Fn(text_editor::Action) -> Event + 'a,
+    ) -> Column<'a, Event> {
+        let icao_row = row![
+            styles::label_container("ICAO"),
+            styles::value_row(icao_input)
+        ];
+
+        let name = airport
+            .info
+            .as_ref()
+            .map(AirportRecord::describe)
+            .unwrap_or_default();
+        let name_row = row![
+            styles::label_container("Airport"),
+            styles::value_row(styles::bordered_text_container(name).into())
+        ];
+
+        let runway_row = row![
+            styles::label_container("Runway"),
+            styles::value_row(styles::bordered_text_container(airport.runway.clone()).into())
+        ];
+
+        let transition_row = row![
+            styles::label_container("Transition"),
+            styles::value_row(
+                row![
+                    styles::bordered_text_container(transition::format_altitude(
+                        airport.transition_altitude
+                    )),
+                    styles::label_container(" Level"),
+                    styles::bordered_text_container(transition::format_level(
+                        airport.transition_level
+                    )),
+                ]
+                .into()
+            )
+        ];
+
+        let wind_row = row![
+            styles::label_container("Wind"),
+            styles::value_row(
+                row![
+                    styles::bordered_text_container(airport.weather.wind_direction().to_string()),
+                    styles::label_container("  °"),
+                    styles::bordered_text_container(airport.weather.wind_speed().to_string()),
                     styles::label_container("  kts")
                 ]
                 .into()
             )
         ];
 
+        let runway_wind = RunwayWind::from_weather(&airport.runway, &airport.weather)
+            .map(|runway_wind| runway_wind.describe())
+            .unwrap_or_default();
+        let runway_wind_row = row![
+            styles::label_container("Runway Wind"),
+            styles::value_row(styles::bordered_text_container(runway_wind).into())
+        ];
+
         let temperature_row = row![
             styles::label_container("Temperature"),
             styles::value_row(
@@ -313,6 +1611,46 @@ impl App {
             )
         ];
 
+        let updated_row = row![
+            styles::label_container("Updated"),
+            styles::value_row(
+                styles::bordered_text_container(describe_freshness(airport.weather.freshness))
+                    .into()
+            )
+        ];
+
+        let frequency_rows = airport.frequencies.iter().map(|frequency| {
+            let style = if frequency.online {
+                button::success
+            } else {
+                button::secondary
+            };
+
+            row![
+                styles::label_container(frequency.kind.to_string()),
+                styles::value_row(
+                    row![
+                        container(text(frequency.name.clone())).width(Length::FillPortion(2)),
+                        button(text(frequency.frequency.clone()))
+                            .on_press(Event::CopyFrequency(frequency.frequency.clone()))
+                            .style(style)
+                    ]
+                    .into()
+                )
+            ]
+            .into()
+        });
+
+        let frequency_column = column![
+            container(text("Frequencies")).padding(5),
+            Column::with_children(frequency_rows).spacing(2)
+        ];
+
+        let notam_column = column![
+            container(text("NOTAMs")).padding(5),
+            scrollable(Self::create_notam_list(&airport.notams, &airport.runway)).height(150)
+        ];
+
         let metar_column = column![
             container(text("Metar")).padding(5),
             container(
@@ -338,10 +1676,17 @@ impl App {
         let information_container = container(
             column![
                 icao_row,
+                name_row,
+                runway_row,
+                transition_row,
                 wind_row,
+                runway_wind_row,
                 temperature_row,
                 qnh_row,
                 visibility_row,
+                updated_row,
+                frequency_column,
+                notam_column,
                 metar_column,
                 atc_notes
             ]
@@ -355,21 +1700,614 @@ impl App {
             .spacing(10)
     }
 
+    fn create_aircraft_container<'a>(
+        flightplan: Option<&'a FlightPlan>,
+        profiles: &'a [AircraftProfile],
+        selected: Option<&'a AircraftProfile>,
+        status: &'a str,
+        warnings: Vec<String>,
+    ) -> Container<'a, Event> {
+        let planned = flightplan
+            .map(|flightplan| {
+                let aircraft = &flightplan.aircraft;
+                format!(
+                    "{} {} ({}) {}",
+                    aircraft.icao_code, aircraft.registration, aircraft.name, aircraft.equipment
+                )
+            })
+            .unwrap_or_default();
+
+        let profile_buttons = profiles.iter().map(|profile| {
+            let style = if selected.is_some_and(|selected| selected.name == profile.name) {
+                button::primary
+            } else {
+                button::secondary
+            };
+            button(text(format!("{} {}", profile.icao_type, profile.name)))
+                .on_press(Event::AircraftEvent(AircraftEvent::SelectProfile(
+                    profile.name.clone(),
+                )))
+                .style(style)
+                .into()
+        });
+        let message = if !status.is_empty() {
+            status.to_string()
+        } else if profiles.is_empty() {
+            format!(
+                "Add an aircraft profile to {}",
+                aircraft::PROFILES_DIRECTORY
+            )
+        } else if let (Some(flightplan), None) = (flightplan, selected) {
+            format!(
+                "No aircraft profile for the {}",
+                flightplan.aircraft.icao_code
+            )
+        } else {
+            String::new()
+        };
+
+        let warning_rows = warnings
+            .into_iter()
+            .map(|warning| text(warning).style(text::danger).into());
+
+        container(
+            column![
+                row![
+                    styles::label_container("Aircraft"),
+                    styles::value_row(styles::bordered_text_container(planned).into())
+                ],
+                row![styles::label_container("Profile")]
+                    .extend(profile_buttons)
+                    .push(text(message).style(text::danger))
+                    .spacing(5),
+                Column::with_children(warning_rows).spacing(2)
+            ]
+            .spacing(5),
+        )
+        .padding(10)
+        .style(container::bordered_box)
+    }
+
+    fn create_times_container<'a>(
+        schedule: Option<&Schedule>,
+        actual_off_block: &'a str,
+    ) -> Container<'a, Event> {
+        let mut content = column![container(text("Times")).padding(5)].spacing(5);
+        let Some(schedule) = schedule else {
+            return container(
+                content.push(text("Fetch the OFP for the flight times").style(text::danger)),
+            )
+            .padding(10)
+            .style(container::bordered_box);
+        };
+
+        let expected = schedule.expected(schedule.actual_off_block(actual_off_block));
+
+        let header_row = row![
+            styles::label_container(""),
+            styles::label_container("Scheduled"),
+            styles::label_container("Estimated"),
+            styles::label_container("Expected"),
+        ];
+        let milestone_rows = Milestone::ALL.into_iter().map(|milestone| {
+            row![
+                styles::label_container(milestone.label()),
+                styles::bordered_text_container(
+                    schedule.format(milestone, schedule.scheduled.get(milestone))
+                ),
+                styles::bordered_text_container(
+                    schedule.format(milestone, schedule.estimated.get(milestone))
+                ),
+                styles::bordered_text_container(
+                    schedule.format(milestone, expected.get(milestone))
+                ),
+            ]
+            .into()
+        });
+        let duration_row = |label: &'static str, duration: fn(&BlockTimes) -> Option<TimeDelta>| {
+            let format = |times: &BlockTimes| {
+                duration(times)
+                    .map(utils::format_duration)
+                    .unwrap_or_default()
+            };
+            row![
+                styles::label_container(label),
+                styles::bordered_text_container(format(&schedule.scheduled)),
+                styles::bordered_text_container(format(&schedule.estimated)),
+                styles::bordered_text_container(format(&expected)),
+            ]
+        };
+
+        let now = Utc::now();
+        let countdowns = [
+            ("Off block", expected.off_block),
+            ("Takeoff", expected.takeoff),
+        ]
+        .into_iter()
+        .filter_map(|(label, time)| Some(schedule::describe_countdown(label, time? - now)))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+        let actual_row = row![
+            styles::label_container("Actual Off Block"),
+            styles::value_row(
+                row![
+                    text_input("HHMM", actual_off_block)
+                        .on_input(Event::EditActualOffBlock)
+                        .width(Length::Fixed(80.0)),
+                    button("Now").on_press(Event::SetActualOffBlockNow),
+                    container(text(countdowns)).padding(5),
+                ]
+                .spacing(5)
+                .into()
+            )
+        ];
+
+        content = content
+            .push(header_row)
+            .push(Column::with_children(milestone_rows).spacing(5))
+            .push(duration_row("Block Time", BlockTimes::block_time))
+            .push(duration_row("Air Time", BlockTimes::air_time))
+            .push(actual_row);
+
+        container(content)
+            .padding(10)
+            .style(container::bordered_box)
+    }
+
+    // Without a planned time the sun times of today are shown
+    fn create_daylight_container<'a>(
+        airport: &Airport,
+        milestone: Milestone,
+        schedule: Option<&Schedule>,
+        time: Option<DateTime<Utc>>,
+    ) -> Container<'a, Event> {
+        let content = column![container(text("Daylight")).padding(5)].spacing(5);
+        let Some((latitude, longitude)) = airport.coordinates() else {
+            return container(
+                content.push(text("The position of the airport is unknown").style(text::danger)),
+            )
+            .padding(10)
+            .style(container::bordered_box);
+        };
+
+        let sun = SunTimes::around(time.unwrap_or_else(Utc::now), latitude, longitude);
+        let missing = if sun.midnight_sun {
+            "sun does not set"
+        } else {
+            "sun does not rise"
+        };
+        let format = |time: Option<DateTime<Utc>>| match (schedule, time) {
+            (Some(schedule), Some(_)) => schedule.format(milestone, time),
+            (None, Some(time)) => time.format("%H%MZ").to_string(),
+            (_, None) => missing.to_string(),
+        };
+        // Close to the poles the twilight can last all night while the sun still rises and sets
+        let format_twilight = |time: Option<DateTime<Utc>>| match time {
+            None if sun.sunrise.is_some() => "-".to_string(),
+            time => format(time),
+        };
+        let mut values = vec![
+            ("Civil Dawn", format_twilight(sun.civil_dawn)),
+            ("Sunrise", format(sun.sunrise)),
+            ("Sunset", format(sun.sunset)),
+            ("Civil Dusk", format_twilight(sun.civil_dusk)),
+        ];
+        if let Some(time) = time {
+            let daylight = Daylight::at(time, latitude, longitude);
+            values.push((
+                milestone.label(),
+                format!("{} {daylight}", format(Some(time))),
+            ));
+        }
+
+        container(content.push(Self::create_value_rows(values)))
+            .padding(10)
+            .style(container::bordered_box)
+    }
+
+    fn create_fuel_container<'a>(
+        plan: Option<FuelPlan>,
+        discretionary: &'a str,
+    ) -> Container<'a, Event> {
+        let discretionary_row = row![
+            styles::label_container("Discretionary"),
+            styles::value_row(
+                row![
+                    text_input("0", discretionary)
+                        .on_input(Event::EditDiscretionaryFuel)
+                        .width(Length::Fixed(100.0)),
+                    styles::label_container("  kg")
+                ]
+                .into()
+            )
+        ];
+
+        let mut content = column![container(text("Fuel")).padding(5)].spacing(5);
+        let Some(plan) = plan else {
+            return container(
+                content.push(text("Fetch the OFP to plan the fuel").style(text::danger)),
+            )
+            .padding(10)
+            .style(container::bordered_box);
+        };
+
+        // An invalid entry counts as no extra fuel
+        let extra: f32 = discretionary.trim().parse().unwrap_or_default();
+        let kilograms = |value: f32| format!("{value:.0} kg");
+        let values = vec![
+            ("Taxi", kilograms(plan.taxi)),
+            ("Trip", kilograms(plan.trip)),
+            ("Contingency", kilograms(plan.contingency)),
+            ("Alternate", kilograms(plan.alternate)),
+            ("Final Reserve", kilograms(plan.reserve)),
+            ("ETOPS", kilograms(plan.etops)),
+            ("Extra", kilograms(plan.extra)),
+        ];
+        let mut results = vec![
+            (
+                "Block",
+                format!(
+                    "{} (OFP {})",
+                    kilograms(plan.block(extra)),
+                    kilograms(plan.planned_block)
+                ),
+            ),
+            (
+                "Max Tanks",
+                plan.max_tanks.map(kilograms).unwrap_or_default(),
+            ),
+            ("Extra Burn", kilograms(plan.extra_burn(extra))),
+            ("Landing Fuel", kilograms(plan.landing(extra))),
+            (
+                "Endurance",
+                plan.endurance(extra)
+                    .map(utils::format_duration)
+                    .unwrap_or_default(),
+            ),
+        ];
+        results.retain(|(_, value)| !value.is_empty());
+
+        let warning_rows = plan
+            .warnings(extra)
+            .into_iter()
+            .map(|warning| text(warning).style(text::danger).into());
+
+        content = content
+            .push(Self::create_value_rows(values))
+            .push(discretionary_row)
+            .push(Self::create_value_rows(results))
+            .push(Column::with_children(warning_rows).spacing(2));
+
+        container(content)
+            .padding(10)
+            .style(container::bordered_box)
+    }
+
+    fn create_takeoff_container<'a>(
+        profile: Option<&'a PerformanceProfile>,
+        inputs: anyhow::Result<PerformanceInputs>,
+    ) -> Container<'a, Event> {
+        let mut content = column![container(text("Takeoff Performance")).padding(5)].spacing(5);
+
+        match (profile, inputs) {
+            (None, _) => {
+                content = content.push(text("Select an aircraft profile").style(text::danger));
+            }
+            (Some(_), Err(e)) => {
+                content = content.push(text(format!("{e:#}")).style(text::danger));
+            }
+            (Some(profile), Ok(inputs)) => {
+                content = content.push(Self::create_value_rows(inputs.describe()));
+                content = match profile.takeoff(&inputs) {
+                    Ok(result) => content.push(Self::create_value_rows(result.describe())),
+                    Err(e) => content.push(text(format!("{e:#}")).style(text::danger)),
+                };
+            }
+        }
+
+        container(content.padding(10)).style(container::bordered_box)
+    }
+
+    fn create_landing_container<'a>(
+        profile: Option<&'a PerformanceProfile>,
+        inputs: anyhow::Result<PerformanceInputs>,
+        condition: RunwayCondition,
+    ) -> Container<'a, Event> {
+        let mut content = column![container(text("Landing Performance")).padding(5)].spacing(5);
+
+        match (profile, inputs) {
+            (None, _) => {
+                content = content.push(text("Select an aircraft profile").style(text::danger));
+            }
+            (Some(_), Err(e)) => {
+                content = content.push(text(format!("{e:#}")).style(text::danger));
+            }
+            (Some(profile), Ok(inputs)) => {
+                let mut values = inputs.describe();
+                values.push(("Condition", condition.to_string()));
+                content = content.push(Self::create_value_rows(values));
+                match profile.landing(&inputs, condition) {
+                    Ok(results) => {
+                        for result in results {
+                            content = content.push(Self::create_value_rows(
+                                result.describe(inputs.runway_length),
+                            ));
+                        }
+                    }
+                    Err(e) => content = content.push(text(format!("{e:#}")).style(text::danger)),
+                }
+            }
+        }
+
+        container(content.padding(10)).style(container::bordered_box)
+    }
+
+    fn create_value_rows<'a>(values: Vec<(impl Into<String>, String)>) -> Column<'a, Event> {
+        Column::with_children(values.into_iter().map(|(label, value)| {
+            row![
+                styles::label_container(label),
+                styles::value_row(styles::bordered_text_container(value).into())
+            ]
+            .into()
+        }))
+        .spacing(5)
+    }
+
+    fn create_notam_list<'a>(notams: &'a [Notam], runway: &'a str) -> Column<'a, Event> {
+        Column::with_children(notams.iter().map(|notam| {
+            let mut notam_column = column![
+                text(format!("{} {}", notam.category(), notam.notam_id)),
+                text(notam.notam_text.clone()).wrapping(text::Wrapping::WordOrGlyph),
+            ];
+
+            if notam.affects_runway(runway) {
+                notam_column = notam_column
+                    .push(text(format!("Affects planned runway {runway}")).style(text::danger));
+            }
+
+            notam_column.into()
+        }))
+        .spacing(5)
+    }
+
+    fn create_alternates_container<'a>(
+        alternates: &'a [(String, String, Vec<Notam>)],
+        weather: &'a HashMap<String, Weather>,
+    ) -> Container<'a, Event> {
+        let alternate_columns = alternates.iter().map(|(icao, runway, notams)| {
+            let metar = weather
+                .get(icao)
+                .map(|weather| weather.metar.clone())
+                .unwrap_or_default();
+
+            column![
+                container(text(icao.clone())).padding(5),
+                styles::bordered_text_container(metar),
+                scrollable(Self::create_notam_list(notams, runway)).height(150)
+            ]
+            .width(Length::FillPortion(1))
+            .into()
+        });
+
+        container(column![
+            container(text("Alternates")).padding(5),
+            row(alternate_columns).spacing(20)
+        ])
+        .padding(10)
+        .style(container::bordered_box)
+    }
+
+    fn create_trend_container<'a>(
+        trend: Option<&'a Trend>,
+        hours: u32,
+        status: &'a str,
+        airports: [&String; 2],
+    ) -> Container<'a, Event> {
+        let airport_buttons = airports
+            .into_iter()
+            .filter(|icao| !icao.is_empty())
+            .map(|icao| {
+                button(text(icao.clone()))
+                    .on_press(Event::TrendEvent(TrendEvent::Fetch(icao.clone())))
+                    .into()
+            });
+        let hour_buttons = trend::HISTORY_HOURS.into_iter().map(|option| {
+            let style = if option == hours {
+                button::primary
+            } else {
+                button::secondary
+            };
+            button(text(format!("{option} h")))
+                .on_press(Event::TrendEvent(TrendEvent::SetHours(option)))
+                .style(style)
+                .into()
+        });
+
+        let controls = row![container(text("Trend")).padding(5)]
+            .extend(airport_buttons)
+            .extend(hour_buttons)
+            .push(container(text(status)).padding(5))
+            .spacing(10);
+
+        let mut content = column![controls].spacing(5);
+        if let Some(trend) = trend {
+            content = content.push(canvas(TrendChart { trend }).width(Length::Fill).height(240));
+        }
+
+        container(content)
+            .padding(10)
+            .style(container::bordered_box)
+    }
+
+    fn create_watchlist_container<'a>(
+        watchlist: &'a Watchlist,
+        station: &'a str,
+    ) -> Container<'a, Event> {
+        let input_row = row![
+            container(text("Watchlist")).padding(5),
+            text_input("ICAO", station)
+                .on_input(|station| Event::WatchlistEvent(WatchlistEvent::EditStation(station)))
+                .on_submit(Event::WatchlistEvent(WatchlistEvent::Add))
+                .width(Length::Fixed(70.0)),
+            button("Add").on_press(Event::WatchlistEvent(WatchlistEvent::Add)),
+            button("Refresh").on_press(Event::WatchlistEvent(WatchlistEvent::Refresh)),
+        ]
+        .spacing(10);
+
+        // Clicking a column header sorts by it, clicking it again reverses the order
+        let header_row = row(SortColumn::ALL.into_iter().map(|column| {
+            let arrow = match (watchlist.sort_column() == column, watchlist.is_descending()) {
+                (true, false) => " ▲",
+                (true, true) => " ▼",
+                (false, _) => "",
+            };
+            button(text(format!("{}{arrow}", column.label())))
+                .on_press(Event::WatchlistEvent(WatchlistEvent::Sort(column)))
+                .style(button::secondary)
+                .width(Length::FillPortion(1))
+                .into()
+        }))
+        .push(container(text("")).width(Length::FillPortion(1)))
+        .spacing(5);
+
+        let rows = watchlist.rows().into_iter().map(|watchlist_row| {
+            let (category, wind, qnh) = match watchlist_row.weather {
+                Some(weather) => (
+                    weather.flight_category().to_string(),
+                    watchlist::format_wind(weather),
+                    format!("{:.0}", weather.altimeter()),
+                ),
+                None => ("-".to_string(), "-".to_string(), "-".to_string()),
+            };
+            let category_style = match category.as_str() {
+                "VFR" => text::success,
+                "MVFR" => text::primary,
+                "IFR" => text::warning,
+                "LIFR" => text::danger,
+                _ => text::default,
+            };
+
+            row![
+                container(text(watchlist_row.station)).width(Length::FillPortion(1)),
+                container(text(category).style(category_style)).width(Length::FillPortion(1)),
+                container(text(wind)).width(Length::FillPortion(1)),
+                container(text(qnh)).width(Length::FillPortion(1)),
+                container(button("Remove").style(button::secondary).on_press(
+                    Event::WatchlistEvent(WatchlistEvent::Remove(
+                        watchlist_row.station.to_string()
+                    ))
+                ))
+                .width(Length::FillPortion(1)),
+            ]
+            .spacing(5)
+            .into()
+        });
+
+        container(
+            column![
+                input_row,
+                header_row,
+                Column::with_children(rows).spacing(2)
+            ]
+            .spacing(5),
+        )
+        .padding(10)
+        .style(container::bordered_box)
+    }
+
+    fn create_logbook_container<'a>(
+        found: &'a [LogbookEntry],
+        totals: &Totals,
+        search: &'a str,
+        on_block: &'a str,
+        fuel_used: &'a str,
+        status: &'a str,
+    ) -> Container<'a, Event> {
+        let log_row = row![
+            container(text("Logbook")).padding(5),
+            text_input("On block HHMM", on_block)
+                .on_input(|time| Event::LogbookEvent(LogbookEvent::EditOnBlock(time)))
+                .width(Length::Fixed(120.0)),
+            text_input("Fuel used kg", fuel_used)
+                .on_input(|fuel| Event::LogbookEvent(LogbookEvent::EditFuelUsed(fuel)))
+                .width(Length::Fixed(120.0)),
+            button("Log Flight").on_press(Event::LogbookEvent(LogbookEvent::Log)),
+            text_input("Search", search)
+                .on_input(|query| Event::LogbookEvent(LogbookEvent::Search(query)))
+                .width(Length::Fixed(200.0)),
+            button("Export CSV").on_press(Event::LogbookEvent(LogbookEvent::ExportCsv)),
+            container(text(status)).padding(5),
+        ]
+        .spacing(10);
+
+        let totals_row = text(format!(
+            "{} flights, {} block, {} air, {} night, {} day and {} night landings, {:.0} kg fuel",
+            totals.flights,
+            utils::format_duration(totals.block_time),
+            utils::format_duration(totals.air_time),
+            utils::format_duration(totals.night_time),
+            totals.day_landings,
+            totals.night_landings,
+            totals.fuel_used,
+        ));
+
+        let header_row = row([
+            "Date",
+            "Callsign",
+            "Aircraft",
+            "From - To",
+            "Block",
+            "Night",
+            "Landings",
+        ]
+        .into_iter()
+        .map(|label| container(text(label)).width(Length::FillPortion(1)).into()))
+        .spacing(5);
+        let rows = found.iter().map(|entry| {
+            let cells = [
+                entry.date.to_string(),
+                entry.callsign.clone(),
+                format!("{} {}", entry.aircraft_type, entry.registration),
+                format!("{} - {}", entry.origin, entry.destination),
+                utils::format_duration(entry.block_time()),
+                utils::format_duration(entry.night_time()),
+                format!("{} day, {} night", entry.day_landings, entry.night_landings),
+            ];
+            row(cells
+                .into_iter()
+                .map(|cell| container(text(cell)).width(Length::FillPortion(1)).into()))
+            .spacing(5)
+            .into()
+        });
+
+        container(
+            column![
+                log_row,
+                totals_row,
+                header_row,
+                scrollable(Column::with_children(rows).spacing(2)).height(200)
+            ]
+            .spacing(5),
+        )
+        .padding(10)
+        .style(container::bordered_box)
+    }
+
     fn populate_flight_plan_information<'a>() -> Element<'a, Event> {
         container(column![]).into()
     }
 
-    async fn load_user_id(path: &str) -> Option<String> {
+    async fn load_settings(path: &str) -> Option<Settings> {
         let mut file = OpenOptions::new().read(true).open(path).await.ok()?;
         let mut contents = String::new();
         file.read_to_string(&mut contents).await.ok()?;
-        let user: Option<User> = serde_json::from_str(&contents).ok();
 
-        user.map(|User(user_id)| user_id)
+        Settings::from_json(&contents)
     }
 
     // TODO: Modify return type with a more descriptive result
-    async fn save_user_id(path: &str, user_id: String) -> Result<(), String> {
+    async fn save_settings(path: &str, settings: Settings) -> Result<(), String> {
         let Ok(mut file) = OpenOptions::new()
             .read(true)
             .write(true)
@@ -381,23 +2319,29 @@ impl App {
             return Err("failed to open file when attempting to save".to_string());
         };
 
-        let user = User(user_id);
-
-        match serde_json::to_string_pretty(&user) {
+        match serde_json::to_string_pretty(&settings) {
             Ok(json) => {
                 if let Err(e) = file.write_all(json.as_bytes()).await {
                     return Err(e.to_string());
                 }
             }
-            Err(_) => return Err("failed to serialize user".to_string()),
+            Err(_) => return Err("failed to serialize settings".to_string()),
         }
 
         Ok(())
     }
 
-    fn create_route_container(route_content: &text_editor::Content) -> Container<'_, Event> {
+    fn create_route_container(
+        route_content: &text_editor::Content,
+        freshness: Option<Freshness>,
+    ) -> Container<'_, Event> {
+        let title = match freshness {
+            Some(_) => format!("Route (OFP fetched {})", describe_freshness(freshness)),
+            None => "Route".to_string(),
+        };
+
         container(column![
-            container(text("Route")).padding(5),
+            container(text(title)).padding(5),
             container(
                 text_editor(route_content)
                     .height(125)
@@ -409,16 +2353,261 @@ impl App {
         .style(container::bordered_box)
     }
 
+    fn create_ofp_container<'a>(
+        ofp_content: &'a text_editor::Content,
+        search: &'a str,
+        matches: &'a [(usize, String)],
+        downloads: Vec<Download>,
+        directory: &'a str,
+        status: &'a str,
+    ) -> Container<'a, Event> {
+        let search_row = row![
+            container(text("OFP")).padding(5),
+            text_input("Search", search)
+                .on_input(|query| Event::OfpEvent(OfpEvent::Search(query)))
+                .width(Length::Fixed(200.0)),
+        ]
+        .spacing(10);
+
+        // Clicking a match selects its line in the OFP
+        let match_rows = matches.iter().map(|(line, contents)| {
+            button(text(format!("{:>4}  {contents}", line + 1)).font(Font::MONOSPACE))
+                .on_press(Event::OfpEvent(OfpEvent::GoToLine(*line)))
+                .style(button::secondary)
+                .into()
+        });
+        let match_count = match (search.trim().is_empty(), matches.len()) {
+            (true, _) => String::new(),
+            (false, 1) => "1 match".to_string(),
+            (false, count) => format!("{count} matches"),
+        };
+
+        let download_row = row![
+            text_input("Download directory", directory)
+                .on_input(|directory| Event::OfpEvent(OfpEvent::SetDirectory(directory)))
+                .on_submit(Event::UserEvent(UserEvent::SaveSettings))
+                .width(Length::Fixed(250.0)),
+        ]
+        .extend(downloads.into_iter().map(|download| {
+            button(text(download.name.clone()))
+                .on_press(Event::OfpEvent(OfpEvent::Download(download)))
+                .into()
+        }))
+        .push(container(text(status)).padding(5))
+        .spacing(10)
+        .wrap();
+
+        container(
+            column![
+                search_row,
+                container(
+                    text_editor(ofp_content)
+                        .height(400)
+                        .font(Font::MONOSPACE)
+                        .on_action(|action| Event::OfpEvent(OfpEvent::Action(action)))
+                        .wrapping(text::Wrapping::None),
+                ),
+                text(match_count),
+                scrollable(Column::with_children(match_rows).spacing(2)).height(100),
+                download_row,
+            ]
+            .spacing(5),
+        )
+        .padding(10)
+        .style(container::bordered_box)
+    }
+
+    fn create_clearance_container<'a>(
+        clearance: &'a Clearance,
+        callsign: String,
+    ) -> Container<'a, Event> {
+        let field_rows = ClearanceField::ALL.into_iter().map(|field| {
+            row![
+                styles::label_container(field.to_string()),
+                styles::value_row(
+                    text_input(&field.to_string(), clearance.get(field))
+                        .on_input(move |value| {
+                            Event::ClearanceEvent(ClearanceEvent::Edit(field, value))
+                        })
+                        .into()
+                )
+            ]
+            .into()
+        });
+
+        let emergency_label = if clearance.emergency_squawk_intended {
+            "Emergency squawk intended: Yes"
+        } else {
+            "Emergency squawk intended: No"
+        };
+        let emergency_button = button(emergency_label)
+            .on_press(Event::ClearanceEvent(ClearanceEvent::ToggleEmergencySquawk));
+
+        let errors = Column::with_children(
+            clearance
+                .validate()
+                .into_iter()
+                .map(|error| text(error.to_string()).into()),
+        );
+
+        container(
+            column![
+                container(text("Clearance")).padding(5),
+                Column::with_children(field_rows).spacing(5),
+                emergency_button,
+                errors,
+                container(text("Read back")).padding(5),
+                styles::bordered_text_container(clearance.readback(&callsign)).width(Length::Fill),
+            ]
+            .spacing(5),
+        )
+        .padding(10)
+        .style(container::bordered_box)
+    }
+
+    fn create_fpl_container(plan: &IcaoFlightPlan) -> Container<'_, Event> {
+        let item_rows = FplItem::ALL.into_iter().map(|item| {
+            row![
+                styles::label_container(format!("{} {item}", item.number())),
+                styles::value_row(
+                    text_input(&item.to_string(), plan.get(item))
+                        .on_input(move |value| Event::FplEvent(FplEvent::Edit(item, value)))
+                        .into()
+                )
+            ]
+            .into()
+        });
+
+        let errors = Column::with_children(
+            plan.validate()
+                .into_iter()
+                .map(|error| text(error.to_string()).style(text::danger).into()),
+        );
+
+        container(
+            column![
+                container(text("ICAO Flight Plan")).padding(5),
+                Column::with_children(item_rows).spacing(5),
+                errors,
+                styles::bordered_text_container(plan.message()).width(Length::Fill),
+                row![
+                    button("Copy Message").on_press(Event::FplEvent(FplEvent::Copy)),
+                    button("Copy Item 19").on_press(Event::FplEvent(FplEvent::CopySupplementary)),
+                ]
+                .spacing(10),
+            ]
+            .spacing(5),
+        )
+        .padding(10)
+        .style(container::bordered_box)
+    }
+
+    fn create_acars_container<'a>(
+        station: &'a str,
+        pdc_request: &'a PdcRequest,
+        log: &'a text_editor::Content,
+    ) -> Container<'a, Event> {
+        let station_row = row![
+            styles::label_container("Station"),
+            styles::value_row(
+                text_input("Station", station)
+                    .on_input(|input| Event::AcarsEvent(AcarsEvent::EditStation(input)))
+                    .into()
+            )
+        ];
+
+        let pdc_row = row![
+            styles::label_container("PDC"),
+            styles::value_row(
+                row![
+                    text_input("Type", &pdc_request.aircraft_type)
+                        .on_input(|input| Event::AcarsEvent(AcarsEvent::EditAircraftType(input))),
+                    text_input("Stand", &pdc_request.stand)
+                        .on_input(|input| Event::AcarsEvent(AcarsEvent::EditStand(input))),
+                    text_input("ATIS", &pdc_request.atis)
+                        .on_input(|input| Event::AcarsEvent(AcarsEvent::EditAtis(input))),
+                ]
+                .spacing(5)
+                .into()
+            )
+        ];
+
+        let buttons = row![
+            button("Request PDC").on_press(Event::AcarsEvent(AcarsEvent::RequestPdc)),
+            button("METAR").on_press(Event::AcarsEvent(AcarsEvent::RequestMetar)),
+            button("TAF").on_press(Event::AcarsEvent(AcarsEvent::RequestTaf)),
+            button("CPDLC Logon").on_press(Event::AcarsEvent(AcarsEvent::Logon)),
+            button("Poll").on_press(Event::AcarsEvent(AcarsEvent::Poll)),
+        ]
+        .spacing(5);
+
+        container(
+            column![
+                container(text("ACARS")).padding(5),
+                station_row,
+                pdc_row,
+                buttons,
+                container(
+                    text_editor(log)
+                        .height(125)
+                        .on_action(|action| Event::AcarsEvent(AcarsEvent::LogAction(action)))
+                        .wrapping(text::Wrapping::WordOrGlyph),
+                )
+                .style(container::bordered_box)
+            ]
+            .spacing(5),
+        )
+        .padding(10)
+        .style(container::bordered_box)
+    }
+
     async fn refresh_simbrief_flightplan(user_id: String) -> Box<Option<FlightPlan>> {
         Box::new(FlightPlan::fetch(&user_id).await.ok())
     }
 
-    async fn refresh_airport_weather(icao: String) -> Option<Weather> {
-        let weather = Weather::fetch(&icao, true).await.ok();
-        if weather.is_none() {
-            println!("An error occurred when deserializing the weather response");
+    // The VATSIM feed is fetched once for all airports
+    async fn refresh_frequencies(icaos: Vec<String>) -> Vec<(String, Vec<Frequency>)> {
+        let vatsim = VatsimData::fetch().await;
+        if let Err(e) = &vatsim {
+            eprintln!("Unable to fetch the VATSIM controllers: {e}");
+        }
+        let vatsim = vatsim.ok();
+
+        let mut frequencies = Vec::new();
+        for icao in icaos {
+            let loaded = frequency::load_frequencies(&icao, vatsim.as_ref()).await;
+            frequencies.push((icao, loaded));
         }
 
-        weather
+        frequencies
+    }
+
+    async fn refresh_runways(icao: String) -> (String, Vec<RunwayRecord>) {
+        let runways = database::load_runways(&icao).await.unwrap_or_else(|e| {
+            eprintln!("{e:#}");
+            Vec::new()
+        });
+
+        (icao, runways)
     }
+
+    // Departure, arrival and the alternates of the flight plan
+    fn weather_stations(&self) -> Vec<String> {
+        [&self.departure_airport.icao, &self.arrival_airport.icao]
+            .into_iter()
+            .chain(self.alternate_notams.iter().map(|(icao, _, _)| icao))
+            .cloned()
+            .collect()
+    }
+
+    async fn refresh_airport_weather(stations: Vec<String>) -> anyhow::Result<StationWeather> {
+        Weather::fetch_many(&stations, true).await
+    }
+}
+
+// The age is worked out when the view is drawn, so it is only as current as the last redraw
+fn describe_freshness(freshness: Option<Freshness>) -> String {
+    freshness
+        .map(|freshness| freshness.describe(Utc::now()))
+        .unwrap_or_default()
 }
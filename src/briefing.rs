@@ -0,0 +1,431 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+
+use crate::{airport::Airport, flightplan::FlightPlan, taf, transition, utils, wind::RunwayWind};
+
+// Fits a kneeboard page printed in a monospaced font
+const KNEEBOARD_WIDTH: usize = 48;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BriefingFormat {
+    Markdown,
+    Html,
+    PlainText,
+}
+
+impl BriefingFormat {
+    pub const ALL: [BriefingFormat; 3] = [
+        BriefingFormat::Markdown,
+        BriefingFormat::Html,
+        BriefingFormat::PlainText,
+    ];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            BriefingFormat::Markdown => "md",
+            BriefingFormat::Html => "html",
+            BriefingFormat::PlainText => "txt",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BriefingFormat::Markdown => "Markdown",
+            BriefingFormat::Html => "HTML",
+            BriefingFormat::PlainText => "Text",
+        }
+    }
+}
+
+// A format independent view of the briefing, every output format renders these sections
+#[derive(Debug, Clone, Default)]
+pub struct Section {
+    pub title: String,
+    pub fields: Vec<(String, String)>,
+    pub blocks: Vec<(String, String)>,
+}
+
+impl Section {
+    fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            ..Default::default()
+        }
+    }
+
+    fn field(mut self, label: &str, value: impl Into<String>) -> Self {
+        let value = value.into();
+        if !value.trim().is_empty() && value != "No Value" {
+            self.fields.push((label.to_string(), value));
+        }
+        self
+    }
+
+    fn block(mut self, title: &str, text: impl Into<String>) -> Self {
+        let text = text.into();
+        if !text.trim().is_empty() {
+            self.blocks
+                .push((title.to_string(), text.trim().to_string()));
+        }
+        self
+    }
+}
+
+pub struct AirportBriefing<'a> {
+    pub role: &'a str,
+    pub airport: &'a Airport,
+    pub notes: String,
+}
+
+pub struct Briefing<'a> {
+    pub flightplan: Option<&'a FlightPlan>,
+    pub departure: AirportBriefing<'a>,
+    pub arrival: AirportBriefing<'a>,
+}
+
+impl Briefing<'_> {
+    pub fn title(&self) -> String {
+        match self.flightplan {
+            Some(flightplan) => format!(
+                "{} {} - {}",
+                flightplan.callsign(),
+                self.departure.airport.icao,
+                self.arrival.airport.icao
+            ),
+            None => format!(
+                "{} - {}",
+                self.departure.airport.icao, self.arrival.airport.icao
+            ),
+        }
+    }
+
+    pub fn sections(&self) -> Vec<Section> {
+        let mut sections = Vec::new();
+
+        if let Some(flightplan) = self.flightplan {
+            let information = &flightplan.flight_information;
+            let fuel = &flightplan.fuel;
+            let weights = &flightplan.weights;
+
+            sections.push(
+                Section::new("Flight")
+                    .field("Callsign", flightplan.callsign())
+                    .field("Cost index", information.cost_index.clone())
+                    .field("Initial altitude", information.initial_altitude.clone())
+                    .field("Route distance", information.route_distance.clone())
+                    .field("Block fuel", fuel.plan_ramp.clone())
+                    .field("Takeoff fuel", fuel.plan_takeoff.clone())
+                    .field("Landing fuel", fuel.plan_landing.clone())
+                    .field("Payload", weights.payload.clone())
+                    .field("Passengers", weights.pax_count.clone())
+                    .field("ZFW", format_weight(&weights.est_zfw, &weights.max_zfw))
+                    .field("TOW", format_weight(&weights.est_tow, &weights.max_tow))
+                    .field("LDW", format_weight(&weights.est_ldw, &weights.max_ldw))
+                    .block("Route", information.route_ifps.clone()),
+            );
+        }
+
+        sections.push(Self::airport_section(&self.departure));
+        sections.push(Self::airport_section(&self.arrival));
+
+        sections
+    }
+
+    fn airport_section(briefing: &AirportBriefing) -> Section {
+        let airport = briefing.airport;
+        let weather = &airport.weather;
+        let runway_wind = RunwayWind::from_weather(&airport.runway, weather)
+            .map(|runway_wind| runway_wind.describe())
+            .unwrap_or_default();
+        let decoded_taf = weather
+            .taf
+            .as_deref()
+            .and_then(taf::decode_taf)
+            .map(|taf| taf.to_string())
+            .unwrap_or_default();
+        let frequencies = airport
+            .frequencies
+            .iter()
+            .map(|frequency| {
+                let online = if frequency.online { " (online)" } else { "" };
+                format!(
+                    "{:<10}{:<9}{}{online}",
+                    frequency.kind.to_string(),
+                    frequency.frequency,
+                    frequency.name
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        Section::new(format!("{} {}", briefing.role, airport.icao))
+            .field("Runway", airport.runway.clone())
+            .field(
+                "Transition",
+                format!(
+                    "{} / {}",
+                    transition::format_altitude(airport.transition_altitude),
+                    transition::format_level(airport.transition_level)
+                ),
+            )
+            .field(
+                "Wind",
+                format!(
+                    "{:03.0}° {:.0} kt",
+                    weather.wind_direction(),
+                    weather.wind_speed()
+                ),
+            )
+            .field("Runway wind", runway_wind)
+            .field(
+                "Temperature",
+                format!("{:.0} / {:.0}", weather.temperature(), weather.dew_point()),
+            )
+            .field("QNH", format!("{:.0}", weather.altimeter()))
+            .field("Visibility", weather.visibility())
+            .block("Frequencies", frequencies)
+            .block("METAR", weather.metar.clone())
+            .block("TAF", decoded_taf)
+            .block("ATC notes", briefing.notes.clone())
+    }
+
+    pub fn render(&self, format: BriefingFormat) -> String {
+        match format {
+            BriefingFormat::Markdown => self.render_markdown(),
+            BriefingFormat::Html => self.render_html(),
+            BriefingFormat::PlainText => self.render_plain_text(),
+        }
+    }
+
+    fn render_markdown(&self) -> String {
+        let mut output = format!("# {}\n", self.title());
+
+        for section in self.sections() {
+            output.push_str(&format!("\n## {}\n\n", section.title));
+            if !section.fields.is_empty() {
+                output.push_str("| | |\n|---|---|\n");
+                for (label, value) in &section.fields {
+                    output.push_str(&format!("| {label} | {} |\n", value.replace('|', "\\|")));
+                }
+            }
+            for (title, text) in &section.blocks {
+                output.push_str(&format!("\n**{title}**\n\n```\n{text}\n```\n"));
+            }
+        }
+
+        output
+    }
+
+    fn render_html(&self) -> String {
+        let title = utils::escape_html(&self.title());
+        let mut output = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+             <style>\nbody {{ font-family: sans-serif; max-width: 48em; margin: auto; }}\n\
+             table {{ border-collapse: collapse; }}\n\
+             td {{ border: 1px solid #999; padding: 2px 8px; }}\n\
+             pre {{ white-space: pre-wrap; background: #eee; padding: 4px; }}\n</style>\n\
+             </head>\n<body>\n<h1>{title}</h1>\n"
+        );
+
+        for section in self.sections() {
+            output.push_str(&format!(
+                "<h2>{}</h2>\n",
+                utils::escape_html(&section.title)
+            ));
+            if !section.fields.is_empty() {
+                output.push_str("<table>\n");
+                for (label, value) in &section.fields {
+                    output.push_str(&format!(
+                        "<tr><td>{}</td><td>{}</td></tr>\n",
+                        utils::escape_html(label),
+                        utils::escape_html(value)
+                    ));
+                }
+                output.push_str("</table>\n");
+            }
+            for (title, text) in &section.blocks {
+                output.push_str(&format!(
+                    "<h3>{}</h3>\n<pre>{}</pre>\n",
+                    utils::escape_html(title),
+                    utils::escape_html(text)
+                ));
+            }
+        }
+
+        output.push_str("</body>\n</html>\n");
+        output
+    }
+
+    fn render_plain_text(&self) -> String {
+        let separator = "=".repeat(KNEEBOARD_WIDTH);
+        let mut output = String::new();
+
+        for line in utils::wrap_text(&self.title(), KNEEBOARD_WIDTH) {
+            output.push_str(&format!("{line}\n"));
+        }
+
+        for section in self.sections() {
+            output.push_str(&format!("{separator}\n{}\n{separator}\n", section.title));
+            for (label, value) in &section.fields {
+                let indent = " ".repeat(14);
+                let lines = utils::wrap_text(value, KNEEBOARD_WIDTH - indent.len());
+                for (index, line) in lines.iter().enumerate() {
+                    if index == 0 {
+                        output.push_str(&format!("{label:<14}{line}\n"));
+                    } else {
+                        output.push_str(&format!("{indent}{line}\n"));
+                    }
+                }
+            }
+            for (title, text) in &section.blocks {
+                output.push_str(&format!("\n{title}:\n"));
+                for paragraph in text.lines() {
+                    if paragraph.chars().count() <= KNEEBOARD_WIDTH {
+                        output.push_str(&format!("{paragraph}\n"));
+                        continue;
+                    }
+                    for line in utils::wrap_text(paragraph, KNEEBOARD_WIDTH) {
+                        output.push_str(&format!("{line}\n"));
+                    }
+                }
+            }
+            output.push('\n');
+        }
+
+        output
+    }
+}
+
+// Rendering borrows the application state, so only the finished text is handed to the task
+pub async fn save(contents: String, format: BriefingFormat, directory: PathBuf) -> Result<PathBuf> {
+    let path = directory.join(format!("briefing.{}", format.extension()));
+
+    tokio::fs::write(&path, contents)
+        .await
+        .with_context(|| format!("failed to write {}", path.display()))?;
+
+    Ok(path)
+}
+
+// Planned against the structural limit, e.g. "61234 / 64300"
+fn format_weight(planned: &str, maximum: &str) -> String {
+    let (planned, maximum) = (utils::known(planned), utils::known(maximum));
+
+    match (planned.is_empty(), maximum.is_empty()) {
+        (true, _) => String::new(),
+        (false, true) => planned.to_string(),
+        (false, false) => format!("{planned} / {maximum}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn airport(icao: &str, runway: &str, weather: &str) -> Airport {
+        let mut airport = Airport {
+            icao: icao.to_string(),
+            runway: runway.to_string(),
+            ..Default::default()
+        };
+        airport.set_weather(serde_json::from_str(weather).unwrap());
+        airport
+    }
+
+    fn render(format: BriefingFormat) -> String {
+        let departure = airport(
+            "EDDF",
+            "25C",
+            r#"{"wdir": 250, "wspd": 12, "temp": 18, "dewp": 9, "altim": 1015, "visib": "6+",
+                "rawOb": "EDDF 181150Z 25012KT CAVOK 18/09 Q1015 NOSIG"}"#,
+        );
+        let arrival = airport(
+            "EGLL",
+            "27L",
+            r#"{"wdir": 240, "wspd": 8, "visib": "6+", "rawOb": "EGLL 181150Z 24008KT 9999 SCT030 15/10 Q1012"}"#,
+        );
+        let briefing = Briefing {
+            flightplan: None,
+            departure: AirportBriefing {
+                role: "Departure",
+                airport: &departure,
+                notes: "Expect <RWY 25C> & \"high\" speed exit, \
+                        then contact apron on 121.855 after vacating the runway"
+                    .to_string(),
+            },
+            arrival: AirportBriefing {
+                role: "Arrival",
+                airport: &arrival,
+                notes: String::new(),
+            },
+        };
+
+        briefing.render(format)
+    }
+
+    #[test]
+    pub fn test_format_weight() {
+        assert_eq!(format_weight("61234", "64300"), "61234 / 64300");
+        assert_eq!(format_weight("61234", "No Value"), "61234");
+        assert_eq!(format_weight("No Value", "No Value"), "");
+    }
+
+    #[test]
+    pub fn test_render_markdown() {
+        let output = render(BriefingFormat::Markdown);
+
+        assert!(output.starts_with("# EDDF - EGLL\n"));
+        assert!(output.contains("\n## Departure EDDF\n\n| | |\n|---|---|\n| Runway | 25C |\n"));
+        assert!(output.contains("| Wind | 250° 12 kt |\n"));
+        assert!(output.contains("| QNH | 1015 |\n"));
+        assert!(
+            output.contains(
+                "\n**METAR**\n\n```\nEDDF 181150Z 25012KT CAVOK 18/09 Q1015 NOSIG\n```\n"
+            )
+        );
+        assert!(output.contains("\n**ATC notes**\n\n```\nExpect <RWY 25C>"));
+        assert!(output.contains("\n## Arrival EGLL\n"));
+        // Empty notes leave the block out
+        assert_eq!(output.matches("**ATC notes**").count(), 1);
+    }
+
+    #[test]
+    pub fn test_render_html() {
+        let output = render(BriefingFormat::Html);
+
+        assert!(output.starts_with("<!DOCTYPE html>\n"));
+        assert!(output.contains("<title>EDDF - EGLL</title>"));
+        assert!(
+            output.contains(
+                "<h2>Departure EDDF</h2>\n<table>\n<tr><td>Runway</td><td>25C</td></tr>\n"
+            )
+        );
+        assert!(output.contains(
+            "<h3>ATC notes</h3>\n<pre>Expect &lt;RWY 25C&gt; &amp; &quot;high&quot; speed exit"
+        ));
+        assert!(!output.contains("<RWY"));
+        assert!(output.ends_with("</body>\n</html>\n"));
+    }
+
+    #[test]
+    pub fn test_render_plain_text() {
+        let output = render(BriefingFormat::PlainText);
+        let separator = "=".repeat(KNEEBOARD_WIDTH);
+
+        assert!(output.starts_with(&format!(
+            "EDDF - EGLL\n{separator}\nDeparture EDDF\n{separator}\nRunway        25C\n"
+        )));
+        assert!(output.contains("\nMETAR:\nEDDF 181150Z 25012KT CAVOK 18/09 Q1015 NOSIG\n"));
+        assert!(output.contains(
+            "\nATC notes:\nExpect <RWY 25C> & \"high\" speed exit, then\n\
+             contact apron on 121.855 after vacating the\n\
+             runway\n"
+        ));
+        assert!(
+            output
+                .lines()
+                .all(|line| line.chars().count() <= KNEEBOARD_WIDTH)
+        );
+    }
+}
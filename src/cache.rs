@@ -0,0 +1,264 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, TimeDelta, Utc};
+use reqwest::{
+    StatusCode,
+    header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::utils;
+
+const CACHE_DIRECTORY: &str = "cache";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Weather,
+    FlightPlan,
+    Vatsim,
+}
+
+impl Source {
+    // How long a response is used without asking the server again. The OFP only changes when it
+    // is regenerated on SimBrief, so it is always revalidated and the copy is kept for when
+    // SimBrief can't be reached.
+    fn ttl(&self) -> TimeDelta {
+        match self {
+            Source::Weather => TimeDelta::minutes(5),
+            Source::FlightPlan => TimeDelta::zero(),
+            Source::Vatsim => TimeDelta::minutes(1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Freshness {
+    pub fetched_at: DateTime<Utc>,
+    // Set when the network request failed and the last known response was used instead
+    pub offline: bool,
+}
+
+impl Freshness {
+    pub fn age(&self, now: DateTime<Utc>) -> TimeDelta {
+        now - self.fetched_at
+    }
+
+    pub fn describe(&self, now: DateTime<Utc>) -> String {
+        let minutes = self.age(now).num_minutes();
+        let age = match minutes {
+            ..1 => "just now".to_string(),
+            1..60 => format!("{minutes} min ago"),
+            _ => format!("{} h {} min ago", minutes / 60, minutes % 60),
+        };
+
+        if self.offline {
+            format!("{age} (offline)")
+        } else {
+            age
+        }
+    }
+}
+
+pub struct CachedResponse {
+    pub body: String,
+    pub freshness: Freshness,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct CacheEntry {
+    url: String,
+    fetched_at: DateTime<Utc>,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    body: String,
+}
+
+impl CacheEntry {
+    fn response(&self, offline: bool) -> CachedResponse {
+        CachedResponse {
+            body: self.body.clone(),
+            freshness: Freshness {
+                fetched_at: self.fetched_at,
+                offline,
+            },
+        }
+    }
+}
+
+// Serves the response from the cache while it's fresh, otherwise asks the server whether it
+// changed and falls back to the cached copy if the server can't be reached
+pub async fn fetch(url: &str, source: Source) -> Result<CachedResponse> {
+    fetch_in(Path::new(CACHE_DIRECTORY), url, source).await
+}
+
+async fn fetch_in(directory: &Path, url: &str, source: Source) -> Result<CachedResponse> {
+    let path = entry_path(directory, url);
+    let cached = load_entry(&path).await;
+
+    let fresh = cached
+        .as_ref()
+        .filter(|entry| Utc::now() - entry.fetched_at < source.ttl());
+    if let Some(entry) = fresh {
+        return Ok(entry.response(false));
+    }
+
+    let entry = match request(url, cached.as_ref()).await {
+        Ok(Some(entry)) => entry,
+        Ok(None) => {
+            // Not modified, the cached copy is still current
+            let mut entry = cached.context("not modified response without a cached copy")?;
+            entry.fetched_at = Utc::now();
+            entry
+        }
+        Err(e) => {
+            let entry = cached.ok_or(e)?;
+            return Ok(entry.response(true));
+        }
+    };
+
+    if let Err(e) = save_entry(directory, &path, &entry).await {
+        eprintln!("{e:#}");
+    }
+
+    Ok(entry.response(false))
+}
+
+// Returns None when the server answered that the cached copy is still valid
+async fn request(url: &str, cached: Option<&CacheEntry>) -> Result<Option<CacheEntry>> {
+    let mut request = utils::http_client().get(url);
+
+    if let Some(entry) = cached {
+        if let Some(etag) = &entry.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &entry.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+
+    let response = utils::send_with_retry(request).await?;
+    if response.status() == StatusCode::NOT_MODIFIED {
+        return Ok(None);
+    }
+
+    let response = response.error_for_status().context("unable to fetch url")?;
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    };
+    let etag = header(ETAG);
+    let last_modified = header(LAST_MODIFIED);
+    let body = response
+        .text()
+        .await
+        .context("failed to get text from response")?;
+
+    Ok(Some(CacheEntry {
+        url: url.to_string(),
+        fetched_at: Utc::now(),
+        etag,
+        last_modified,
+        body,
+    }))
+}
+
+// The url is turned into the file name so each request has its own entry
+fn entry_path(directory: &Path, url: &str) -> PathBuf {
+    let name: String = url
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+
+    directory.join(format!("{name}.json"))
+}
+
+async fn load_entry(path: &Path) -> Option<CacheEntry> {
+    let contents = tokio::fs::read_to_string(path).await.ok()?;
+
+    serde_json::from_str(&contents).ok()
+}
+
+async fn save_entry(directory: &Path, path: &Path, entry: &CacheEntry) -> Result<()> {
+    tokio::fs::create_dir_all(directory)
+        .await
+        .context("failed to create cache directory")?;
+    let contents = serde_json::to_string(entry).context("failed to serialize cache entry")?;
+
+    tokio::fs::write(path, contents)
+        .await
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    #[test]
+    pub fn test_describe_freshness() {
+        let now = Utc::now();
+        let freshness = Freshness {
+            fetched_at: now - TimeDelta::minutes(75),
+            offline: true,
+        };
+
+        assert_eq!(freshness.describe(now), "1 h 15 min ago (offline)");
+        assert_eq!(
+            Freshness {
+                fetched_at: now,
+                offline: false
+            }
+            .describe(now),
+            "just now"
+        );
+    }
+
+    #[tokio::test]
+    pub async fn test_revalidate_and_offline_fallback() -> anyhow::Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}/metar", listener.local_addr()?);
+
+        // Answers the first request with an ETag and the second one with not modified, then
+        // goes away to simulate losing the connection
+        let server = tokio::spawn(async move {
+            let mut requests = Vec::new();
+            for reply in [
+                "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: 4\r\nConnection: close\r\n\r\nEGLL",
+                "HTTP/1.1 304 Not Modified\r\nConnection: close\r\n\r\n",
+            ] {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buffer = [0; 1024];
+                let read = stream.read(&mut buffer).await.unwrap();
+                requests.push(String::from_utf8_lossy(&buffer[..read]).to_lowercase());
+                stream.write_all(reply.as_bytes()).await.unwrap();
+            }
+            requests
+        });
+
+        let directory = std::env::temp_dir().join(format!("flypad-cache-{}", std::process::id()));
+        let first = fetch_in(&directory, &url, Source::FlightPlan).await?;
+        let second = fetch_in(&directory, &url, Source::FlightPlan).await?;
+        let requests = server.await?;
+        let third = fetch_in(&directory, &url, Source::FlightPlan).await?;
+        tokio::fs::remove_dir_all(&directory).await?;
+
+        assert_eq!(first.body, "EGLL");
+        assert!(requests[1].contains("if-none-match: \"v1\""));
+        assert_eq!(second.body, "EGLL");
+        assert!(!second.freshness.offline);
+        assert_eq!(third.body, "EGLL");
+        assert!(third.freshness.offline);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+use iced::{
+    Color, Pixels, Point, Rectangle, Renderer, Size, Theme, mouse,
+    widget::canvas::{self, Frame, Geometry, Path, Stroke, Text},
+};
+
+use crate::trend::{Trend, TrendSeries};
+
+const LABEL_SIZE: f32 = 12.0;
+const PADDING: f32 = 4.0;
+
+// One small line chart per series stacked on top of each other, every series is scaled to its
+// own range as only the direction of the change matters
+pub struct TrendChart<'a> {
+    pub trend: &'a Trend,
+}
+
+impl<Message> canvas::Program<Message> for TrendChart<'_> {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let palette = theme.palette();
+        let panel_height = bounds.height / TrendSeries::ALL.len() as f32;
+
+        for (index, series) in TrendSeries::ALL.into_iter().enumerate() {
+            let panel = Rectangle::new(
+                Point::new(0.0, panel_height * index as f32),
+                Size::new(bounds.width, panel_height),
+            );
+            draw_series(
+                &mut frame,
+                self.trend,
+                series,
+                panel,
+                palette.text,
+                palette.primary,
+            );
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
+
+fn draw_series(
+    frame: &mut Frame,
+    trend: &Trend,
+    series: TrendSeries,
+    panel: Rectangle,
+    text_color: Color,
+    line_color: Color,
+) {
+    let points = trend.points(series);
+    let label = match (points.first(), points.last()) {
+        (Some((_, first)), Some((_, last))) => {
+            format!("{} {first:.0} to {last:.0}", series.label())
+        }
+        _ => format!("{} no data", series.label()),
+    };
+    frame.fill_text(Text {
+        content: label,
+        position: Point::new(panel.x + PADDING, panel.y + PADDING),
+        color: text_color,
+        size: Pixels(LABEL_SIZE),
+        ..Text::default()
+    });
+
+    let baseline = panel.y + panel.height - PADDING;
+    frame.stroke(
+        &Path::line(
+            Point::new(panel.x, baseline),
+            Point::new(panel.x + panel.width, baseline),
+        ),
+        Stroke::default()
+            .with_color(Color {
+                a: 0.3,
+                ..text_color
+            })
+            .with_width(1.0),
+    );
+
+    let (Some((start, _)), Some((end, _))) = (points.first(), points.last()) else {
+        return;
+    };
+    let duration = (*end - *start).num_seconds().max(1) as f32;
+    let minimum = points
+        .iter()
+        .map(|(_, value)| *value)
+        .fold(f32::MAX, f32::min);
+    let maximum = points
+        .iter()
+        .map(|(_, value)| *value)
+        .fold(f32::MIN, f32::max);
+    let range = (maximum - minimum).max(1.0);
+
+    // Leaves room for the label above the line
+    let top = panel.y + LABEL_SIZE + PADDING * 2.0;
+    let height = baseline - top;
+
+    let path = Path::new(|builder| {
+        for (index, (time, value)) in points.iter().enumerate() {
+            let x = panel.x + (*time - *start).num_seconds() as f32 / duration * panel.width;
+            let y = baseline - (value - minimum) / range * height;
+            if index == 0 {
+                builder.move_to(Point::new(x, y));
+            } else {
+                builder.line_to(Point::new(x, y));
+            }
+        }
+    });
+    frame.stroke(
+        &path,
+        Stroke::default().with_color(line_color).with_width(2.0),
+    );
+}
@@ -0,0 +1,291 @@
+use std::fmt::Display;
+
+use crate::flightplan::FlightPlan;
+
+const EMERGENCY_SQUAWKS: [&str; 3] = ["7500", "7600", "7700"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClearanceField {
+    ClearanceLimit,
+    Route,
+    Altitude,
+    Frequency,
+    Squawk,
+}
+
+impl ClearanceField {
+    pub const ALL: [ClearanceField; 5] = [
+        ClearanceField::ClearanceLimit,
+        ClearanceField::Route,
+        ClearanceField::Altitude,
+        ClearanceField::Frequency,
+        ClearanceField::Squawk,
+    ];
+}
+
+impl Display for ClearanceField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            ClearanceField::ClearanceLimit => "Clearance limit",
+            ClearanceField::Route => "Route",
+            ClearanceField::Altitude => "Altitude",
+            ClearanceField::Frequency => "Frequency",
+            ClearanceField::Squawk => "Squawk",
+        };
+
+        write!(f, "{label}")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClearanceError {
+    Missing(ClearanceField),
+    InvalidAltitude(String),
+    InvalidFrequency(String),
+    InvalidSquawk(String),
+    EmergencySquawk(String),
+}
+
+impl Display for ClearanceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClearanceError::Missing(field) => write!(f, "{field} is missing"),
+            ClearanceError::InvalidAltitude(altitude) => {
+                write!(f, "{altitude} is not an altitude or flight level")
+            }
+            ClearanceError::InvalidFrequency(frequency) => {
+                write!(f, "{frequency} is not a VHF frequency")
+            }
+            ClearanceError::InvalidSquawk(squawk) => {
+                write!(f, "squawk {squawk} must be four octal digits")
+            }
+            ClearanceError::EmergencySquawk(squawk) => {
+                write!(f, "squawk {squawk} is an emergency code")
+            }
+        }
+    }
+}
+
+// Structured version of the CRAFT mnemonic: Clearance limit, Route, Altitude, Frequency, Transponder
+#[derive(Debug, Clone, Default)]
+pub struct Clearance {
+    pub clearance_limit: String,
+    pub route: String,
+    pub altitude: String,
+    pub frequency: String,
+    pub squawk: String,
+    pub emergency_squawk_intended: bool,
+}
+
+impl Clearance {
+    pub fn from_flight_plan(flightplan: &FlightPlan) -> Self {
+        let information = &flightplan.flight_information;
+        let route = match (
+            information.sid_ident.as_str(),
+            information.sid_trans.as_str(),
+        ) {
+            ("" | "No Value", _) => String::new(),
+            (sid, "" | "No Value") => format!("{sid} departure"),
+            (sid, transition) => format!("{sid} departure, {transition} transition"),
+        };
+        let altitude = match information.initial_altitude.as_str() {
+            "No Value" => String::new(),
+            altitude => altitude.to_string(),
+        };
+
+        Self {
+            clearance_limit: flightplan.destination.icao_code.clone(),
+            route,
+            altitude,
+            ..Default::default()
+        }
+    }
+
+    pub fn get(&self, field: ClearanceField) -> &str {
+        match field {
+            ClearanceField::ClearanceLimit => &self.clearance_limit,
+            ClearanceField::Route => &self.route,
+            ClearanceField::Altitude => &self.altitude,
+            ClearanceField::Frequency => &self.frequency,
+            ClearanceField::Squawk => &self.squawk,
+        }
+    }
+
+    pub fn set(&mut self, field: ClearanceField, value: String) {
+        let value = match field {
+            ClearanceField::ClearanceLimit | ClearanceField::Altitude => value.to_uppercase(),
+            _ => value,
+        };
+
+        match field {
+            ClearanceField::ClearanceLimit => self.clearance_limit = value,
+            ClearanceField::Route => self.route = value,
+            ClearanceField::Altitude => self.altitude = value,
+            ClearanceField::Frequency => self.frequency = value,
+            ClearanceField::Squawk => self.squawk = value,
+        }
+    }
+
+    pub fn validate(&self) -> Vec<ClearanceError> {
+        let mut errors: Vec<ClearanceError> = ClearanceField::ALL
+            .into_iter()
+            .filter(|field| self.get(*field).trim().is_empty())
+            .map(ClearanceError::Missing)
+            .collect();
+
+        let altitude = self.altitude.trim();
+        if !altitude.is_empty() && parse_altitude(altitude).is_none() {
+            errors.push(ClearanceError::InvalidAltitude(altitude.to_string()));
+        }
+
+        let frequency = self.frequency.trim();
+        if !frequency.is_empty() && !is_valid_frequency(frequency) {
+            errors.push(ClearanceError::InvalidFrequency(frequency.to_string()));
+        }
+
+        let squawk = self.squawk.trim();
+        if !squawk.is_empty() {
+            if !is_valid_squawk(squawk) {
+                errors.push(ClearanceError::InvalidSquawk(squawk.to_string()));
+            } else if EMERGENCY_SQUAWKS.contains(&squawk) && !self.emergency_squawk_intended {
+                errors.push(ClearanceError::EmergencySquawk(squawk.to_string()));
+            }
+        }
+
+        errors
+    }
+
+    pub fn readback(&self, callsign: &str) -> String {
+        let mut parts = Vec::new();
+
+        if !self.clearance_limit.is_empty() {
+            parts.push(format!("cleared to {}", self.clearance_limit));
+        }
+        if !self.route.is_empty() {
+            parts.push(format!("via {}", self.route));
+        }
+        match parse_altitude(self.altitude.trim()) {
+            Some(Altitude::FlightLevel(level)) => {
+                parts.push(format!("climb flight level {level:03}"))
+            }
+            Some(Altitude::Feet(feet)) => parts.push(format!("climb {feet} feet")),
+            None => (),
+        }
+        if !self.frequency.is_empty() {
+            parts.push(format!("departure frequency {}", self.frequency.trim()));
+        }
+        if !self.squawk.is_empty() {
+            parts.push(format!("squawk {}", self.squawk.trim()));
+        }
+        if !callsign.is_empty() {
+            parts.push(callsign.to_string());
+        }
+
+        parts.join(", ")
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Altitude {
+    FlightLevel(u32),
+    Feet(u32),
+}
+
+fn parse_altitude(altitude: &str) -> Option<Altitude> {
+    let altitude = altitude.to_uppercase();
+
+    if let Some(level) = altitude.strip_prefix("FL") {
+        return level.trim().parse().ok().map(Altitude::FlightLevel);
+    }
+
+    altitude
+        .trim_end_matches("FT")
+        .trim()
+        .parse()
+        .ok()
+        .map(Altitude::Feet)
+}
+
+// VHF air band, either 25 kHz or 8.33 kHz channels. Every 25 kHz block holds the 25 kHz channel
+// and three 8.33 kHz channels named 5, 10 and 15 kHz above it, like 118.700, 118.705 and 118.710.
+fn is_valid_frequency(frequency: &str) -> bool {
+    let (mhz, khz) = frequency.split_once('.').unwrap_or((frequency, ""));
+    if khz.len() > 3 || !khz.chars().all(|c| c.is_ascii_digit()) {
+        return false;
+    }
+    let (Ok(mhz), Ok(khz)) = (mhz.parse::<u32>(), format!("{khz:0<3}").parse::<u32>()) else {
+        return false;
+    };
+    let channel = mhz * 1000 + khz;
+
+    (118_000..=136_990).contains(&channel) && [0, 5, 10, 15].contains(&(channel % 25))
+}
+
+fn is_valid_squawk(squawk: &str) -> bool {
+    squawk.len() == 4 && squawk.chars().all(|c| ('0'..='7').contains(&c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clearance(squawk: &str) -> Clearance {
+        Clearance {
+            clearance_limit: "EDDF".to_string(),
+            route: "MAXI 1A departure".to_string(),
+            altitude: "5000".to_string(),
+            frequency: "120.805".to_string(),
+            squawk: squawk.to_string(),
+            emergency_squawk_intended: false,
+        }
+    }
+
+    #[test]
+    pub fn test_squawk_validation() {
+        assert!(clearance("4621").validate().is_empty());
+        assert_eq!(
+            clearance("4681").validate(),
+            vec![ClearanceError::InvalidSquawk("4681".to_string())]
+        );
+        assert_eq!(
+            clearance("462").validate(),
+            vec![ClearanceError::InvalidSquawk("462".to_string())]
+        );
+        assert_eq!(
+            clearance("7600").validate(),
+            vec![ClearanceError::EmergencySquawk("7600".to_string())]
+        );
+
+        let mut intended = clearance("7700");
+        intended.emergency_squawk_intended = true;
+        assert!(intended.validate().is_empty());
+    }
+
+    #[test]
+    pub fn test_frequency_validation() {
+        for frequency in ["118.000", "120.805", "121.8", "124.350", "136.990"] {
+            assert!(is_valid_frequency(frequency), "{frequency}");
+        }
+        for frequency in [
+            "117.975", "120.820", "121.82", "137.000", "121.8000", "12a.800",
+        ] {
+            assert!(!is_valid_frequency(frequency), "{frequency}");
+        }
+    }
+
+    #[test]
+    pub fn test_readback() {
+        assert_eq!(
+            clearance("4621").readback("DLH4CK"),
+            "cleared to EDDF, via MAXI 1A departure, climb 5000 feet, departure frequency 120.805, squawk 4621, DLH4CK"
+        );
+
+        let mut flight_level = clearance("4621");
+        flight_level.altitude = "FL70".to_string();
+        assert!(
+            flight_level
+                .readback("DLH4CK")
+                .contains("climb flight level 070")
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use anyhow::{Context, Result, bail};
+use chrono::Utc;
+use serde::Serialize;
+
+use crate::{flightplan::FlightPlan, taf, vatsim::VatsimData, weather::Weather, wind::RunwayWind};
+
+pub const USAGE: &str = "Usage: flypad [COMMAND] [--json]
+
+Without a command the graphical pad is started.
+
+Commands:
+  metar <ICAO>            Latest METAR
+  taf <ICAO>              Latest TAF, decoded
+  ofp --user-id <ID>      Summary of the latest SimBrief OFP
+  atis <ICAO>             ATIS of the airport currently online on VATSIM
+  wind <ICAO> <RUNWAY>    Head and crosswind components for a runway";
+
+pub fn run(args: Vec<String>) -> Result<()> {
+    let runtime = tokio::runtime::Runtime::new().context("unable to start the async runtime")?;
+
+    runtime.block_on(run_command(args))
+}
+
+async fn run_command(args: Vec<String>) -> Result<()> {
+    let json = args.iter().any(|arg| arg == "--json");
+    let args: Vec<&str> = args
+        .iter()
+        .map(String::as_str)
+        .filter(|arg| *arg != "--json")
+        .collect();
+
+    match args.as_slice() {
+        ["metar", icao] => {
+            let weather = Weather::fetch(&icao.to_uppercase(), false).await?;
+            print_output(&weather, describe_weather(&weather), json)
+        }
+        ["taf", icao] => {
+            let weather = Weather::fetch(&icao.to_uppercase(), true).await?;
+            let Some(raw_taf) = weather.taf.as_deref() else {
+                bail!("no TAF available for {}", icao.to_uppercase());
+            };
+            let decoded = taf::decode_taf(raw_taf).context("unable to decode the TAF")?;
+
+            print_output(&decoded, format!("{raw_taf}\n\n{decoded}"), json)
+        }
+        ["ofp", "--user-id", user_id] => {
+            let flightplan = FlightPlan::fetch(user_id).await?;
+            print_output(&flightplan, describe_flight_plan(&flightplan), json)
+        }
+        ["atis", icao] => {
+            let vatsim = VatsimData::fetch().await?;
+            let atis = vatsim.atis_for(icao);
+            if atis.is_empty() {
+                bail!("no ATIS online for {}", icao.to_uppercase());
+            }
+
+            let text = atis
+                .iter()
+                .map(|atis| {
+                    format!(
+                        "{} {} information {}\n{}",
+                        atis.callsign,
+                        atis.frequency,
+                        atis.atis_code.as_deref().unwrap_or("-"),
+                        atis.atis_text()
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n\n");
+
+            print_output(&atis, text, json)
+        }
+        ["wind", icao, runway] => {
+            let weather = Weather::fetch(&icao.to_uppercase(), false).await?;
+            let runway_wind = RunwayWind::from_weather(runway, &weather)
+                .with_context(|| format!("{runway} is not a valid runway"))?;
+
+            print_output(&runway_wind, runway_wind.describe(), json)
+        }
+        ["help" | "--help" | "-h"] => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        _ => bail!("{USAGE}"),
+    }
+}
+
+fn print_output<T: Serialize>(value: &T, text: String, json: bool) -> Result<()> {
+    println!("{}", format_output(value, text, json)?);
+
+    Ok(())
+}
+
+fn format_output<T: Serialize>(value: &T, text: String, json: bool) -> Result<String> {
+    if json {
+        serde_json::to_string_pretty(value).context("failed to serialize output")
+    } else {
+        Ok(text)
+    }
+}
+
+fn describe_weather(weather: &Weather) -> String {
+    let mut description = format!(
+        "{}\n\nWind        {:03.0}° {:.0} kt\nTemperature {:.0} / {:.0}\nQNH         {:.0}\nVisibility  {}",
+        weather.metar,
+        weather.wind_direction(),
+        weather.wind_speed(),
+        weather.temperature(),
+        weather.dew_point(),
+        weather.altimeter(),
+        weather.visibility()
+    );
+
+    if let Some(freshness) = weather.freshness {
+        description.push_str(&format!("\nUpdated     {}", freshness.describe(Utc::now())));
+    }
+
+    description
+}
+
+fn describe_flight_plan(flightplan: &FlightPlan) -> String {
+    let information = &flightplan.flight_information;
+
+    format!(
+        "{} {} - {}\nRoute       {}\nCost index  {}\nInitial alt {}\nDistance    {} nm\nBlock fuel  {}",
+        flightplan.callsign(),
+        flightplan.origin.icao_code,
+        flightplan.destination.icao_code,
+        information.route_ifps,
+        information.cost_index,
+        information.initial_altitude,
+        information.route_distance,
+        flightplan.fuel.plan_ramp
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_wind_json_output() {
+        let runway_wind = RunwayWind::compute("27", 270.0, 10.0, 0.0).unwrap();
+
+        let output = format_output(&runway_wind, runway_wind.describe(), true).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(value["runway"], "27");
+        assert_eq!(value["runway_heading"], 270.0);
+        assert_eq!(value["headwind"], 10.0);
+        assert_eq!(value["crosswind"], 0.0);
+        assert!(value["gust_headwind"].is_null());
+
+        let output = format_output(&runway_wind, runway_wind.describe(), false).unwrap();
+        assert_eq!(output, "27: 10 kt headwind, 0 kt crosswind from the right");
+    }
+}
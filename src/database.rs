@@ -0,0 +1,328 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+
+use crate::utils;
+
+// The airport database is the OurAirports dataset (https://ourairports.com/data/), the CSV files
+// need to be downloaded into the data directory next to the executable
+pub const AIRPORT_FREQUENCIES_PATH: &str = "data/airport-frequencies.csv";
+pub const AIRPORTS_PATH: &str = "data/airports.csv";
+pub const RUNWAYS_PATH: &str = "data/runways.csv";
+
+#[derive(Debug, Clone)]
+pub struct AirportFrequencyRecord {
+    pub airport_ident: String,
+    pub frequency_type: String,
+    pub description: String,
+    pub frequency_mhz: String,
+}
+
+// Columns: id, airport_ref, airport_ident, type, description, frequency_mhz
+pub async fn load_airport_frequencies(icao: &str) -> Result<Vec<AirportFrequencyRecord>> {
+    let contents = tokio::fs::read_to_string(AIRPORT_FREQUENCIES_PATH)
+        .await
+        .context("unable to read the airport frequency database")?;
+
+    Ok(contents
+        .lines()
+        .skip(1)
+        .map(utils::split_csv_line)
+        .filter(|fields| fields.len() >= 6 && fields[2].eq_ignore_ascii_case(icao))
+        .map(|fields| AirportFrequencyRecord {
+            airport_ident: fields[2].clone(),
+            frequency_type: fields[3].clone(),
+            description: fields[4].clone(),
+            frequency_mhz: fields[5].clone(),
+        })
+        .collect())
+}
+
+// The dataset stores both ends of a runway on one line, each end becomes its own record
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunwayRecord {
+    pub ident: String,
+    pub length_ft: u32,
+    pub heading: Option<f32>,
+    pub elevation_ft: Option<i32>,
+    pub displaced_threshold_ft: u32,
+}
+
+impl RunwayRecord {
+    pub fn length_m(&self) -> f32 {
+        self.length_ft as f32 * 0.3048
+    }
+
+    // The displaced threshold can be used for the takeoff roll but not for landing
+    pub fn landing_length_m(&self) -> f32 {
+        self.length_ft.saturating_sub(self.displaced_threshold_ft) as f32 * 0.3048
+    }
+}
+
+pub async fn load_runways(icao: &str) -> Result<Vec<RunwayRecord>> {
+    let contents = tokio::fs::read_to_string(RUNWAYS_PATH)
+        .await
+        .context("unable to read the runway database")?;
+
+    parse_runways(&contents, icao)
+}
+
+fn parse_runways(contents: &str, icao: &str) -> Result<Vec<RunwayRecord>> {
+    let mut lines = contents.lines();
+    let header = utils::split_csv_line(lines.next().context("runway database is empty")?);
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|column| column == name)
+            .with_context(|| format!("runway database has no {name} column"))
+    };
+    let airport = column("airport_ident")?;
+    let length = column("length_ft")?;
+    let closed = column("closed")?;
+    let ends = [
+        (
+            column("le_ident")?,
+            column("le_heading_degT")?,
+            column("le_elevation_ft")?,
+            column("le_displaced_threshold_ft")?,
+        ),
+        (
+            column("he_ident")?,
+            column("he_heading_degT")?,
+            column("he_elevation_ft")?,
+            column("he_displaced_threshold_ft")?,
+        ),
+    ];
+    let columns = header.len();
+
+    Ok(lines
+        .map(utils::split_csv_line)
+        .filter(|fields| fields.len() >= columns && fields[airport].eq_ignore_ascii_case(icao))
+        .filter(|fields| fields[closed] != "1")
+        .flat_map(|fields| {
+            ends.map(
+                |(ident, heading, elevation, displaced_threshold)| RunwayRecord {
+                    ident: fields[ident].to_uppercase(),
+                    length_ft: fields[length].parse().unwrap_or_default(),
+                    heading: fields[heading].parse().ok(),
+                    elevation_ft: fields[elevation].parse().ok(),
+                    displaced_threshold_ft: fields[displaced_threshold].parse().unwrap_or_default(),
+                },
+            )
+        })
+        .filter(|runway| !runway.ident.is_empty())
+        .collect())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AirportRecord {
+    pub icao: String,
+    pub iata: String,
+    pub name: String,
+    pub city: String,
+    pub country: String,
+    pub elevation_ft: Option<i32>,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl AirportRecord {
+    pub fn describe(&self) -> String {
+        let mut description = self.name.clone();
+        if !self.city.is_empty() {
+            description.push_str(&format!(", {}", self.city));
+        }
+        if !self.country.is_empty() {
+            description.push_str(&format!(", {}", self.country));
+        }
+        if let Some(elevation) = self.elevation_ft {
+            description.push_str(&format!(" ({elevation} ft)"));
+        }
+
+        description
+    }
+}
+
+// Kept in memory as lookups happen on every key press
+#[derive(Debug, Clone, Default)]
+pub struct AirportDatabase {
+    airports: HashMap<String, AirportRecord>,
+}
+
+impl AirportDatabase {
+    pub async fn load() -> Result<AirportDatabase> {
+        let contents = tokio::fs::read_to_string(AIRPORTS_PATH)
+            .await
+            .context("unable to read the airport database")?;
+
+        Self::parse(&contents)
+    }
+
+    // The column order changed between releases of the dataset, so columns are found by name
+    fn parse(contents: &str) -> Result<AirportDatabase> {
+        let mut lines = contents.lines();
+        let header = utils::split_csv_line(lines.next().context("airport database is empty")?);
+        let column = |name: &str| {
+            header
+                .iter()
+                .position(|column| column == name)
+                .with_context(|| format!("airport database has no {name} column"))
+        };
+        let ident = column("ident")?;
+        let kind = column("type")?;
+        let name = column("name")?;
+        let latitude = column("latitude_deg")?;
+        let longitude = column("longitude_deg")?;
+        let elevation = column("elevation_ft")?;
+        let country = column("iso_country")?;
+        let city = column("municipality")?;
+        let iata = column("iata_code")?;
+        let columns = header.len();
+
+        let airports = lines
+            .map(utils::split_csv_line)
+            .filter(|fields| fields.len() >= columns)
+            .filter(|fields| {
+                !matches!(fields[kind].as_str(), "closed" | "heliport" | "balloonport")
+            })
+            .map(|fields| {
+                let record = AirportRecord {
+                    icao: fields[ident].to_uppercase(),
+                    iata: fields[iata].to_uppercase(),
+                    name: fields[name].clone(),
+                    city: fields[city].clone(),
+                    country: fields[country].clone(),
+                    elevation_ft: fields[elevation].parse().ok(),
+                    latitude: fields[latitude].parse().unwrap_or_default(),
+                    longitude: fields[longitude].parse().unwrap_or_default(),
+                };
+                (record.icao.clone(), record)
+            })
+            .collect();
+
+        Ok(AirportDatabase { airports })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.airports.is_empty()
+    }
+
+    pub fn get(&self, icao: &str) -> Option<&AirportRecord> {
+        self.airports.get(&icao.trim().to_uppercase())
+    }
+
+    // Without the database any four letter code is accepted
+    pub fn is_valid_icao(&self, icao: &str) -> bool {
+        if self.is_empty() {
+            icao.len() == 4 && icao.chars().all(|c| c.is_ascii_alphanumeric())
+        } else {
+            self.get(icao).is_some()
+        }
+    }
+
+    // Matches the start of the ICAO code, the IATA code or any word of the name or city. ICAO
+    // matches come first, then IATA and then names.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<&AirportRecord> {
+        let query = query.trim().to_uppercase();
+        if query.len() < 2 {
+            return Vec::new();
+        }
+
+        let rank = |airport: &AirportRecord| {
+            let matches_word = |text: &str| {
+                text.to_uppercase()
+                    .split(|c: char| !c.is_alphanumeric())
+                    .any(|word| word.starts_with(&query))
+            };
+
+            if airport.icao.starts_with(&query) {
+                Some(0)
+            } else if airport.iata == query {
+                Some(1)
+            } else if matches_word(&airport.name) || matches_word(&airport.city) {
+                Some(2)
+            } else {
+                None
+            }
+        };
+
+        let mut matches: Vec<(u8, &AirportRecord)> = self
+            .airports
+            .values()
+            .filter_map(|airport| rank(airport).map(|rank| (rank, airport)))
+            .collect();
+        matches.sort_by(|(a_rank, a), (b_rank, b)| a_rank.cmp(b_rank).then(a.icao.cmp(&b.icao)));
+
+        matches
+            .into_iter()
+            .take(limit)
+            .map(|(_, airport)| airport)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AIRPORTS: &str = "\
+\"id\",\"ident\",\"type\",\"name\",\"latitude_deg\",\"longitude_deg\",\"elevation_ft\",\"continent\",\"iso_country\",\"iso_region\",\"municipality\",\"scheduled_service\",\"gps_code\",\"iata_code\",\"local_code\"
+2434,\"EGLL\",\"large_airport\",\"London Heathrow Airport\",51.4706,-0.461941,83,\"EU\",\"GB\",\"GB-ENG\",\"London\",\"yes\",\"EGLL\",\"LHR\",
+2429,\"EGKK\",\"large_airport\",\"London Gatwick Airport\",51.148102,-0.190278,202,\"EU\",\"GB\",\"GB-ENG\",\"London\",\"yes\",\"EGKK\",\"LGW\",
+2212,\"EDDF\",\"large_airport\",\"Frankfurt am Main Airport\",50.036249,8.559294,364,\"EU\",\"DE\",\"DE-HE\",\"Frankfurt am Main\",\"yes\",\"EDDF\",\"FRA\",
+1,\"EGXX\",\"closed\",\"Closed Field\",51,0,,\"EU\",\"GB\",\"GB-ENG\",\"\",\"no\",\"\",\"\",
+";
+
+    #[test]
+    pub fn test_airport_lookup() -> anyhow::Result<()> {
+        let database = AirportDatabase::parse(AIRPORTS)?;
+
+        let heathrow = database.get("egll").unwrap();
+        assert_eq!(heathrow.iata, "LHR");
+        assert_eq!(heathrow.elevation_ft, Some(83));
+        assert_eq!(
+            heathrow.describe(),
+            "London Heathrow Airport, London, GB (83 ft)"
+        );
+        assert!(database.is_valid_icao("EDDF"));
+        assert!(!database.is_valid_icao("EGXX"));
+
+        let icaos = |query| {
+            database
+                .search(query, 5)
+                .iter()
+                .map(|airport| airport.icao.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(icaos("lgw"), vec!["EGKK"]);
+        assert_eq!(icaos("london"), vec!["EGKK", "EGLL"]);
+        assert_eq!(icaos("frankfurt"), vec!["EDDF"]);
+        assert_eq!(icaos("EG"), vec!["EGKK", "EGLL"]);
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_runway_ends() -> anyhow::Result<()> {
+        let runways = parse_runways(
+            "\
+\"id\",\"airport_ref\",\"airport_ident\",\"length_ft\",\"width_ft\",\"surface\",\"lighted\",\"closed\",\"le_ident\",\"le_latitude_deg\",\"le_longitude_deg\",\"le_elevation_ft\",\"le_heading_degT\",\"le_displaced_threshold_ft\",\"he_ident\",\"he_latitude_deg\",\"he_longitude_deg\",\"he_elevation_ft\",\"he_heading_degT\",\"he_displaced_threshold_ft\"
+1,2434,\"EGLL\",12802,164,\"ASP\",1,0,\"09L\",51.4775,-0.48462,79,89.6,1013,\"27R\",51.4777,-0.43328,78,269.6,
+2,2434,\"EGLL\",12008,164,\"ASP\",1,0,\"09R\",51.4647,-0.48226,75,89.6,1007,\"27L\",51.4649,-0.43412,77,269.7,
+3,2434,\"EGLL\",6000,150,\"ASP\",0,1,\"05\",51.47,-0.47,80,50,,\"23\",51.48,-0.46,80,230,
+4,2429,\"EGKK\",10879,148,\"ASP\",1,0,\"08R\",51.14,-0.21,196,77.8,,\"26L\",51.15,-0.16,202,257.8,
+",
+            "egll",
+        )?;
+
+        let idents: Vec<&str> = runways.iter().map(|runway| runway.ident.as_str()).collect();
+        assert_eq!(idents, vec!["09L", "27R", "09R", "27L"]);
+        assert_eq!(runways[0].displaced_threshold_ft, 1013);
+        assert_eq!(runways[1].displaced_threshold_ft, 0);
+        assert_eq!(runways[3].elevation_ft, Some(77));
+        assert_eq!(runways[1].length_m().round(), 3902.0);
+        assert_eq!(runways[0].landing_length_m().round(), 3593.0);
+
+        Ok(())
+    }
+}
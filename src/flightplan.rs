@@ -1,12 +1,17 @@
+use std::collections::BTreeMap;
+
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-use crate::utils;
-
-#[derive(Deserialize, Serialize)]
-pub struct User(pub String);
+use crate::{
+    cache::{self, Freshness, Source},
+    notam::Notam,
+    utils,
+};
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct Airport {
     #[serde(default)]
     pub icao_code: String,
@@ -20,9 +25,19 @@ pub struct Airport {
     pub trans_alt: String,
     #[serde(default)]
     pub trans_level: String,
+    #[serde(default)]
+    pub pos_lat: String,
+    #[serde(default)]
+    pub pos_long: String,
+    // Feet
+    #[serde(default)]
+    pub elevation: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_one_or_many")]
+    pub notam: Vec<Notam>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct FlightOverview {
     #[serde(default)]
     #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
@@ -49,6 +64,9 @@ pub struct FlightOverview {
     pub initial_altitude: String,
     #[serde(default)]
     #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub cruise_tas: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
     pub route_ifps: String,
     #[serde(default)]
     #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
@@ -67,7 +85,7 @@ pub struct FlightOverview {
     pub star_trans: String,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct Fuel {
     #[serde(default)]
     #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
@@ -116,16 +134,304 @@ pub struct Fuel {
     pub max_tanks: String,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Aircraft {
+    #[serde(rename = "icaocode")]
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub icao_code: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub name: String,
+    #[serde(rename = "reg")]
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub registration: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub selcal: String,
+    #[serde(rename = "equip")]
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub equipment: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Impact {
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub burn_difference: String,
+}
+
+// How the plan changes with a different weight, altitude or cost index
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Impacts {
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_optional_object")]
+    pub zfw_plus_1000: Option<Impact>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Params {
+    // "kgs" or "lbs", every weight and fuel value of the OFP uses it
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub units: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub airac: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Fix {
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub ident: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub name: String,
+    // wpt, vor, ndb, apt or ltlg for coordinates and the top of climb and descent
+    #[serde(rename = "type")]
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub fix_type: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub pos_lat: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub pos_long: String,
+    // The airway, procedure or DCT leading to the fix
+    #[serde(rename = "via_airway")]
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub via: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub is_sid_star: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub altitude_feet: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Navlog {
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_one_or_many")]
+    pub fix: Vec<Fix>,
+}
+
+// What SimBrief prepared for filing the flight plan
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Atc {
+    // Item 18 of the ICAO flight plan, like "PBN/A1B1C1D1S1S2 DOF/251018 REG/DAINA RMK/TCAS"
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub section18: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Text {
+    // The OFP as it is shown on the SimBrief website
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub plan_html: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct FileLink {
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub name: String,
+    // Relative to the directory of the files
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub link: String,
+}
+
+// The PDF and the plans for the simulators and add-on aircraft. Every format has its own key,
+// so they are collected from whatever is next to the directory.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Files {
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub directory: String,
+    #[serde(flatten)]
+    pub entries: BTreeMap<String, Value>,
+}
+
+impl Files {
+    pub fn links(&self) -> Vec<FileLink> {
+        self.entries
+            .values()
+            .flat_map(|value| {
+                utils::deserialize_one_or_many::<_, FileLink>(value.clone()).unwrap_or_default()
+            })
+            .filter(|file| !file.link.is_empty())
+            .collect()
+    }
+
+    pub fn url(&self, file: &FileLink) -> String {
+        format!("{}{}", self.directory, file.link)
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Weights {
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub payload: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub pax_count: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub cargo: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub est_zfw: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub max_zfw: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub est_tow: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub max_tow: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub est_ldw: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub max_ldw: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub est_ramp: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Times {
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub sched_out: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub sched_off: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub sched_on: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub sched_in: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub est_out: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub est_off: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub est_on: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub est_in: String,
+    // Hours from UTC, SimBrief uses fractions for zones like India
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub orig_timezone: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub dest_timezone: String,
+}
+
+impl Times {
+    pub fn scheduled_out(&self) -> Option<DateTime<Utc>> {
+        utils::parse_unix_timestamp(&self.sched_out)
+    }
+
+    pub fn scheduled_off(&self) -> Option<DateTime<Utc>> {
+        utils::parse_unix_timestamp(&self.sched_off)
+    }
+
+    pub fn scheduled_on(&self) -> Option<DateTime<Utc>> {
+        utils::parse_unix_timestamp(&self.sched_on)
+    }
+
+    pub fn scheduled_in(&self) -> Option<DateTime<Utc>> {
+        utils::parse_unix_timestamp(&self.sched_in)
+    }
+
+    pub fn estimated_out(&self) -> Option<DateTime<Utc>> {
+        utils::parse_unix_timestamp(&self.est_out)
+    }
+
+    pub fn estimated_off(&self) -> Option<DateTime<Utc>> {
+        utils::parse_unix_timestamp(&self.est_off)
+    }
+
+    pub fn estimated_on(&self) -> Option<DateTime<Utc>> {
+        utils::parse_unix_timestamp(&self.est_on)
+    }
+
+    pub fn estimated_in(&self) -> Option<DateTime<Utc>> {
+        utils::parse_unix_timestamp(&self.est_in)
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct FlightPlan {
     pub origin: Airport,
     pub destination: Airport,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_one_or_many")]
+    pub alternate: Vec<Airport>,
     #[serde(rename = "general")]
     pub flight_information: FlightOverview,
     pub fuel: Fuel,
+    #[serde(default)]
+    pub weights: Weights,
+    #[serde(default)]
+    pub params: Params,
+    #[serde(default)]
+    pub aircraft: Aircraft,
+    #[serde(default)]
+    pub impacts: Impacts,
+    #[serde(default)]
+    pub times: Times,
+    #[serde(default)]
+    pub navlog: Navlog,
+    #[serde(default)]
+    pub atc: Atc,
+    #[serde(default)]
+    pub text: Text,
+    #[serde(default)]
+    pub files: Files,
+    #[serde(default)]
+    pub fms_downloads: Files,
+    #[serde(skip)]
+    pub freshness: Option<Freshness>,
 }
 
 impl FlightPlan {
+    pub fn callsign(&self) -> String {
+        format!(
+            "{}{}",
+            self.flight_information.icao_airline, self.flight_information.flight_number
+        )
+    }
+
+    // Converts an OFP weight to kilograms
+    pub fn weight_kg(&self, value: &str) -> Option<f32> {
+        let weight: f32 = value.trim().parse().ok()?;
+
+        if self.params.units.eq_ignore_ascii_case("lbs") {
+            Some(weight * 0.453_592)
+        } else {
+            Some(weight)
+        }
+    }
+
     pub async fn fetch(user_id: &str) -> Result<FlightPlan> {
         // FIXME Remove this to prevent having my user id static.
         let user_id = if user_id.is_empty() {
@@ -134,8 +440,12 @@ impl FlightPlan {
             user_id
         };
         let url = format!("https://www.simbrief.com/api/xml.fetcher.php?userid={user_id}&json=1");
-        let body = utils::fetch_url_data(&url).await?;
+        let response = cache::fetch(&url, Source::FlightPlan).await?;
+
+        let mut flightplan: FlightPlan =
+            serde_json::from_str(&response.body).context("failed deserializing flightplan")?;
+        flightplan.freshness = Some(response.freshness);
 
-        serde_json::from_str(&body).context("failed deserializing flightplan")
+        Ok(flightplan)
     }
 }
@@ -0,0 +1,203 @@
+use std::fmt::Display;
+
+use crate::{
+    database::{self, AirportFrequencyRecord},
+    vatsim::VatsimData,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FrequencyKind {
+    Atis,
+    Delivery,
+    Ground,
+    Tower,
+    Departure,
+    Approach,
+    Centre,
+    Other,
+}
+
+impl FrequencyKind {
+    // Handles both the OurAirports frequency types and the suffix of VATSIM callsigns
+    pub fn from_type(kind: &str) -> Self {
+        match kind.to_uppercase().as_str() {
+            "ATIS" | "D-ATIS" => FrequencyKind::Atis,
+            "DEL" | "CLD" | "CLNC" | "CLR" | "DELIVERY" => FrequencyKind::Delivery,
+            "GND" | "GROUND" | "RMP" | "APRON" => FrequencyKind::Ground,
+            "TWR" | "TOWER" => FrequencyKind::Tower,
+            "DEP" | "DEPARTURE" => FrequencyKind::Departure,
+            "APP" | "APPROACH" | "ARR" | "DIR" => FrequencyKind::Approach,
+            "CTR" | "ACC" | "CNTR" | "CENTER" | "CENTRE" => FrequencyKind::Centre,
+            _ => FrequencyKind::Other,
+        }
+    }
+}
+
+impl Display for FrequencyKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            FrequencyKind::Atis => "ATIS",
+            FrequencyKind::Delivery => "Delivery",
+            FrequencyKind::Ground => "Ground",
+            FrequencyKind::Tower => "Tower",
+            FrequencyKind::Departure => "Departure",
+            FrequencyKind::Approach => "Approach",
+            FrequencyKind::Centre => "Centre",
+            FrequencyKind::Other => "Other",
+        };
+
+        write!(f, "{label}")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Frequency {
+    pub kind: FrequencyKind,
+    pub name: String,
+    pub frequency: String,
+    pub online: bool,
+}
+
+impl From<AirportFrequencyRecord> for Frequency {
+    fn from(record: AirportFrequencyRecord) -> Self {
+        Self {
+            kind: FrequencyKind::from_type(&record.frequency_type),
+            name: record.description,
+            frequency: normalize_frequency(&record.frequency_mhz),
+            online: false,
+        }
+    }
+}
+
+// Both sources are optional, the database might not have been downloaded and VATSIM might be
+// unreachable, so whatever is available is combined
+pub async fn load_frequencies(icao: &str, vatsim: Option<&VatsimData>) -> Vec<Frequency> {
+    let mut frequencies: Vec<Frequency> = match database::load_airport_frequencies(icao).await {
+        Ok(records) => records.into_iter().map(Frequency::from).collect(),
+        Err(e) => {
+            eprintln!("{e}");
+            Vec::new()
+        }
+    };
+
+    if let Some(vatsim) = vatsim {
+        merge_online_controllers(&mut frequencies, icao, vatsim);
+    }
+
+    frequencies.sort_by(|a, b| a.kind.cmp(&b.kind).then(a.frequency.cmp(&b.frequency)));
+    frequencies
+}
+
+pub fn merge_online_controllers(frequencies: &mut Vec<Frequency>, icao: &str, vatsim: &VatsimData) {
+    for controller in vatsim.online_controllers() {
+        let frequency = normalize_frequency(&controller.frequency);
+
+        // Centre controllers are not tied to an airport, so they are only highlighted when the
+        // database lists their frequency for this airport. Frequencies are reused far apart, so
+        // the centre has to be in the region of the airport too.
+        let matching = frequencies
+            .iter_mut()
+            .filter(|known| known.frequency == frequency)
+            .collect::<Vec<_>>();
+        let serves_airport = controller.serves_airport(icao);
+
+        if matching.is_empty() && serves_airport {
+            frequencies.push(Frequency {
+                kind: FrequencyKind::from_type(controller.position()),
+                name: controller.callsign.clone(),
+                frequency,
+                online: true,
+            });
+        } else if serves_airport
+            || (FrequencyKind::from_type(controller.position()) == FrequencyKind::Centre
+                && controller.in_region(icao))
+        {
+            for known in matching {
+                known.online = true;
+            }
+        }
+    }
+}
+
+fn normalize_frequency(frequency: &str) -> String {
+    frequency
+        .trim()
+        .parse::<f32>()
+        .map(|frequency| format!("{frequency:.3}"))
+        .unwrap_or_else(|_| frequency.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vatsim::Controller;
+
+    fn frequency(kind: FrequencyKind, name: &str, frequency: &str) -> Frequency {
+        Frequency {
+            kind,
+            name: name.to_string(),
+            frequency: frequency.to_string(),
+            online: false,
+        }
+    }
+
+    fn controller(callsign: &str, frequency: &str) -> Controller {
+        Controller {
+            callsign: callsign.to_string(),
+            frequency: frequency.to_string(),
+            atis_code: None,
+            text_atis: None,
+        }
+    }
+
+    #[test]
+    pub fn test_merge_online_controllers() {
+        let mut frequencies = vec![
+            frequency(FrequencyKind::Tower, "Kennedy Tower", "119.100"),
+            frequency(FrequencyKind::Ground, "Kennedy Ground", "121.900"),
+            frequency(FrequencyKind::Centre, "New York Center", "128.300"),
+        ];
+        let vatsim = VatsimData {
+            controllers: vec![
+                controller("JFK_TWR", "119.1"),
+                controller("JFK_DEL", "135.050"),
+                controller("KZNY_CTR", "128.300"),
+                // Another airport on a frequency of this one
+                controller("LGA_GND", "121.900"),
+            ],
+            atis: vec![controller("KJFK_ATIS", "128.725")],
+        };
+
+        merge_online_controllers(&mut frequencies, "KJFK", &vatsim);
+
+        let online: Vec<(&str, &str)> = frequencies
+            .iter()
+            .filter(|frequency| frequency.online)
+            .map(|frequency| (frequency.name.as_str(), frequency.frequency.as_str()))
+            .collect();
+        assert_eq!(
+            online,
+            vec![
+                ("Kennedy Tower", "119.100"),
+                ("New York Center", "128.300"),
+                ("JFK_DEL", "135.050"),
+                ("KJFK_ATIS", "128.725"),
+            ]
+        );
+        assert_eq!(frequencies[3].kind, FrequencyKind::Delivery);
+        assert_eq!(frequencies[4].kind, FrequencyKind::Atis);
+
+        // A centre on the same frequency elsewhere in the world
+        let mut frequencies = vec![frequency(
+            FrequencyKind::Centre,
+            "New York Center",
+            "128.300",
+        )];
+        let vatsim = VatsimData {
+            controllers: vec![controller("EDGG_CTR", "128.300")],
+            atis: Vec::new(),
+        };
+        merge_online_controllers(&mut frequencies, "KJFK", &vatsim);
+        assert!(!frequencies[0].online);
+    }
+}
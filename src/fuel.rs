@@ -0,0 +1,167 @@
+use chrono::TimeDelta;
+
+use crate::flightplan::FlightPlan;
+
+// Differences below this are rounding in the OFP
+const TOLERANCE_KG: f32 = 10.0;
+
+// The fuel figures of the OFP converted to kilograms
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FuelPlan {
+    pub taxi: f32,
+    pub trip: f32,
+    pub contingency: f32,
+    pub alternate: f32,
+    pub reserve: f32,
+    pub etops: f32,
+    pub extra: f32,
+    pub planned_block: f32,
+    pub max_tanks: Option<f32>,
+    // Per hour, used for the endurance
+    pub average_fuel_flow: Option<f32>,
+    // Additional trip burn for every tonne of extra weight, from the impacts of the OFP
+    pub burn_per_tonne: Option<f32>,
+}
+
+impl FuelPlan {
+    pub fn from_flight_plan(flightplan: &FlightPlan) -> Option<FuelPlan> {
+        let fuel = &flightplan.fuel;
+        let weight = |value: &str| flightplan.weight_kg(value);
+
+        Some(FuelPlan {
+            taxi: weight(&fuel.taxi).unwrap_or_default(),
+            trip: weight(&fuel.enroute_burn)?,
+            contingency: weight(&fuel.contingency).unwrap_or_default(),
+            alternate: weight(&fuel.alternate_burn).unwrap_or_default(),
+            reserve: weight(&fuel.reserve).unwrap_or_default(),
+            etops: weight(&fuel.etops).unwrap_or_default(),
+            extra: weight(&fuel.extra).unwrap_or_default(),
+            planned_block: weight(&fuel.plan_ramp)?,
+            max_tanks: weight(&fuel.max_tanks),
+            average_fuel_flow: weight(&fuel.avg_fuel_flow),
+            // SimBrief works out the burn for 1000 units of additional zero fuel weight
+            burn_per_tonne: flightplan
+                .impacts
+                .zfw_plus_1000
+                .as_ref()
+                .and_then(|impact| weight(&impact.burn_difference))
+                .map(|burn| {
+                    let tonne = flightplan.weight_kg("1000").unwrap_or(1000.0) / 1000.0;
+                    burn / tonne
+                }),
+        })
+    }
+
+    // The sum of the components plus what the pilot adds on top
+    pub fn block(&self, discretionary: f32) -> f32 {
+        self.taxi
+            + self.trip
+            + self.contingency
+            + self.alternate
+            + self.reserve
+            + self.etops
+            + self.extra
+            + discretionary
+    }
+
+    // Carrying the extra fuel costs fuel itself
+    pub fn extra_burn(&self, discretionary: f32) -> f32 {
+        self.burn_per_tonne.unwrap_or_default() * discretionary.max(0.0) / 1000.0
+    }
+
+    pub fn landing(&self, discretionary: f32) -> f32 {
+        self.block(discretionary) - self.taxi - self.trip - self.extra_burn(discretionary)
+    }
+
+    // How long the fuel on board at takeoff lasts at the average fuel flow of the flight
+    pub fn endurance(&self, discretionary: f32) -> Option<TimeDelta> {
+        let fuel_flow = self
+            .average_fuel_flow
+            .filter(|fuel_flow| *fuel_flow > 0.0)?;
+        let hours = (self.block(discretionary) - self.taxi) / fuel_flow;
+
+        Some(TimeDelta::minutes((hours * 60.0) as i64))
+    }
+
+    pub fn warnings(&self, discretionary: f32) -> Vec<String> {
+        let mut warnings = Vec::new();
+
+        let block = self.block(0.0);
+        if (block - self.planned_block).abs() > TOLERANCE_KG {
+            warnings.push(format!(
+                "The components add up to {block:.0} kg but the OFP block fuel is {:.0} kg",
+                self.planned_block
+            ));
+        }
+        if let Some(max_tanks) = self.max_tanks
+            && self.block(discretionary) > max_tanks + TOLERANCE_KG
+        {
+            warnings.push(format!(
+                "Block fuel of {:.0} kg exceeds the tank capacity of {max_tanks:.0} kg",
+                self.block(discretionary)
+            ));
+        }
+        // Arriving with less than the alternate and final reserve means a fuel emergency
+        let minimum = self.alternate + self.reserve;
+        if self.landing(discretionary) < minimum - TOLERANCE_KG {
+            warnings.push(format!(
+                "Landing fuel of {:.0} kg is below the {minimum:.0} kg for the alternate and reserve",
+                self.landing(discretionary)
+            ));
+        }
+
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils;
+
+    use super::*;
+
+    #[test]
+    pub fn test_fuel_plan() -> anyhow::Result<()> {
+        let flightplan: FlightPlan = serde_json::from_str(
+            r#"{
+                "origin": {"icao_code": "EDDF"},
+                "destination": {"icao_code": "EGLL"},
+                "general": {"max_tanks": "18700"},
+                "fuel": {
+                    "taxi": "200",
+                    "enroute_burn": "3500",
+                    "contingency": "175",
+                    "alternate_burn": "1200",
+                    "reserve": "1100",
+                    "etops": "0",
+                    "extra": "0",
+                    "plan_ramp": "6175",
+                    "max_tanks": "7000",
+                    "avg_fuel_flow": "2400"
+                },
+                "impacts": {"zfw_plus_1000": {"burn_difference": "40"}},
+                "params": {"units": "kgs"}
+            }"#,
+        )?;
+
+        let plan = FuelPlan::from_flight_plan(&flightplan).unwrap();
+        assert_eq!(plan.block(0.0), 6175.0);
+        assert_eq!(plan.landing(0.0), 2475.0);
+        assert!(plan.warnings(0.0).is_empty());
+
+        // 1000 kg more costs 40 kg of it on the way
+        assert_eq!(plan.extra_burn(1000.0), 40.0);
+        assert_eq!(plan.landing(1000.0), 3435.0);
+        assert_eq!(
+            plan.endurance(1000.0).map(utils::format_duration),
+            Some("2:54".to_string())
+        );
+        assert_eq!(
+            plan.warnings(1000.0),
+            vec!["Block fuel of 7175 kg exceeds the tank capacity of 7000 kg"]
+        );
+        assert_eq!(plan.warnings(-2000.0).len(), 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,892 @@
+use std::fmt::Display;
+
+use anyhow::{Result, bail};
+use chrono::{NaiveDate, NaiveTime, Utc};
+
+use crate::{
+    flightplan::{self, FlightPlan},
+    route::{Route, Waypoint, WaypointKind},
+    transition, utils,
+};
+
+// Only the A380 is in the super category, everything else is sorted by its maximum takeoff mass
+pub fn wake_category(icao_type: &str, max_takeoff_kg: Option<f32>) -> char {
+    match (icao_type, max_takeoff_kg) {
+        ("A388", _) => 'J',
+        (_, Some(weight)) if weight >= 136_000.0 => 'H',
+        (_, Some(weight)) if weight > 7_000.0 => 'M',
+        (_, Some(_)) => 'L',
+        (_, None) => 'M',
+    }
+}
+
+// Indicators of item 18 in the order ICAO lists them
+const OTHER_INDICATORS: [&str; 23] = [
+    "STS", "PBN", "NAV", "COM", "DAT", "SUR", "DEP", "DEST", "DOF", "REG", "EET", "SEL", "TYP",
+    "CODE", "DLE", "OPR", "ORGN", "PER", "ALTN", "RALT", "TALT", "RIF", "RMK",
+];
+// Endurance, persons on board, radios, survival equipment, jackets, dinghies, colour, remarks
+// and the pilot in command
+const SUPPLEMENTARY_INDICATORS: [&str; 9] = ["E", "P", "R", "S", "J", "D", "A", "N", "C"];
+const PBN_CODES: [&str; 24] = [
+    "A1", "B1", "B2", "B3", "B4", "B5", "B6", "C1", "C2", "C3", "C4", "D1", "D2", "D3", "D4", "L1",
+    "O1", "O2", "O3", "O4", "S1", "S2", "T1", "T2",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FplItem {
+    AircraftId,
+    FlightRules,
+    AircraftType,
+    Equipment,
+    Departure,
+    Route,
+    Destination,
+    Other,
+    Supplementary,
+}
+
+impl FplItem {
+    pub const ALL: [FplItem; 9] = [
+        FplItem::AircraftId,
+        FplItem::FlightRules,
+        FplItem::AircraftType,
+        FplItem::Equipment,
+        FplItem::Departure,
+        FplItem::Route,
+        FplItem::Destination,
+        FplItem::Other,
+        FplItem::Supplementary,
+    ];
+
+    pub fn number(&self) -> u8 {
+        match self {
+            FplItem::AircraftId => 7,
+            FplItem::FlightRules => 8,
+            FplItem::AircraftType => 9,
+            FplItem::Equipment => 10,
+            FplItem::Departure => 13,
+            FplItem::Route => 15,
+            FplItem::Destination => 16,
+            FplItem::Other => 18,
+            FplItem::Supplementary => 19,
+        }
+    }
+
+    // Items 18 and 19 are "0" and left out when there is nothing to tell
+    fn is_required(&self) -> bool {
+        !matches!(self, FplItem::Other | FplItem::Supplementary)
+    }
+}
+
+impl Display for FplItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            FplItem::AircraftId => "Aircraft identification",
+            FplItem::FlightRules => "Flight rules and type",
+            FplItem::AircraftType => "Aircraft type and wake",
+            FplItem::Equipment => "Equipment",
+            FplItem::Departure => "Departure and time",
+            FplItem::Route => "Speed, level and route",
+            FplItem::Destination => "Destination, EET and alternates",
+            FplItem::Other => "Other information",
+            FplItem::Supplementary => "Supplementary information",
+        };
+
+        write!(f, "{label}")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FplError {
+    Missing(FplItem),
+    Invalid(FplItem, String),
+}
+
+impl Display for FplError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FplError::Missing(item) => write!(f, "Item {} is missing", item.number()),
+            FplError::Invalid(item, reason) => write!(f, "Item {}: {reason}", item.number()),
+        }
+    }
+}
+
+// The items of the ICAO flight plan as they are written in the message, so each can be
+// corrected before filing
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IcaoFlightPlan {
+    pub aircraft_id: String,
+    pub flight_rules: String,
+    pub aircraft_type: String,
+    pub equipment: String,
+    pub departure: String,
+    pub route: String,
+    pub destination: String,
+    pub other: String,
+    pub supplementary: String,
+}
+
+impl IcaoFlightPlan {
+    pub fn from_flight_plan(flightplan: &FlightPlan) -> Self {
+        let route = Route::from_flight_plan(flightplan);
+        let information = &flightplan.flight_information;
+        let times = &flightplan.times;
+
+        let flight_type = if information.icao_airline.trim().is_empty() {
+            'G'
+        } else {
+            'S'
+        };
+        let wake = wake_category(
+            &flightplan.aircraft.icao_code,
+            flightplan.weight_kg(&flightplan.weights.max_tow),
+        );
+        let equipment = match utils::known(&flightplan.aircraft.equipment) {
+            "" => "S/C",
+            equipment => equipment,
+        };
+        let off_block = times
+            .scheduled_out()
+            .map(|time| time.format("%H%M").to_string())
+            .unwrap_or_default();
+        let elapsed = match (times.scheduled_off(), times.scheduled_on()) {
+            (Some(off), Some(on)) => format_duration((on - off).num_minutes()),
+            _ => String::new(),
+        };
+        let speed: u32 = information.cruise_tas.trim().parse().unwrap_or_default();
+        let transition_altitude = transition::transition_altitude(
+            &flightplan.origin.icao_code,
+            flightplan.origin.trans_alt.trim().parse().ok(),
+        );
+        let alternates: String = flightplan
+            .alternate
+            .iter()
+            .take(2)
+            .map(|alternate| format!(" {}", alternate.icao_code))
+            .collect();
+
+        Self {
+            aircraft_id: flightplan.callsign(),
+            flight_rules: format!("I{flight_type}"),
+            aircraft_type: format!("{}/{wake}", flightplan.aircraft.icao_code),
+            equipment: equipment.to_string(),
+            departure: format!("{}{off_block}", route.origin.ident),
+            route: format!(
+                "N{speed:04}{} {}",
+                format_level(route.cruise_altitude_ft, transition_altitude),
+                route.icao_route()
+            ),
+            destination: format!("{}{elapsed}{alternates}", route.destination.ident),
+            other: other_information(flightplan),
+            supplementary: supplementary_information(flightplan),
+        }
+    }
+
+    pub fn get(&self, item: FplItem) -> &str {
+        match item {
+            FplItem::AircraftId => &self.aircraft_id,
+            FplItem::FlightRules => &self.flight_rules,
+            FplItem::AircraftType => &self.aircraft_type,
+            FplItem::Equipment => &self.equipment,
+            FplItem::Departure => &self.departure,
+            FplItem::Route => &self.route,
+            FplItem::Destination => &self.destination,
+            FplItem::Other => &self.other,
+            FplItem::Supplementary => &self.supplementary,
+        }
+    }
+
+    // Flight plans are written in capitals, remarks included
+    pub fn set(&mut self, item: FplItem, value: String) {
+        let value = value.to_uppercase();
+
+        match item {
+            FplItem::AircraftId => self.aircraft_id = value,
+            FplItem::FlightRules => self.flight_rules = value,
+            FplItem::AircraftType => self.aircraft_type = value,
+            FplItem::Equipment => self.equipment = value,
+            FplItem::Departure => self.departure = value,
+            FplItem::Route => self.route = value,
+            FplItem::Destination => self.destination = value,
+            FplItem::Other => self.other = value,
+            FplItem::Supplementary => self.supplementary = value,
+        }
+    }
+
+    // The message as it is filed, like
+    // (FPL-DLH900-IS -A20N/M-SDE2E3FGHIRWY/LB1 -EDDF1100 -N0451F360 ... -EGLL0155 EGKK -DOF/251018)
+    // Item 19 stays with the operator and isn't part of it.
+    pub fn message(&self) -> String {
+        let item = |item: FplItem| self.get(item).trim();
+        let other = match item(FplItem::Other) {
+            "" => "0",
+            other => other,
+        };
+
+        format!(
+            "(FPL-{}-{}\n-{}-{}\n-{}\n-{}\n-{}\n-{other})",
+            item(FplItem::AircraftId),
+            item(FplItem::FlightRules),
+            item(FplItem::AircraftType),
+            item(FplItem::Equipment),
+            item(FplItem::Departure),
+            item(FplItem::Route),
+            item(FplItem::Destination),
+        )
+    }
+
+    pub fn validate(&self) -> Vec<FplError> {
+        let mut errors: Vec<FplError> = FplItem::ALL
+            .into_iter()
+            .filter(|item| item.is_required() && self.get(*item).trim().is_empty())
+            .map(FplError::Missing)
+            .collect();
+
+        let other = match self.other.trim() {
+            "0" => Vec::new(),
+            other => fields(other, &OTHER_INDICATORS),
+        };
+        let has = |indicators: &[&str]| {
+            other
+                .iter()
+                .any(|(indicator, _)| indicators.contains(&indicator.as_str()))
+        };
+        let mut invalid = |item: FplItem, reason: &str| {
+            errors.push(FplError::Invalid(item, reason.to_string()));
+        };
+
+        // A hyphen starts the next item and brackets end the message
+        for item in FplItem::ALL {
+            if self.get(item).contains(['-', '(', ')']) {
+                invalid(item, "must not contain hyphens or brackets");
+            }
+        }
+
+        let aircraft_id = self.aircraft_id.trim();
+        if !aircraft_id.is_empty()
+            && (aircraft_id.len() > 7 || !aircraft_id.chars().all(|c| c.is_ascii_alphanumeric()))
+        {
+            invalid(FplItem::AircraftId, "must be up to 7 letters and digits");
+        }
+
+        let flight_rules = self.flight_rules.trim();
+        if !flight_rules.is_empty() {
+            let mut chars = flight_rules.chars();
+            let valid = chars.next().is_some_and(|rules| "IVYZ".contains(rules))
+                && chars.next().is_some_and(|kind| "SNGMX".contains(kind))
+                && chars.next().is_none();
+            if !valid {
+                invalid(
+                    FplItem::FlightRules,
+                    "must be the rules I, V, Y or Z followed by the type S, N, G, M or X",
+                );
+            }
+        }
+
+        let aircraft_type = self
+            .aircraft_type
+            .trim()
+            .trim_start_matches(|c: char| c.is_ascii_digit());
+        if !aircraft_type.is_empty() {
+            match aircraft_type.split_once('/') {
+                Some((designator, wake))
+                    if is_type_designator(designator) && ["L", "M", "H", "J"].contains(&wake) =>
+                {
+                    if designator == "ZZZZ" && !has(&["TYP"]) {
+                        invalid(FplItem::Other, "TYP/ is required for aircraft type ZZZZ");
+                    }
+                }
+                _ => invalid(
+                    FplItem::AircraftType,
+                    "must be the type designator and the wake category L, M, H or J, like A20N/M",
+                ),
+            }
+        }
+
+        let equipment = self.equipment.trim();
+        if !equipment.is_empty() {
+            match equipment.split_once('/') {
+                Some((radio, surveillance)) => {
+                    if let Err(reason) = check_codes(
+                        radio,
+                        "ABCDFGHIKLORSTUVWXYZ",
+                        &[
+                            "E1", "E2", "E3", "J1", "J2", "J3", "J4", "J5", "J6", "J7", "M1", "M2",
+                            "M3", "P1", "P2", "P3", "P4", "P5", "P6", "P7", "P8", "P9",
+                        ],
+                    ) {
+                        invalid(FplItem::Equipment, &reason);
+                    }
+                    if let Err(reason) = check_codes(
+                        surveillance,
+                        "ACEHILPSX",
+                        &["B1", "B2", "D1", "G1", "U1", "U2", "V1", "V2"],
+                    ) {
+                        invalid(FplItem::Equipment, &reason);
+                    }
+                    if radio.contains('R') && !has(&["PBN"]) {
+                        invalid(FplItem::Other, "PBN/ is required for R in item 10");
+                    }
+                    if radio.contains('Z') && !has(&["NAV", "COM", "DAT"]) {
+                        invalid(
+                            FplItem::Other,
+                            "NAV/, COM/ or DAT/ is required for Z in item 10",
+                        );
+                    }
+                }
+                None => invalid(
+                    FplItem::Equipment,
+                    "must be the radio and surveillance equipment separated by /, like S/C",
+                ),
+            }
+        }
+
+        let departure = self.departure.trim();
+        if !departure.is_empty() {
+            let (airport, time) = split_ident(departure);
+            if !is_location_indicator(airport) || parse_hhmm(time).is_none() {
+                invalid(
+                    FplItem::Departure,
+                    "must be the airport and the off-block time, like EDDF1100",
+                );
+            } else if airport == "ZZZZ" && !has(&["DEP"]) {
+                invalid(FplItem::Other, "DEP/ is required for departure ZZZZ");
+            }
+        }
+
+        let mut route = self.route.split_whitespace();
+        if let Some(speed_level) = route.next() {
+            if parse_speed_level(speed_level).is_none() {
+                invalid(
+                    FplItem::Route,
+                    "must start with the cruising speed and level, like N0451F360",
+                );
+            }
+            if route.next().is_none() {
+                invalid(FplItem::Route, "has no route, DCT for a direct flight");
+            }
+        }
+
+        let mut destination = self.destination.split_whitespace();
+        if let Some(first) = destination.next() {
+            let (airport, elapsed) = split_ident(first);
+            if !is_location_indicator(airport) || !is_duration(elapsed) {
+                invalid(
+                    FplItem::Destination,
+                    "must be the airport and the total EET, like EGLL0130",
+                );
+            } else if airport == "ZZZZ" && !has(&["DEST"]) {
+                invalid(FplItem::Other, "DEST/ is required for destination ZZZZ");
+            }
+
+            let alternates: Vec<&str> = destination.collect();
+            if alternates.len() > 2 {
+                invalid(
+                    FplItem::Destination,
+                    "has more than 2 alternates, list the others in ALTN/",
+                );
+            }
+            if !alternates
+                .iter()
+                .all(|alternate| is_location_indicator(alternate))
+            {
+                invalid(
+                    FplItem::Destination,
+                    "alternates must be 4 letter location indicators",
+                );
+            }
+        }
+
+        for (indicator, value) in &other {
+            let valid = match indicator.as_str() {
+                "" => false,
+                "DOF" => NaiveDate::parse_from_str(value, "%y%m%d").is_ok(),
+                "REG" => value.len() <= 7 && value.chars().all(|c| c.is_ascii_alphanumeric()),
+                "SEL" => value.len() == 4 && value.chars().all(|c| c.is_ascii_uppercase()),
+                "PBN" => {
+                    value.len() % 2 == 0
+                        && value.len() <= 16
+                        && value.as_bytes().chunks(2).all(|code| {
+                            PBN_CODES.iter().any(|pbn_code| pbn_code.as_bytes() == code)
+                        })
+                }
+                _ => !value.is_empty(),
+            };
+            if !valid {
+                invalid(FplItem::Other, &field_error(indicator, value));
+            }
+        }
+        let supplementary = match self.supplementary.trim() {
+            "0" => Vec::new(),
+            supplementary => fields(supplementary, &SUPPLEMENTARY_INDICATORS),
+        };
+        for (indicator, value) in supplementary {
+            let valid = match indicator.as_str() {
+                "" => false,
+                "E" => is_duration(&value),
+                "P" => {
+                    value == "TBN"
+                        || (value.len() <= 3
+                            && value.parse::<u32>().is_ok_and(|persons| persons > 0))
+                }
+                _ => !value.is_empty(),
+            };
+            if !valid {
+                invalid(FplItem::Supplementary, &field_error(&indicator, &value));
+            }
+        }
+
+        errors
+    }
+}
+
+// SimBrief's item 18 completed with the date, registration and SELCAL and sorted like ICAO
+// lists the indicators
+fn other_information(flightplan: &FlightPlan) -> String {
+    let mut fields: Vec<(String, String)> =
+        fields(utils::known(&flightplan.atc.section18), &OTHER_INDICATORS)
+            .into_iter()
+            .filter(|(indicator, _)| !indicator.is_empty())
+            .collect();
+    let mut add = |indicator: &str, value: String| {
+        if !value.is_empty() && !fields.iter().any(|(existing, _)| existing == indicator) {
+            fields.push((indicator.to_string(), value));
+        }
+    };
+    add(
+        "DOF",
+        flightplan
+            .times
+            .scheduled_out()
+            .map(|time| time.format("%y%m%d").to_string())
+            .unwrap_or_default(),
+    );
+    add(
+        "REG",
+        utils::known(&flightplan.aircraft.registration).replace('-', ""),
+    );
+    add("SEL", utils::known(&flightplan.aircraft.selcal).to_string());
+
+    fields.sort_by_key(|(indicator, _)| {
+        OTHER_INDICATORS
+            .iter()
+            .position(|known| known == indicator)
+            .unwrap_or(OTHER_INDICATORS.len())
+    });
+    fields
+        .iter()
+        .map(|(indicator, value)| format!("{indicator}/{value}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// The endurance follows from the takeoff fuel and the average fuel flow. The crew isn't in the
+// OFP, so the persons on board are to be notified.
+fn supplementary_information(flightplan: &FlightPlan) -> String {
+    let fuel = &flightplan.fuel;
+    let endurance = match (
+        fuel.plan_takeoff.trim().parse::<f64>(),
+        fuel.avg_fuel_flow.trim().parse::<f64>(),
+    ) {
+        (Ok(takeoff), Ok(flow)) if flow > 0.0 => {
+            let minutes = (takeoff / flow * 60.0).floor() as i64;
+            format!("E/{} ", format_duration(minutes))
+        }
+        _ => String::new(),
+    };
+
+    format!("{endurance}P/TBN")
+}
+
+// Fields like DOF/251018 REG/DAINA RMK/TCAS EQUIPPED, text not following an indicator has an
+// empty one
+fn fields(text: &str, indicators: &[&str]) -> Vec<(String, String)> {
+    let mut fields: Vec<(String, String)> = Vec::new();
+
+    for word in text.split_whitespace() {
+        let field = word
+            .split_once('/')
+            .filter(|(indicator, _)| indicators.contains(indicator));
+        match (field, fields.last_mut()) {
+            (Some((indicator, value)), _) => {
+                fields.push((indicator.to_string(), value.to_string()))
+            }
+            (None, Some((_, value))) => {
+                value.push(' ');
+                value.push_str(word);
+            }
+            (None, None) => fields.push((String::new(), word.to_string())),
+        }
+    }
+
+    fields
+}
+
+fn field_error(indicator: &str, value: &str) -> String {
+    if indicator.is_empty() {
+        format!("{value} doesn't start with an indicator like DOF/")
+    } else {
+        format!("{indicator}/{value} is not valid")
+    }
+}
+
+// Codes are a letter, some followed by a digit, like SDE2E3FGHIJ1RWY
+fn check_codes(value: &str, letters: &str, codes_with_digit: &[&str]) -> Result<(), String> {
+    if value == "N" {
+        return Ok(());
+    }
+    if value.is_empty() {
+        return Err("has no equipment, N for none".to_string());
+    }
+
+    let mut rest = value;
+    while !rest.is_empty() {
+        if let Some(code) = codes_with_digit.iter().find(|code| rest.starts_with(*code)) {
+            rest = &rest[code.len()..];
+        } else if let Some(letter) = rest.chars().next().filter(|c| letters.contains(*c)) {
+            rest = &rest[letter.len_utf8()..];
+        } else {
+            return Err(format!("{value} has the unknown code {rest}"));
+        }
+    }
+
+    Ok(())
+}
+
+fn is_type_designator(designator: &str) -> bool {
+    (2..=4).contains(&designator.len())
+        && designator.starts_with(|c: char| c.is_ascii_uppercase())
+        && designator
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+}
+
+fn is_location_indicator(ident: &str) -> bool {
+    ident.len() == 4 && ident.chars().all(|c| c.is_ascii_uppercase())
+}
+
+// Hours may go past a day, like 2830 for 28 hours and 30 minutes
+fn is_duration(value: &str) -> bool {
+    value.len() == 4
+        && value.chars().all(|c| c.is_ascii_digit())
+        && value[2..].parse::<u32>().is_ok_and(|minutes| minutes < 60)
+}
+
+// Below the transition altitude the level is an altitude, like A045, above it a flight level
+fn format_level(altitude_ft: u32, transition_altitude: Option<u32>) -> String {
+    match transition_altitude {
+        Some(transition_altitude) if altitude_ft < transition_altitude => {
+            format!("A{:03}", altitude_ft / 100)
+        }
+        _ => format!("F{:03}", altitude_ft / 100),
+    }
+}
+
+fn format_duration(minutes: i64) -> String {
+    format!("{:02}{:02}", minutes / 60, minutes % 60)
+}
+
+// Reads the message of IcaoFlightPlan back into a flight plan. Without a navigation database the
+// waypoints of the route have no positions, except the ones given as coordinates.
+pub fn parse(message: &str) -> Result<FlightPlan> {
+    let message = message.trim().trim_start_matches('(').trim_end_matches(')');
+    let items: Vec<&str> = message.split('-').map(str::trim).collect();
+    let [
+        kind,
+        callsign,
+        _rules,
+        aircraft,
+        equipment,
+        departure,
+        route,
+        destination,
+        other @ ..,
+    ] = items.as_slice()
+    else {
+        bail!("the message is missing items");
+    };
+    if !kind.starts_with("FPL") {
+        bail!("{kind} is not a flight plan message");
+    }
+    let other = other.join("-");
+
+    let mut route_items = route.split_whitespace();
+    let Some((cruise_tas, cruise_altitude_ft)) = route_items.next().and_then(parse_speed_level)
+    else {
+        bail!("the route has no cruising speed and level");
+    };
+    let mut route = parse_route(route_items);
+    let (departure, off_block) = split_ident(departure);
+    let mut destination_items = destination.split_whitespace();
+    let (destination, elapsed) = split_ident(destination_items.next().unwrap_or_default());
+    route.origin = airport(departure);
+    route.destination = airport(destination);
+    route.cruise_altitude_ft = cruise_altitude_ft;
+
+    let mut flightplan = route.to_flight_plan();
+    flightplan.alternate = destination_items
+        .map(|alternate| flightplan::Airport {
+            icao_code: alternate.to_string(),
+            ..Default::default()
+        })
+        .collect();
+
+    // Airline callsigns are the ICAO code of the airline and the flight number
+    let information = &mut flightplan.flight_information;
+    match callsign.split_at_checked(3) {
+        Some((airline, number))
+            if airline.chars().all(|c| c.is_ascii_alphabetic())
+                && number.starts_with(|c: char| c.is_ascii_digit()) =>
+        {
+            information.icao_airline = airline.to_string();
+            information.flight_number = number.to_string();
+        }
+        _ => information.flight_number = callsign.to_string(),
+    }
+    information.cruise_tas = cruise_tas;
+
+    let aircraft_type = aircraft.split('/').next().unwrap_or_default();
+    flightplan.aircraft.icao_code = aircraft_type
+        .trim_start_matches(|c: char| c.is_ascii_digit())
+        .to_string();
+    flightplan.aircraft.equipment = equipment.to_string();
+    flightplan.aircraft.registration = field(&other, "REG").unwrap_or_default().to_string();
+    if other != "0" {
+        flightplan.atc.section18 = other.clone();
+    }
+
+    // Taxi times aren't part of the message, off and on block are the takeoff and landing
+    let date = field(&other, "DOF")
+        .and_then(|date| NaiveDate::parse_from_str(date, "%y%m%d").ok())
+        .unwrap_or_else(|| Utc::now().date_naive());
+    if let Some(time) = parse_hhmm(off_block) {
+        let off = date.and_time(time).and_utc();
+        let on = parse_hhmm(elapsed)
+            .map(|elapsed| off + (elapsed - NaiveTime::MIN))
+            .unwrap_or(off);
+        let times = &mut flightplan.times;
+        times.sched_out = off.timestamp().to_string();
+        times.sched_off = off.timestamp().to_string();
+        times.sched_on = on.timestamp().to_string();
+        times.sched_in = on.timestamp().to_string();
+    }
+
+    Ok(flightplan)
+}
+
+// Like N0451F360, the speed in knots, Mach or km/h and the level in feet or metres
+fn parse_speed_level(item: &str) -> Option<(String, u32)> {
+    let (speed, level) = match item.chars().next()? {
+        'N' | 'K' => item.split_at_checked(5)?,
+        'M' => item.split_at_checked(4)?,
+        _ => return None,
+    };
+    let speed_value: f64 = speed.get(1..)?.parse().ok()?;
+    let cruise_tas = match speed.chars().next()? {
+        'N' => format!("{speed_value:.0}"),
+        'K' => format!("{:.0}", speed_value / 1.852),
+        _ => String::new(),
+    };
+
+    let cruise_altitude_ft = if level == "VFR" {
+        0.0
+    } else {
+        let (unit, digits) = level.split_at_checked(1)?;
+        let value: f64 = digits.parse().ok()?;
+        match (unit, digits.len()) {
+            ("F" | "A", 3) => value * 100.0,
+            ("S" | "M", 4) => value * 10.0 / 0.3048,
+            _ => return None,
+        }
+    };
+
+    Some((cruise_tas, cruise_altitude_ft.round() as u32))
+}
+
+// Points and airways alternate, with the SID first and the STAR last when there are any
+fn parse_route<'a>(items: impl Iterator<Item = &'a str>) -> Route {
+    let mut route = Route::default();
+    // Changes of speed and level are appended to the point, like SOBRA/N0450F380
+    let mut items: Vec<&str> = items
+        .map(|item| item.split('/').next().unwrap_or_default())
+        .collect();
+
+    if items.first().is_some_and(|item| is_procedure(item)) {
+        route.sid = items.remove(0).to_string();
+    }
+    if items.last().is_some_and(|item| is_procedure(item)) {
+        route.star = items.pop().unwrap_or_default().to_string();
+    }
+
+    let mut airway = String::new();
+    let mut expect_point = true;
+    for item in items {
+        if item == "DCT" {
+            airway = item.to_string();
+            expect_point = true;
+        } else if expect_point {
+            let mut waypoint = Waypoint {
+                ident: item.to_string(),
+                airway: std::mem::take(&mut airway),
+                ..Default::default()
+            };
+            if let Some((latitude, longitude)) = parse_coordinates(item) {
+                waypoint.kind = WaypointKind::Coordinates;
+                waypoint.latitude = latitude;
+                waypoint.longitude = longitude;
+            }
+            route.waypoints.push(waypoint);
+            expect_point = false;
+        } else {
+            airway = item.to_string();
+            expect_point = true;
+        }
+    }
+
+    route
+}
+
+// Procedures are named after a point with a number and often a letter, like SOBRA3G or KONA1H
+fn is_procedure(item: &str) -> bool {
+    let letters = item.chars().take_while(char::is_ascii_alphabetic).count();
+    let rest = &item[letters..];
+    let mut rest_chars = rest.chars();
+
+    letters >= 3
+        && rest_chars.next().is_some_and(|c| c.is_ascii_digit())
+        && rest_chars.next().is_none_or(|c| c.is_ascii_alphabetic())
+        && rest_chars.next().is_none()
+}
+
+// Coordinates in whole degrees like 50N008E or with minutes like 5015N00754E
+fn parse_coordinates(item: &str) -> Option<(f64, f64)> {
+    let latitude_length = item.find(['N', 'S'])?;
+    let (latitude, longitude) = item.split_at(latitude_length + 1);
+    let parse = |value: &str, degree_digits: usize, negative: char| {
+        let (number, hemisphere) = value.split_at_checked(value.len().checked_sub(1)?)?;
+        if !number.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let value = match number.len().checked_sub(degree_digits)? {
+            0 => number.parse::<f64>().ok()?,
+            2 => {
+                let (degrees, minutes) = number.split_at(degree_digits);
+                degrees.parse::<f64>().ok()? + minutes.parse::<f64>().ok()? / 60.0
+            }
+            _ => return None,
+        };
+        let sign = if hemisphere.starts_with(negative) {
+            -1.0
+        } else {
+            1.0
+        };
+        Some(sign * value)
+    };
+
+    if !longitude.ends_with(['E', 'W']) {
+        return None;
+    }
+    Some((parse(latitude, 2, 'S')?, parse(longitude, 3, 'W')?))
+}
+
+fn split_ident(item: &str) -> (&str, &str) {
+    item.split_at_checked(4).unwrap_or((item, ""))
+}
+
+fn airport(ident: &str) -> Waypoint {
+    Waypoint {
+        ident: ident.to_string(),
+        kind: WaypointKind::Airport,
+        ..Default::default()
+    }
+}
+
+fn parse_hhmm(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value, "%H%M").ok()
+}
+
+// Item 18 is made of fields like DOF/251018 REG/DAINA
+fn field<'a>(other: &'a str, name: &str) -> Option<&'a str> {
+    other
+        .split_whitespace()
+        .find_map(|item| item.strip_prefix(name)?.strip_prefix('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_validate() -> anyhow::Result<()> {
+        let flightplan: FlightPlan =
+            serde_json::from_str(include_str!("../tests/fixtures/EDDFEGLL.json"))?;
+        let mut plan = IcaoFlightPlan::from_flight_plan(&flightplan);
+
+        assert_eq!(plan.aircraft_type, "A20N/M");
+        assert!(plan.route.starts_with("N0451F360 "));
+        assert_eq!(plan.supplementary, "E/0220 P/TBN");
+        assert!(plan.validate().is_empty());
+
+        plan.set(FplItem::Other, "dof/251018 reg/daina".to_string());
+        assert_eq!(plan.other, "DOF/251018 REG/DAINA");
+        assert_eq!(
+            plan.validate(),
+            vec![FplError::Invalid(
+                FplItem::Other,
+                "PBN/ is required for R in item 10".to_string()
+            )]
+        );
+
+        plan.set(FplItem::Equipment, "SDE2E3FGHIJ1WY/LB1".to_string());
+        plan.set(FplItem::Departure, "EDDF2460".to_string());
+        plan.set(FplItem::Route, "N0451F36 DCT".to_string());
+        plan.set(FplItem::Supplementary, "E/0220 P/0".to_string());
+        let errors: Vec<FplItem> = plan
+            .validate()
+            .into_iter()
+            .map(|error| match error {
+                FplError::Missing(item) | FplError::Invalid(item, _) => item,
+            })
+            .collect();
+        assert_eq!(
+            errors,
+            vec![FplItem::Departure, FplItem::Route, FplItem::Supplementary]
+        );
+
+        plan.set(FplItem::Equipment, "SQ/LB1".to_string());
+        plan.set(FplItem::AircraftId, String::new());
+        assert_eq!(
+            plan.validate()[..2],
+            [
+                FplError::Missing(FplItem::AircraftId),
+                FplError::Invalid(FplItem::Equipment, "SQ has the unknown code Q".to_string())
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_cruising_level() -> anyhow::Result<()> {
+        let mut flightplan: FlightPlan =
+            serde_json::from_str(include_str!("../tests/fixtures/EDDFEGLL.json"))?;
+
+        // Germany has a transition altitude of 5000 ft
+        flightplan.flight_information.initial_altitude = "4500".to_string();
+        let plan = IcaoFlightPlan::from_flight_plan(&flightplan);
+        assert!(plan.route.starts_with("N0451A045 "));
+        assert!(
+            !plan
+                .validate()
+                .iter()
+                .any(|error| matches!(error, FplError::Invalid(FplItem::Route, _)))
+        );
+
+        flightplan.origin.trans_alt = "4000".to_string();
+        let plan = IcaoFlightPlan::from_flight_plan(&flightplan);
+        assert!(plan.route.starts_with("N0451F045 "));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,209 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+
+use crate::{
+    briefing::Briefing,
+    clearance::ClearanceField,
+    pdf::{Font, PdfDocument, PdfPage},
+    utils,
+};
+
+const MARGIN: f32 = 24.0;
+const TITLE_SIZE: f32 = 12.0;
+const HEADING_SIZE: f32 = 10.0;
+const TEXT_SIZE: f32 = 8.0;
+const LINE_HEIGHT: f32 = 10.0;
+// Courier glyphs are all 600/1000 em wide, which makes wrapping exact
+const COURIER_WIDTH: f32 = 0.6;
+const LABEL_COLUMNS: usize = 14;
+const CLEARANCE_BOX_HEIGHT: f32 = 28.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageSize {
+    A5,
+    Letter,
+}
+
+impl PageSize {
+    pub const ALL: [PageSize; 2] = [PageSize::A5, PageSize::Letter];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PageSize::A5 => "A5",
+            PageSize::Letter => "Letter",
+        }
+    }
+
+    // Width and height in points
+    fn dimensions(&self) -> (f32, f32) {
+        match self {
+            PageSize::A5 => (419.53, 595.28),
+            PageSize::Letter => (612.0, 792.0),
+        }
+    }
+}
+
+// Writes from top to bottom and starts a new page whenever the next element doesn't fit
+struct Layout {
+    size: PageSize,
+    document: PdfDocument,
+    page: PdfPage,
+    y: f32,
+    page_number: usize,
+}
+
+impl Layout {
+    fn new(size: PageSize) -> Self {
+        let (width, height) = size.dimensions();
+
+        let mut layout = Self {
+            size,
+            document: PdfDocument::new(),
+            page: PdfPage::new(width, height),
+            y: height - MARGIN,
+            page_number: 1,
+        };
+        layout.footer();
+        layout
+    }
+
+    fn width(&self) -> f32 {
+        self.page.width - 2.0 * MARGIN
+    }
+
+    fn columns(&self) -> usize {
+        (self.width() / (TEXT_SIZE * COURIER_WIDTH)) as usize
+    }
+
+    fn footer(&mut self) {
+        self.page.text(
+            MARGIN,
+            MARGIN / 2.0,
+            Font::Regular,
+            TEXT_SIZE,
+            &format!("Page {}", self.page_number),
+        );
+    }
+
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height >= MARGIN {
+            return;
+        }
+
+        let (width, height) = self.size.dimensions();
+        let page = std::mem::replace(&mut self.page, PdfPage::new(width, height));
+        self.document.add_page(page);
+        self.y = height - MARGIN;
+        self.page_number += 1;
+        self.footer();
+    }
+
+    fn title(&mut self, title: &str) {
+        self.ensure_space(TITLE_SIZE);
+        self.y -= TITLE_SIZE;
+        self.page
+            .text(MARGIN, self.y, Font::Bold, TITLE_SIZE, title);
+        self.y -= LINE_HEIGHT / 2.0;
+    }
+
+    fn heading(&mut self, heading: &str) {
+        // Keep at least the heading and one line of content together
+        self.ensure_space(HEADING_SIZE + LINE_HEIGHT * 2.0);
+        self.y -= LINE_HEIGHT;
+        self.y -= HEADING_SIZE;
+        self.page
+            .text(MARGIN, self.y, Font::Bold, HEADING_SIZE, heading);
+        self.y -= 3.0;
+        self.page
+            .line(MARGIN, self.y, self.page.width - MARGIN, self.y);
+    }
+
+    fn line(&mut self, x: f32, font: Font, text: &str) {
+        self.ensure_space(LINE_HEIGHT);
+        self.y -= LINE_HEIGHT;
+        self.page.text(x, self.y, font, TEXT_SIZE, text);
+    }
+
+    fn field(&mut self, label: &str, value: &str) {
+        let indent = MARGIN + LABEL_COLUMNS as f32 * TEXT_SIZE * COURIER_WIDTH;
+        let lines = utils::wrap_text(value, self.columns() - LABEL_COLUMNS);
+
+        for (index, line) in lines.iter().enumerate() {
+            self.line(indent, Font::Monospace, line);
+            if index == 0 {
+                self.page
+                    .text(MARGIN, self.y, Font::Regular, TEXT_SIZE, label);
+            }
+        }
+    }
+
+    fn block(&mut self, title: &str, text: &str) {
+        self.y -= LINE_HEIGHT / 2.0;
+        self.line(MARGIN, Font::Bold, title);
+        // Lines that fit are kept as they are so aligned columns survive
+        for paragraph in text.lines() {
+            if paragraph.chars().count() <= self.columns() {
+                self.line(MARGIN, Font::Monospace, paragraph);
+                continue;
+            }
+            for line in utils::wrap_text(paragraph, self.columns()) {
+                self.line(MARGIN, Font::Monospace, &line);
+            }
+        }
+    }
+
+    // Empty boxes to write the clearance into while it's read out
+    fn clearance_boxes(&mut self) {
+        self.heading("Clearance");
+        self.y -= LINE_HEIGHT / 2.0;
+
+        for field in ClearanceField::ALL {
+            self.ensure_space(CLEARANCE_BOX_HEIGHT + 4.0);
+            self.y -= CLEARANCE_BOX_HEIGHT;
+            self.page
+                .rectangle(MARGIN, self.y, self.width(), CLEARANCE_BOX_HEIGHT);
+            self.page.text(
+                MARGIN + 3.0,
+                self.y + CLEARANCE_BOX_HEIGHT - TEXT_SIZE - 2.0,
+                Font::Regular,
+                TEXT_SIZE - 1.0,
+                &field.to_string(),
+            );
+            self.y -= 4.0;
+        }
+    }
+
+    fn finish(mut self) -> PdfDocument {
+        self.document.add_page(self.page);
+        self.document
+    }
+}
+
+pub fn render(briefing: &Briefing, size: PageSize) -> Vec<u8> {
+    let mut layout = Layout::new(size);
+    layout.title(&briefing.title());
+
+    for section in briefing.sections() {
+        layout.heading(&section.title);
+        for (label, value) in &section.fields {
+            layout.field(label, value);
+        }
+        for (title, text) in &section.blocks {
+            layout.block(title, text);
+        }
+    }
+
+    layout.clearance_boxes();
+    layout.finish().to_bytes()
+}
+
+pub async fn save(contents: Vec<u8>, directory: PathBuf) -> Result<PathBuf> {
+    let path = directory.join("kneeboard.pdf");
+
+    tokio::fs::write(&path, contents)
+        .await
+        .with_context(|| format!("failed to write {}", path.display()))?;
+
+    Ok(path)
+}
@@ -1,6 +1,35 @@
+pub mod acars;
+pub mod aircraft;
 pub mod airport;
 pub mod app;
+pub mod briefing;
+pub mod cache;
+pub mod chart;
+pub mod clearance;
+pub mod cli;
+pub mod database;
 pub mod flightplan;
+pub mod frequency;
+pub mod fuel;
+pub mod icao_fpl;
+pub mod kneeboard;
+pub mod logbook;
+pub mod notam;
+pub mod ofp;
+pub mod pdf;
+pub mod performance;
+pub mod plan_file;
+pub mod route;
+pub mod runway_condition;
+pub mod schedule;
+pub mod settings;
 pub mod styles;
+pub mod sun;
+pub mod taf;
+pub mod transition;
+pub mod trend;
 pub mod utils;
+pub mod vatsim;
+pub mod watchlist;
 pub mod weather;
+pub mod wind;
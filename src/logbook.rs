@@ -0,0 +1,392 @@
+use std::{cmp::Reverse, path::PathBuf};
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+
+use crate::{flightplan::FlightPlan, schedule::BlockTimes, sun::Daylight, utils};
+
+// One JSON object per line, so a flight is logged by appending to the file
+pub const LOGBOOK_PATH: &str = "logbook.jsonl";
+
+const CSV_HEADER: [&str; 17] = [
+    "Date",
+    "Callsign",
+    "Aircraft",
+    "Registration",
+    "From",
+    "To",
+    "Route",
+    "Scheduled Out",
+    "Scheduled In",
+    "Off Block",
+    "Takeoff",
+    "Landing",
+    "On Block",
+    "Block Time",
+    "Night Time",
+    "Fuel Used",
+    "Landings Day/Night",
+];
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct LogbookEntry {
+    pub date: NaiveDate,
+    pub callsign: String,
+    pub aircraft_type: String,
+    pub registration: String,
+    pub origin: String,
+    pub destination: String,
+    pub route: String,
+    pub scheduled_off_block: Option<DateTime<Utc>>,
+    pub scheduled_on_block: Option<DateTime<Utc>>,
+    pub off_block: DateTime<Utc>,
+    pub takeoff: Option<DateTime<Utc>>,
+    pub landing: Option<DateTime<Utc>>,
+    pub on_block: DateTime<Utc>,
+    pub night_minutes: i64,
+    // Kilograms
+    pub fuel_used: Option<f32>,
+    pub day_landings: u32,
+    pub night_landings: u32,
+}
+
+impl LogbookEntry {
+    // The positions of the airports are needed for the night time, without them the whole
+    // flight is logged as day
+    pub fn from_flight(
+        flightplan: &FlightPlan,
+        actual: BlockTimes,
+        fuel_used: Option<f32>,
+        origin: Option<(f64, f64)>,
+        destination: Option<(f64, f64)>,
+    ) -> Result<LogbookEntry> {
+        let off_block = actual.off_block.context("the off block time is missing")?;
+        let on_block = actual.on_block.context("the on block time is missing")?;
+        if on_block <= off_block {
+            bail!("the on block time has to be after the off block time");
+        }
+
+        let night_minutes = match (origin, destination) {
+            (Some(origin), Some(destination)) => night_minutes(
+                off_block,
+                on_block,
+                actual.takeoff,
+                actual.landing,
+                origin,
+                destination,
+            ),
+            _ => 0,
+        };
+        let landing_at_night = destination.is_some_and(|(latitude, longitude)| {
+            Daylight::at(actual.landing.unwrap_or(on_block), latitude, longitude).is_night()
+        });
+
+        Ok(LogbookEntry {
+            date: off_block.date_naive(),
+            callsign: flightplan.callsign(),
+            aircraft_type: flightplan.aircraft.icao_code.clone(),
+            registration: flightplan.aircraft.registration.clone(),
+            origin: flightplan.origin.icao_code.clone(),
+            destination: flightplan.destination.icao_code.clone(),
+            route: flightplan.flight_information.route_navigraph.clone(),
+            scheduled_off_block: flightplan.times.scheduled_out(),
+            scheduled_on_block: flightplan.times.scheduled_in(),
+            off_block,
+            takeoff: actual.takeoff,
+            landing: actual.landing,
+            on_block,
+            night_minutes,
+            fuel_used,
+            day_landings: u32::from(!landing_at_night),
+            night_landings: u32::from(landing_at_night),
+        })
+    }
+
+    // Logging the same flight twice would count its times twice
+    pub fn is_same_flight(&self, other: &LogbookEntry) -> bool {
+        self.callsign == other.callsign && self.off_block == other.off_block
+    }
+
+    pub fn block_time(&self) -> TimeDelta {
+        self.on_block - self.off_block
+    }
+
+    pub fn air_time(&self) -> Option<TimeDelta> {
+        Some(self.landing? - self.takeoff?)
+    }
+
+    pub fn night_time(&self) -> TimeDelta {
+        TimeDelta::minutes(self.night_minutes)
+    }
+
+    // Every word of the query has to be found in one of the fields, ignoring case
+    pub fn matches(&self, query: &str) -> bool {
+        let fields = [
+            self.date.to_string(),
+            self.callsign.clone(),
+            self.aircraft_type.clone(),
+            self.registration.clone(),
+            self.origin.clone(),
+            self.destination.clone(),
+            self.route.clone(),
+        ]
+        .join(" ")
+        .to_lowercase();
+
+        query
+            .to_lowercase()
+            .split_whitespace()
+            .all(|word| fields.contains(word))
+    }
+
+    fn csv_fields(&self) -> Vec<String> {
+        let format_time = |time: Option<DateTime<Utc>>| {
+            time.map(|time| time.format("%Y-%m-%d %H:%MZ").to_string())
+                .unwrap_or_default()
+        };
+
+        vec![
+            self.date.to_string(),
+            self.callsign.clone(),
+            self.aircraft_type.clone(),
+            self.registration.clone(),
+            self.origin.clone(),
+            self.destination.clone(),
+            self.route.clone(),
+            format_time(self.scheduled_off_block),
+            format_time(self.scheduled_on_block),
+            format_time(Some(self.off_block)),
+            format_time(self.takeoff),
+            format_time(self.landing),
+            format_time(Some(self.on_block)),
+            utils::format_duration(self.block_time()),
+            utils::format_duration(self.night_time()),
+            self.fuel_used
+                .map(|fuel| format!("{fuel:.0}"))
+                .unwrap_or_default(),
+            format!("{}/{}", self.day_landings, self.night_landings),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Totals {
+    pub flights: usize,
+    pub block_time: TimeDelta,
+    pub air_time: TimeDelta,
+    pub night_time: TimeDelta,
+    pub fuel_used: f32,
+    pub day_landings: u32,
+    pub night_landings: u32,
+}
+
+impl Totals {
+    pub fn of<'a>(entries: impl IntoIterator<Item = &'a LogbookEntry>) -> Totals {
+        entries
+            .into_iter()
+            .fold(Totals::default(), |totals, entry| Totals {
+                flights: totals.flights + 1,
+                block_time: totals.block_time + entry.block_time(),
+                air_time: totals.air_time + entry.air_time().unwrap_or_default(),
+                night_time: totals.night_time + entry.night_time(),
+                fuel_used: totals.fuel_used + entry.fuel_used.unwrap_or_default(),
+                day_landings: totals.day_landings + entry.day_landings,
+                night_landings: totals.night_landings + entry.night_landings,
+            })
+    }
+}
+
+// Newest flights first
+pub fn search<'a>(entries: &'a [LogbookEntry], query: &str) -> Vec<&'a LogbookEntry> {
+    let mut found: Vec<&LogbookEntry> = entries
+        .iter()
+        .filter(|entry| entry.matches(query))
+        .collect();
+    found.sort_by_key(|entry| Reverse(entry.off_block));
+
+    found
+}
+
+pub fn to_csv<'a>(entries: impl IntoIterator<Item = &'a LogbookEntry>) -> String {
+    let mut lines = vec![CSV_HEADER.join(",")];
+    lines.extend(entries.into_iter().map(|entry| {
+        entry
+            .csv_fields()
+            .iter()
+            .map(|field| escape_csv(field))
+            .collect::<Vec<_>>()
+            .join(",")
+    }));
+
+    lines.join("\n") + "\n"
+}
+
+// A missing logbook is an empty one. Lines that can't be read are skipped and returned as
+// errors, so a single broken line doesn't hide every other flight.
+pub async fn load(path: PathBuf) -> Result<(Vec<LogbookEntry>, Vec<String>)> {
+    let contents = match tokio::fs::read_to_string(&path).await {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Default::default()),
+        Err(e) => return Err(e).with_context(|| format!("failed to read {}", path.display())),
+    };
+
+    Ok(parse(&contents))
+}
+
+pub async fn append(path: PathBuf, entry: LogbookEntry) -> Result<LogbookEntry> {
+    let line = serde_json::to_string(&entry)? + "\n";
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .await
+        .with_context(|| format!("failed to open {}", path.display()))?;
+    file.write_all(line.as_bytes())
+        .await
+        .with_context(|| format!("failed to write {}", path.display()))?;
+
+    Ok(entry)
+}
+
+pub async fn export_csv(contents: String, directory: PathBuf) -> Result<PathBuf> {
+    let path = directory.join("logbook.csv");
+
+    tokio::fs::write(&path, contents)
+        .await
+        .with_context(|| format!("failed to write {}", path.display()))?;
+
+    Ok(path)
+}
+
+fn parse(contents: &str) -> (Vec<LogbookEntry>, Vec<String>) {
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+
+    for (index, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(line) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => errors.push(format!("invalid entry on line {}: {e}", index + 1)),
+        }
+    }
+
+    (entries, errors)
+}
+
+fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+// Checks every minute of the block time. The aircraft is at the origin until takeoff, at the
+// destination after landing and moves in a straight line in between.
+fn night_minutes(
+    off_block: DateTime<Utc>,
+    on_block: DateTime<Utc>,
+    takeoff: Option<DateTime<Utc>>,
+    landing: Option<DateTime<Utc>>,
+    origin: (f64, f64),
+    destination: (f64, f64),
+) -> i64 {
+    let takeoff = takeoff.unwrap_or(off_block);
+    let landing = landing.unwrap_or(on_block).max(takeoff);
+    let flight_minutes = (landing - takeoff).num_minutes().max(1) as f64;
+
+    (0..(on_block - off_block).num_minutes())
+        .map(|minute| off_block + TimeDelta::minutes(minute))
+        .filter(|time| {
+            let progress =
+                ((*time - takeoff).num_minutes() as f64 / flight_minutes).clamp(0.0, 1.0);
+            let latitude = origin.0 + (destination.0 - origin.0) * progress;
+            let longitude = origin.1 + (destination.1 - origin.1) * progress;
+
+            Daylight::at(*time, latitude, longitude).is_night()
+        })
+        .count() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_logbook() -> anyhow::Result<()> {
+        let flightplan: FlightPlan = serde_json::from_str(
+            r#"{
+                "origin": {"icao_code": "EDDF"},
+                "destination": {"icao_code": "EGLL"},
+                "general": {"icao_airline": "DLH", "flight_number": "900", "route_navigraph": "SOBRA Y6 ROKIL"},
+                "fuel": {},
+                "aircraft": {"icaocode": "A20N", "reg": "D-AINA"},
+                "times": {"sched_out": "1766329800", "sched_in": "1766337300"}
+            }"#,
+        )?;
+        let time = |time: &str| -> anyhow::Result<Option<DateTime<Utc>>> {
+            Ok(Some(DateTime::parse_from_rfc3339(time)?.to_utc()))
+        };
+
+        // An evening flight in December, it gets dark on the way
+        let actual = BlockTimes {
+            off_block: time("2025-12-21T15:10:00Z")?,
+            takeoff: time("2025-12-21T15:25:00Z")?,
+            landing: time("2025-12-21T17:05:00Z")?,
+            on_block: time("2025-12-21T17:15:00Z")?,
+        };
+        let entry = LogbookEntry::from_flight(
+            &flightplan,
+            actual,
+            Some(2650.0),
+            Some((50.0333, 8.5706)),
+            Some((51.4706, -0.4619)),
+        )?;
+        assert_eq!(entry.callsign, "DLH900");
+        assert_eq!(utils::format_duration(entry.block_time()), "2:05");
+        assert!(entry.night_minutes > 0 && entry.night_minutes < 125);
+        assert_eq!((entry.day_landings, entry.night_landings), (0, 1));
+
+        let on_block_first = BlockTimes {
+            on_block: actual.off_block,
+            ..actual
+        };
+        assert!(LogbookEntry::from_flight(&flightplan, on_block_first, None, None, None).is_err());
+
+        let line = serde_json::to_string(&entry)?;
+        let (mut entries, errors) = parse(&format!("{line}\n\n{{}}\n{line}\n"));
+        // The broken line is skipped, the flights around it are kept
+        assert_eq!(entries.len(), 2);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("invalid entry on line 3"));
+        assert!(entries[0].is_same_flight(&entries[1]));
+        entries[1].origin = "EGLL".to_string();
+        entries[1].destination = "LEMD".to_string();
+        entries[1].off_block += TimeDelta::days(1);
+        entries[1].on_block += TimeDelta::days(1);
+        assert!(!entries[0].is_same_flight(&entries[1]));
+
+        assert_eq!(search(&entries, "dlh egll").len(), 2);
+        assert_eq!(search(&entries, "lemd a20n").len(), 1);
+        assert!(search(&entries, "B738").is_empty());
+
+        let totals = Totals::of(&entries);
+        assert_eq!(totals.flights, 2);
+        assert_eq!(utils::format_duration(totals.block_time), "4:10");
+        assert_eq!(utils::format_duration(totals.air_time), "3:20");
+        assert_eq!(totals.fuel_used, 5300.0);
+
+        let csv = to_csv(&entries[..1]);
+        assert_eq!(csv.lines().count(), 2);
+        assert!(csv.lines().nth(1).unwrap().starts_with(
+            "2025-12-21,DLH900,A20N,D-AINA,EDDF,EGLL,SOBRA Y6 ROKIL,2025-12-21 15:10Z,"
+        ));
+        assert_eq!(escape_csv("RMK \"A\", B"), "\"RMK \"\"A\"\", B\"");
+
+        Ok(())
+    }
+}
@@ -1,5 +1,17 @@
-use flypad::app::App;
+use flypad::{app::App, cli};
 
 fn main() -> iced::Result {
-    iced::application(App::new, App::update, App::view).run()
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    if !args.is_empty() {
+        if let Err(e) = cli::run(args) {
+            eprintln!("{e:#}");
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    iced::application(App::new, App::update, App::view)
+        .subscription(App::subscription)
+        .run()
 }
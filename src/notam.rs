@@ -0,0 +1,259 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, NaiveDateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::utils;
+
+// NOTAMs becoming valid or expiring just around the planned times are still worth reading
+const VALIDITY_MARGIN_HOURS: i64 = 1;
+// Unserviceable, not available, on test and a condition given in plain language. Others like CF
+// (changed frequency) or AK (resumed normal operation) don't take a navaid out.
+const OUTAGE_CONDITIONS: [&str; 4] = ["AS", "AU", "CT", "XX"];
+// Navaids named in the text of NOTAMs without a Q-code
+const NAVAIDS: [&str; 10] = [
+    "ILS", "LOC", "GP", "GS", "DME", "VOR", "NDB", "TACAN", "MKR", "GBAS",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum NotamCategory {
+    RunwayClosure,
+    NavaidOutage,
+    ApproachChange,
+    Other,
+}
+
+impl Display for NotamCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            NotamCategory::RunwayClosure => "Runway closure",
+            NotamCategory::NavaidOutage => "Navaid outage",
+            NotamCategory::ApproachChange => "Approach change",
+            NotamCategory::Other => "Other",
+        };
+
+        write!(f, "{label}")
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Notam {
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub notam_id: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub location_icao: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub date_effective: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub date_expire: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub notam_qcode: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_flight_plan_string")]
+    pub notam_text: String,
+}
+
+impl Notam {
+    // The Q-code is QXXYY where XX is the subject and YY its condition, e.g. QMRLC for a
+    // closed runway. The text is only used when there is no usable Q-code.
+    pub fn category(&self) -> NotamCategory {
+        let qcode = self.notam_qcode.trim().to_uppercase();
+        let subject = qcode.get(1..3).unwrap_or_default();
+        let condition = qcode.get(3..5).unwrap_or_default();
+
+        if qcode.len() == 5 && qcode.starts_with('Q') && qcode.is_ascii() {
+            return match subject {
+                "MR" if condition == "LC" => NotamCategory::RunwayClosure,
+                _ if subject.starts_with(['I', 'N']) && OUTAGE_CONDITIONS.contains(&condition) => {
+                    NotamCategory::NavaidOutage
+                }
+                "PI" | "PA" => NotamCategory::ApproachChange,
+                _ => NotamCategory::Other,
+            };
+        }
+
+        let text = self.notam_text.to_uppercase();
+        let words: Vec<&str> = text.split_whitespace().collect();
+        if !closed_runways(&text).is_empty() {
+            NotamCategory::RunwayClosure
+        } else if words.contains(&"U/S") && words.iter().any(|word| NAVAIDS.contains(word)) {
+            NotamCategory::NavaidOutage
+        } else if words.contains(&"APCH") {
+            NotamCategory::ApproachChange
+        } else {
+            NotamCategory::Other
+        }
+    }
+
+    pub fn effective(&self) -> Option<DateTime<Utc>> {
+        parse_notam_date(&self.date_effective)
+    }
+
+    // None means the NOTAM is permanent
+    pub fn expires(&self) -> Option<DateTime<Utc>> {
+        parse_notam_date(&self.date_expire)
+    }
+
+    pub fn is_valid_between(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> bool {
+        let margin = TimeDelta::hours(VALIDITY_MARGIN_HOURS);
+        let starts_in_time = self
+            .effective()
+            .is_none_or(|effective| effective <= end + margin);
+        let still_valid = self
+            .expires()
+            .is_none_or(|expires| expires >= start - margin);
+
+        starts_in_time && still_valid
+    }
+
+    pub fn affects_runway(&self, runway: &str) -> bool {
+        let runway = runway.trim().to_uppercase();
+        if runway.is_empty() || self.category() != NotamCategory::RunwayClosure {
+            return false;
+        }
+
+        closed_runways(&self.notam_text.to_uppercase()).contains(&runway.as_str())
+    }
+}
+
+// The runways of phrases like RWY 08L/26R CLSD. Runways only mentioned, like in TWY A CLSD BTN
+// RWY 25C AND RWY 18, aren't closed.
+fn closed_runways(text: &str) -> Vec<&str> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut closed = Vec::new();
+
+    for (index, _) in words.iter().enumerate().filter(|(_, word)| **word == "RWY") {
+        let runways: Vec<&str> = words[index + 1..]
+            .iter()
+            .copied()
+            .take_while(|word| word.split('/').all(is_runway_designator))
+            .collect();
+        let next = words.get(index + 1 + runways.len());
+
+        if !runways.is_empty() && matches!(next, Some(&"CLSD" | &"CLOSED")) {
+            closed.extend(runways.iter().flat_map(|runway| runway.split('/')));
+        }
+    }
+
+    closed
+}
+
+// Like 08, 26R or 18C
+fn is_runway_designator(word: &str) -> bool {
+    let digits = word.chars().take_while(char::is_ascii_digit).count();
+    let side = &word[digits..];
+
+    (1..=2).contains(&digits) && ["", "L", "R", "C"].contains(&side)
+}
+
+// Keeps the NOTAMs valid during the given period, sorted by category. Without planned times
+// nothing can be filtered out.
+pub fn relevant_notams(
+    notams: &[Notam],
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+) -> Vec<Notam> {
+    let mut relevant: Vec<Notam> = notams
+        .iter()
+        .filter(|notam| match (start, end) {
+            (Some(start), Some(end)) => notam.is_valid_between(start, end),
+            _ => true,
+        })
+        .cloned()
+        .collect();
+
+    relevant.sort_by_key(|notam| notam.category());
+    relevant
+}
+
+fn parse_notam_date(date: &str) -> Option<DateTime<Utc>> {
+    let date = date.trim();
+
+    DateTime::parse_from_rfc3339(date)
+        .map(|date| date.to_utc())
+        .ok()
+        .or_else(|| {
+            NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S")
+                .ok()
+                .map(|date| date.and_utc())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notam(qcode: &str, text: &str, effective: &str, expire: &str) -> Notam {
+        Notam {
+            notam_qcode: qcode.to_string(),
+            notam_text: text.to_string(),
+            date_effective: effective.to_string(),
+            date_expire: expire.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    pub fn test_category() {
+        let closure = notam("QMRLC", "RWY 08L/26R CLSD", "", "");
+        assert_eq!(closure.category(), NotamCategory::RunwayClosure);
+        assert!(closure.affects_runway("26R"));
+        assert!(!closure.affects_runway("26L"));
+
+        let ils = notam("QICAS", "ILS RWY 26L U/S", "", "");
+        assert_eq!(ils.category(), NotamCategory::NavaidOutage);
+        assert!(!ils.affects_runway("26L"));
+
+        // The Q-code wins over the text
+        let taxiway = notam("QMXLC", "TWY A CLSD BTN RWY 25C AND TWY B", "", "");
+        assert_eq!(taxiway.category(), NotamCategory::Other);
+        assert!(!taxiway.affects_runway("25C"));
+
+        let lights = notam("", "TWY N CL LGT U/S", "", "");
+        assert_eq!(lights.category(), NotamCategory::Other);
+        // Changed frequencies and navaids back in service aren't outages
+        let changed = notam("QNVCF", "FFM VOR FREQ CHANGED TO 114.200", "", "");
+        assert_eq!(changed.category(), NotamCategory::Other);
+        let normal = notam("QICAK", "ILS RWY 26L OPR NORMAL", "", "");
+        assert_eq!(normal.category(), NotamCategory::Other);
+        let vor = notam("", "FFM VOR U/S", "", "");
+        assert_eq!(vor.category(), NotamCategory::NavaidOutage);
+
+        let mentioned = notam("", "TWY A CLSD BTN RWY 25C AND RWY 18", "", "");
+        assert_eq!(mentioned.category(), NotamCategory::Other);
+        assert!(!mentioned.affects_runway("25C"));
+        let closed = notam("", "RWY 07C/25C CLSD DUE TO WIP", "", "");
+        assert_eq!(closed.category(), NotamCategory::RunwayClosure);
+        assert!(closed.affects_runway("25C"));
+        assert!(!closed.affects_runway("18"));
+
+        let approach = notam("QPICH", "RNP APCH RWY 26L CHANGED", "", "");
+        assert_eq!(approach.category(), NotamCategory::ApproachChange);
+    }
+
+    #[test]
+    pub fn test_validity() {
+        let start = "2025-06-01T10:00:00Z".parse().unwrap();
+        let end = "2025-06-01T12:00:00Z".parse().unwrap();
+
+        let valid = notam(
+            "",
+            "",
+            "2025-05-01T00:00:00+00:00",
+            "2025-06-01T10:30:00+00:00",
+        );
+        let expired = notam("", "", "2025-05-01 00:00:00", "2025-05-31 23:00:00");
+        let permanent = notam("", "", "2025-05-01T00:00:00Z", "PERM");
+        let future = notam("", "", "2025-06-02T00:00:00Z", "2025-06-03T00:00:00Z");
+
+        assert!(valid.is_valid_between(start, end));
+        assert!(!expired.is_valid_between(start, end));
+        assert!(permanent.is_valid_between(start, end));
+        assert!(!future.is_valid_between(start, end));
+    }
+}
@@ -0,0 +1,220 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+
+use crate::{flightplan::FlightPlan, utils};
+
+// Tags that end a line when the OFP is turned into text
+const LINE_BREAK_TAGS: [&str; 11] = [
+    "br", "/div", "/p", "/tr", "/li", "/h1", "/h2", "/h3", "/h4", "/h5", "/h6",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Download {
+    pub name: String,
+    pub url: String,
+}
+
+impl Download {
+    // The file name SimBrief gave it, like EDDFEGLL_PDF_1760785200.pdf
+    pub fn file_name(&self) -> Option<&str> {
+        self.url
+            .rsplit('/')
+            .next()
+            .filter(|name| !name.is_empty() && *name != "." && *name != "..")
+    }
+}
+
+// The PDF first, then the plans for the simulators. Some are listed in both sections.
+pub fn downloads(flightplan: &FlightPlan) -> Vec<Download> {
+    let mut downloads: Vec<Download> = Vec::new();
+    for files in [&flightplan.files, &flightplan.fms_downloads] {
+        for file in files.links() {
+            let url = files.url(&file);
+            if !downloads.iter().any(|download| download.url == url) {
+                downloads.push(Download {
+                    name: file.name,
+                    url,
+                });
+            }
+        }
+    }
+    downloads.sort_by_key(|download| !download.url.ends_with(".pdf"));
+
+    downloads
+}
+
+// The OFP is preformatted text wrapped in a bit of HTML, so removing the tags and decoding the
+// entities keeps the columns lined up
+pub fn plan_text(html: &str) -> String {
+    let mut text = String::new();
+    let mut chars = html.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '<' => {
+                let tag: String = chars.by_ref().take_while(|c| *c != '>').collect();
+                let name = tag
+                    .split_whitespace()
+                    .next()
+                    .unwrap_or_default()
+                    .trim_end_matches('/')
+                    .to_lowercase();
+                if LINE_BREAK_TAGS.contains(&name.as_str()) {
+                    text.push('\n');
+                }
+            }
+            '&' => {
+                let entity: String = chars.clone().take_while(|c| *c != ';').take(10).collect();
+                match decode_entity(&entity) {
+                    Some(decoded) => {
+                        text.push(decoded);
+                        chars.nth(entity.chars().count());
+                    }
+                    None => text.push('&'),
+                }
+            }
+            '\r' => (),
+            _ => text.push(c),
+        }
+    }
+
+    text.lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_matches('\n')
+        .to_string()
+}
+
+// Line numbers start at 0, matching the lines of the text editor
+pub fn find_lines<'a>(text: &'a str, query: &str) -> Vec<(usize, &'a str)> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| line.to_lowercase().contains(&query))
+        .collect()
+}
+
+pub async fn download(download: Download, directory: PathBuf) -> Result<PathBuf> {
+    let Some(file_name) = download.file_name() else {
+        bail!("{} has no file name", download.url);
+    };
+    let path = directory.join(file_name);
+
+    let contents = utils::send_with_retry(utils::http_client().get(&download.url))
+        .await?
+        .error_for_status()
+        .with_context(|| format!("unable to download {}", download.name))?
+        .bytes()
+        .await
+        .with_context(|| format!("failed to download {}", download.name))?;
+    save(&path, &contents).await?;
+
+    Ok(path)
+}
+
+async fn save(path: &Path, contents: &[u8]) -> Result<()> {
+    if let Some(directory) = path.parent() {
+        tokio::fs::create_dir_all(directory)
+            .await
+            .with_context(|| format!("failed to create {}", directory.display()))?;
+    }
+
+    tokio::fs::write(path, contents)
+        .await
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    match entity {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        _ => {
+            let number = entity.strip_prefix('#')?;
+            let code = match number.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => number.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_plan_text() {
+        let html = "<div style=\"line-height:14px;font-size:13px\"><pre><b>[ OFP ]</b>\r\n\
+                    DLH900  EDDF-EGLL   A20N D-AINA\r\n\
+                    FUEL &lt;KG&gt; &amp; TIME&nbsp;&nbsp;   &#65;&#x42; &unknown\r\n\
+                    </pre><h2 class=\"pagebreak\">WX</h2><br/>EGLL 181150Z</div>";
+
+        let text = plan_text(html);
+        assert_eq!(
+            text,
+            "[ OFP ]\nDLH900  EDDF-EGLL   A20N D-AINA\nFUEL <KG> & TIME     AB &unknown\nWX\n\nEGLL 181150Z"
+        );
+        assert_eq!(
+            find_lines(&text, "egll"),
+            vec![(1, "DLH900  EDDF-EGLL   A20N D-AINA"), (5, "EGLL 181150Z")]
+        );
+        assert!(find_lines(&text, "  ").is_empty());
+    }
+
+    #[test]
+    pub fn test_downloads() -> anyhow::Result<()> {
+        let flightplan: FlightPlan = serde_json::from_str(
+            r#"{
+                "origin": {"icao_code": "EDDF"},
+                "destination": {"icao_code": "EGLL"},
+                "general": {},
+                "fuel": {},
+                "files": {
+                    "directory": "https://www.simbrief.com/ofp/flightplans/",
+                    "pdf": {"name": "PDF Document", "link": "EDDFEGLL_PDF_1760785200.pdf"},
+                    "file": {"name": "X-Plane 11", "link": "EDDFEGLL_XPE_1760785200.fms"}
+                },
+                "fms_downloads": {
+                    "directory": "https://www.simbrief.com/ofp/flightplans/",
+                    "xpe": {"name": "X-Plane 11", "link": "EDDFEGLL_XPE_1760785200.fms"},
+                    "mfs": {"name": "MSFS 2020", "link": "EDDFEGLL_MFS_1760785200.pln"},
+                    "abx": {"name": "Not generated", "link": ""}
+                }
+            }"#,
+        )?;
+
+        let downloads = downloads(&flightplan);
+        let names: Vec<&str> = downloads
+            .iter()
+            .map(|download| download.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["PDF Document", "X-Plane 11", "MSFS 2020"]);
+        assert_eq!(
+            downloads[0].url,
+            "https://www.simbrief.com/ofp/flightplans/EDDFEGLL_PDF_1760785200.pdf"
+        );
+        assert_eq!(
+            downloads[0].file_name(),
+            Some("EDDFEGLL_PDF_1760785200.pdf")
+        );
+
+        let directory = Download {
+            name: "Nothing".to_string(),
+            url: "https://www.simbrief.com/ofp/flightplans/".to_string(),
+        };
+        assert_eq!(directory.file_name(), None);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,211 @@
+// A very small PDF writer, only supporting what the kneeboard needs: text in the standard
+// fonts, lines and rectangles. Using the standard fonts means nothing has to be embedded.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Font {
+    Regular,
+    Bold,
+    Monospace,
+}
+
+impl Font {
+    const ALL: [Font; 3] = [Font::Regular, Font::Bold, Font::Monospace];
+
+    fn resource_name(&self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+            Font::Monospace => "F3",
+        }
+    }
+
+    fn base_font(&self) -> &'static str {
+        match self {
+            Font::Regular => "Helvetica",
+            Font::Bold => "Helvetica-Bold",
+            Font::Monospace => "Courier",
+        }
+    }
+}
+
+// Coordinates are in points with the origin in the bottom left corner
+#[derive(Debug, Clone)]
+pub struct PdfPage {
+    pub width: f32,
+    pub height: f32,
+    content: String,
+}
+
+impl PdfPage {
+    pub fn new(width: f32, height: f32) -> Self {
+        Self {
+            width,
+            height,
+            content: String::new(),
+        }
+    }
+
+    pub fn text(&mut self, x: f32, y: f32, font: Font, size: f32, text: &str) {
+        self.content.push_str(&format!(
+            "BT /{} {size:.1} Tf {x:.2} {y:.2} Td ({}) Tj ET\n",
+            font.resource_name(),
+            escape_text(text)
+        ));
+    }
+
+    pub fn line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32) {
+        self.content
+            .push_str(&format!("0.5 w {x1:.2} {y1:.2} m {x2:.2} {y2:.2} l S\n"));
+    }
+
+    pub fn rectangle(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        self.content.push_str(&format!(
+            "0.5 w {x:.2} {y:.2} {width:.2} {height:.2} re S\n"
+        ));
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PdfDocument {
+    pages: Vec<PdfPage>,
+}
+
+impl PdfDocument {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_page(&mut self, page: PdfPage) {
+        self.pages.push(page);
+    }
+
+    // Objects are numbered: 1 catalog, 2 page tree, 3 to 5 fonts, then a page and its content
+    // stream for every page
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let font_count = Font::ALL.len();
+        let first_page = 3 + font_count;
+        let mut objects: Vec<String> = Vec::new();
+
+        objects.push("<< /Type /Catalog /Pages 2 0 R >>".to_string());
+
+        let kids = (0..self.pages.len())
+            .map(|index| format!("{} 0 R", first_page + index * 2))
+            .collect::<Vec<_>>()
+            .join(" ");
+        objects.push(format!(
+            "<< /Type /Pages /Kids [{kids}] /Count {} >>",
+            self.pages.len()
+        ));
+
+        for font in Font::ALL {
+            objects.push(format!(
+                "<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>",
+                font.base_font()
+            ));
+        }
+
+        let font_resources = Font::ALL
+            .iter()
+            .enumerate()
+            .map(|(index, font)| format!("/{} {} 0 R", font.resource_name(), index + 3))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        for (index, page) in self.pages.iter().enumerate() {
+            let content_object = first_page + index * 2 + 1;
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] \
+                 /Resources << /Font << {font_resources} >> >> /Contents {content_object} 0 R >>",
+                page.width, page.height
+            ));
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{}endstream",
+                page.content.len(),
+                page.content
+            ));
+        }
+
+        let mut output = b"%PDF-1.4\n".to_vec();
+        let mut offsets = Vec::new();
+
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(output.len());
+            output.extend_from_slice(format!("{} 0 obj\n{object}\nendobj\n", index + 1).as_bytes());
+        }
+
+        let xref_offset = output.len();
+        output.extend_from_slice(
+            format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes(),
+        );
+        for offset in offsets {
+            output.extend_from_slice(format!("{offset:010} 00000 n \n").as_bytes());
+        }
+        output.extend_from_slice(
+            format!(
+                "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref_offset}\n%%EOF\n",
+                objects.len() + 1
+            )
+            .as_bytes(),
+        );
+
+        output
+    }
+}
+
+// The standard fonts use WinAnsiEncoding, the degree sign is the only non ASCII character the
+// pad produces, anything else is replaced
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::new();
+
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '°' => escaped.push_str("\\260"),
+            ' '..='~' => escaped.push(c),
+            _ => escaped.push('?'),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_cross_reference_offsets() {
+        let mut page = PdfPage::new(420.0, 595.0);
+        page.text(20.0, 570.0, Font::Bold, 12.0, "DLH4CK (EDDM - EDDF) 250°");
+        page.rectangle(20.0, 20.0, 100.0, 30.0);
+        let mut document = PdfDocument::new();
+        document.add_page(page.clone());
+        document.add_page(page);
+
+        let bytes = document.to_bytes();
+        let text = String::from_utf8_lossy(&bytes);
+        assert!(text.starts_with("%PDF-1.4"));
+        assert!(text.contains("(DLH4CK \\(EDDM - EDDF\\) 250\\260) Tj"));
+        assert!(text.contains("/Count 2"));
+
+        // Every xref entry has to point at the start of its object
+        let xref_start: usize = text
+            .lines()
+            .skip_while(|line| *line != "startxref")
+            .nth(1)
+            .unwrap()
+            .parse()
+            .unwrap();
+        let entries = text[xref_start..]
+            .lines()
+            .skip(3)
+            .take_while(|line| line.ends_with(" n "));
+        for (index, entry) in entries.enumerate() {
+            let offset: usize = entry[..10].parse().unwrap();
+            assert!(text[offset..].starts_with(&format!("{} 0 obj", index + 1)));
+        }
+    }
+}
@@ -0,0 +1,649 @@
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+
+use crate::{airport::Airport, runway_condition::RunwayCondition, wind::RunwayWind};
+
+const STANDARD_PRESSURE_HPA: f32 = 1013.25;
+const FEET_PER_HPA: f32 = 30.0;
+const ISA_LAPSE_RATE_PER_1000FT: f32 = 1.98;
+const MIN_APPROACH_ADDITIVE: f32 = 5.0;
+const MAX_APPROACH_ADDITIVE: f32 = 20.0;
+
+// The tables are for sea level, ISA and no wind. Weights are in kilograms, speeds in knots and
+// distances in metres. Aircraft profiles without tables leave everything out.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(try_from = "ProfileFile")]
+pub struct PerformanceProfile {
+    // Below this temperature the engines produce full thrust, so it is the lowest flex temperature
+    pub flat_rating_temperature: f32,
+    pub max_flex_temperature: f32,
+    pub corrections: Corrections,
+    pub takeoff: Vec<TakeoffTable>,
+    pub landing: Vec<LandingTable>,
+}
+
+// The temperatures are only needed for takeoff tables, but then they have to be given, as
+// defaulting them to 0 °C would give a flex temperature on any warm day
+#[derive(Deserialize)]
+struct ProfileFile {
+    flat_rating_temperature: Option<f32>,
+    max_flex_temperature: Option<f32>,
+    #[serde(default)]
+    corrections: Corrections,
+    #[serde(default)]
+    takeoff: Vec<TakeoffTable>,
+    #[serde(default)]
+    landing: Vec<LandingTable>,
+}
+
+impl TryFrom<ProfileFile> for PerformanceProfile {
+    type Error = anyhow::Error;
+
+    fn try_from(file: ProfileFile) -> Result<Self> {
+        if !file.takeoff.is_empty()
+            && (file.flat_rating_temperature.is_none() || file.max_flex_temperature.is_none())
+        {
+            bail!("takeoff tables need a flat_rating_temperature and a max_flex_temperature");
+        }
+
+        Ok(Self {
+            flat_rating_temperature: file.flat_rating_temperature.unwrap_or_default(),
+            max_flex_temperature: file.max_flex_temperature.unwrap_or_default(),
+            corrections: file.corrections,
+            takeoff: file.takeoff,
+            landing: file.landing,
+        })
+    }
+}
+
+// Fractions of the table distance, used for both takeoff and landing
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Corrections {
+    pub distance_per_1000ft: f32,
+    pub distance_per_10c_above_isa: f32,
+    pub distance_per_headwind_kt: f32,
+    pub distance_per_tailwind_kt: f32,
+}
+
+impl Default for Corrections {
+    fn default() -> Self {
+        Self {
+            distance_per_1000ft: 0.08,
+            distance_per_10c_above_isa: 0.06,
+            distance_per_headwind_kt: 0.005,
+            distance_per_tailwind_kt: 0.03,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TakeoffTable {
+    pub flaps: String,
+    pub rows: Vec<TakeoffRow>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct TakeoffRow {
+    pub weight: f32,
+    pub v1: f32,
+    pub vr: f32,
+    pub v2: f32,
+    pub distance: f32,
+}
+
+// Every distance is the landing distance for the autobrake setting at the same position
+#[derive(Debug, Clone, Deserialize)]
+pub struct LandingTable {
+    pub flaps: String,
+    pub autobrakes: Vec<String>,
+    pub rows: Vec<LandingRow>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LandingRow {
+    pub weight: f32,
+    pub vref: f32,
+    pub distances: Vec<f32>,
+}
+
+// Finds the two rows around the weight and how far the weight is between them. Lighter weights
+// use the lightest row, which is conservative for the distance.
+fn bracket<T>(rows: &[T], weight: f32, row_weight: impl Fn(&T) -> f32) -> Result<(&T, &T, f32)> {
+    let mut rows: Vec<&T> = rows.iter().collect();
+    rows.sort_by(|a, b| row_weight(a).total_cmp(&row_weight(b)));
+
+    let (Some(lightest), Some(heaviest)) = (rows.first(), rows.last()) else {
+        bail!("no performance data");
+    };
+    if weight <= row_weight(lightest) {
+        return Ok((lightest, lightest, 0.0));
+    }
+    if weight > row_weight(heaviest) {
+        bail!(
+            "{weight:.0} kg is above the heaviest weight of {:.0} kg",
+            row_weight(heaviest)
+        );
+    }
+
+    let upper = rows
+        .iter()
+        .position(|row| row_weight(row) >= weight)
+        .unwrap_or(rows.len() - 1);
+    let (low, high) = (rows[upper - 1], rows[upper]);
+
+    Ok((
+        low,
+        high,
+        (weight - row_weight(low)) / (row_weight(high) - row_weight(low)),
+    ))
+}
+
+fn interpolate(low: f32, high: f32, ratio: f32) -> f32 {
+    low + (high - low) * ratio
+}
+
+impl TakeoffTable {
+    fn row(&self, weight: f32) -> Result<TakeoffRow> {
+        let (low, high, ratio) = bracket(&self.rows, weight, |row| row.weight)
+            .with_context(|| format!("flaps {} takeoff", self.flaps))?;
+
+        Ok(TakeoffRow {
+            weight,
+            v1: interpolate(low.v1, high.v1, ratio),
+            vr: interpolate(low.vr, high.vr, ratio),
+            v2: interpolate(low.v2, high.v2, ratio),
+            distance: interpolate(low.distance, high.distance, ratio),
+        })
+    }
+}
+
+impl LandingTable {
+    fn row(&self, weight: f32) -> Result<LandingRow> {
+        let (low, high, ratio) = bracket(&self.rows, weight, |row| row.weight)
+            .with_context(|| format!("flaps {} landing", self.flaps))?;
+
+        Ok(LandingRow {
+            weight,
+            vref: interpolate(low.vref, high.vref, ratio),
+            distances: low
+                .distances
+                .iter()
+                .zip(&high.distances)
+                .map(|(low, high)| interpolate(*low, *high, ratio))
+                .collect(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PerformanceInputs {
+    pub weight: f32,
+    pub temperature: f32,
+    pub qnh: f32,
+    pub elevation_ft: f32,
+    pub runway: String,
+    // Negative values are a tailwind
+    pub headwind: f32,
+    // How much stronger the gusts are than the steady wind
+    pub gust: f32,
+    // Takeoff run or landing distance available
+    pub runway_length: Option<f32>,
+}
+
+impl PerformanceInputs {
+    pub fn takeoff(airport: &Airport, weight: f32) -> Result<PerformanceInputs> {
+        Self::from_airport(
+            airport,
+            weight,
+            airport.planned_runway().map(|runway| runway.length_m()),
+        )
+    }
+
+    pub fn landing(airport: &Airport, weight: f32) -> Result<PerformanceInputs> {
+        Self::from_airport(
+            airport,
+            weight,
+            airport
+                .planned_runway()
+                .map(|runway| runway.landing_length_m()),
+        )
+    }
+
+    fn from_airport(
+        airport: &Airport,
+        weight: f32,
+        runway_length: Option<f32>,
+    ) -> Result<PerformanceInputs> {
+        if airport.weather.metar.is_empty() {
+            bail!("no weather for {}", airport.icao);
+        }
+        let headwind = RunwayWind::from_weather(&airport.runway, &airport.weather)
+            .map(|wind| wind.headwind)
+            .with_context(|| format!("no runway heading for {:?}", airport.runway))?;
+
+        // Both would make the distances shorter than they are
+        let temperature = airport
+            .weather
+            .reported_temperature()
+            .with_context(|| format!("no temperature for {}", airport.icao))?;
+        let elevation_ft = airport
+            .elevation_ft()
+            .with_context(|| format!("no elevation for {}", airport.icao))?;
+
+        Ok(PerformanceInputs {
+            weight,
+            temperature,
+            qnh: airport.weather.altimeter(),
+            elevation_ft: elevation_ft as f32,
+            runway: airport.runway.clone(),
+            headwind,
+            gust: (airport.weather.wind_gust() - airport.weather.wind_speed()).max(0.0),
+            runway_length,
+        })
+    }
+
+    pub fn pressure_altitude(&self) -> f32 {
+        // A missing altimeter setting reads as 0
+        let qnh = if self.qnh > 0.0 {
+            self.qnh
+        } else {
+            STANDARD_PRESSURE_HPA
+        };
+
+        self.elevation_ft + (STANDARD_PRESSURE_HPA - qnh) * FEET_PER_HPA
+    }
+
+    pub fn isa_temperature(&self) -> f32 {
+        15.0 - ISA_LAPSE_RATE_PER_1000FT * self.pressure_altitude() / 1000.0
+    }
+
+    pub fn describe(&self) -> Vec<(&'static str, String)> {
+        let mut wind = if self.headwind >= 0.0 {
+            format!("{:.0} kt headwind", self.headwind)
+        } else {
+            format!("{:.0} kt tailwind", -self.headwind)
+        };
+        if self.gust > 0.0 {
+            wind.push_str(&format!(", gusts {:.0} kt stronger", self.gust));
+        }
+        let runway_length = match self.runway_length {
+            Some(length) => format!("{length:.0} m"),
+            None => "unknown".to_string(),
+        };
+
+        vec![
+            ("Weight", format!("{:.0} kg", self.weight)),
+            (
+                "OAT",
+                format!(
+                    "{:.0} °C (ISA {:+.0})",
+                    self.temperature,
+                    self.isa_deviation()
+                ),
+            ),
+            (
+                "Pressure Alt",
+                format!(
+                    "{:.0} ft (elevation {:.0} ft, QNH {:.0})",
+                    self.pressure_altitude(),
+                    self.elevation_ft,
+                    self.qnh
+                ),
+            ),
+            ("Wind", wind),
+            ("Runway", format!("{} {runway_length}", self.runway)),
+        ]
+    }
+
+    fn isa_deviation(&self) -> f32 {
+        self.temperature - self.isa_temperature()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TakeoffResult {
+    pub flaps: String,
+    // None means a takeoff with full thrust
+    pub flex_temperature: Option<f32>,
+    pub v1: f32,
+    pub vr: f32,
+    pub v2: f32,
+    pub required_distance: f32,
+}
+
+impl TakeoffResult {
+    pub fn describe(&self) -> Vec<(&'static str, String)> {
+        let thrust = match self.flex_temperature {
+            Some(temperature) => format!("FLEX {temperature:.0} °C"),
+            None => "TOGA".to_string(),
+        };
+
+        vec![
+            ("Flaps", self.flaps.clone()),
+            ("Thrust", thrust),
+            (
+                "V-Speeds",
+                format!("V1 {:.0}  VR {:.0}  V2 {:.0}", self.v1, self.vr, self.v2),
+            ),
+            ("Required", format!("{:.0} m", self.required_distance)),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LandingResult {
+    pub flaps: String,
+    pub vref: f32,
+    pub vapp: f32,
+    // Autobrake setting and landing distance
+    pub distances: Vec<(String, f32)>,
+}
+
+impl LandingResult {
+    pub fn describe(&self, runway_length: Option<f32>) -> Vec<(String, String)> {
+        let mut rows = vec![(
+            format!("Flaps {}", self.flaps),
+            format!("VREF {:.0}  VAPP {:.0}", self.vref, self.vapp),
+        )];
+
+        rows.extend(self.distances.iter().map(|(autobrake, distance)| {
+            let value = match runway_length {
+                Some(length) if *distance > length => {
+                    format!("{distance:.0} m, exceeds the runway")
+                }
+                _ => format!("{distance:.0} m"),
+            };
+            (format!("Autobrake {autobrake}"), value)
+        }));
+
+        rows
+    }
+}
+
+impl PerformanceProfile {
+    // Picks the flap setting allowing the highest flex temperature, which saves the most engine
+    // wear. Without a runway length the shortest takeoff at full thrust is shown. Flap settings
+    // whose table doesn't cover the weight are skipped.
+    pub fn takeoff(&self, inputs: &PerformanceInputs) -> Result<TakeoffResult> {
+        let mut best: Option<TakeoffResult> = None;
+        let mut shortest: Option<f32> = None;
+        let mut weight_error = None;
+
+        for table in &self.takeoff {
+            let row = match table.row(inputs.weight) {
+                Ok(row) => row,
+                Err(e) => {
+                    weight_error = Some(e);
+                    continue;
+                }
+            };
+            let required = |temperature| row.distance * self.distance_factor(inputs, temperature);
+            let toga_distance = required(inputs.temperature);
+            shortest = Some(shortest.map_or(toga_distance, |distance| distance.min(toga_distance)));
+
+            let candidate = match inputs.runway_length {
+                Some(length) if toga_distance > length => continue,
+                Some(length) => {
+                    let minimum = self.flat_rating_temperature.max(inputs.temperature + 1.0);
+                    let flex_temperature = (minimum.ceil() as i32
+                        ..=self.max_flex_temperature as i32)
+                        .rev()
+                        .map(|temperature| temperature as f32)
+                        .find(|temperature| required(*temperature) <= length);
+
+                    TakeoffResult {
+                        flaps: table.flaps.clone(),
+                        flex_temperature,
+                        v1: row.v1,
+                        vr: row.vr,
+                        v2: row.v2,
+                        required_distance: flex_temperature.map_or(toga_distance, required),
+                    }
+                }
+                None => TakeoffResult {
+                    flaps: table.flaps.clone(),
+                    flex_temperature: None,
+                    v1: row.v1,
+                    vr: row.vr,
+                    v2: row.v2,
+                    required_distance: toga_distance,
+                },
+            };
+
+            let better = match &best {
+                None => true,
+                Some(best) if inputs.runway_length.is_none() => {
+                    candidate.required_distance < best.required_distance
+                }
+                Some(best) => {
+                    candidate.flex_temperature.unwrap_or(f32::MIN)
+                        > best.flex_temperature.unwrap_or(f32::MIN)
+                }
+            };
+            if better {
+                best = Some(candidate);
+            }
+        }
+
+        match (best, shortest) {
+            (Some(best), _) => Ok(best),
+            (None, Some(shortest)) => bail!(
+                "runway too short, {shortest:.0} m required at TOGA but only {:.0} m available",
+                inputs.runway_length.unwrap_or_default()
+            ),
+            (None, None) => match weight_error {
+                Some(e) => Err(e),
+                None => bail!("the aircraft profile has no takeoff data"),
+            },
+        }
+    }
+
+    // One result per flap setting with the distance for every autobrake setting. Like for the
+    // takeoff, flap settings whose table doesn't cover the weight are left out.
+    pub fn landing(
+        &self,
+        inputs: &PerformanceInputs,
+        condition: RunwayCondition,
+    ) -> Result<Vec<LandingResult>> {
+        if self.landing.is_empty() {
+            bail!("the aircraft profile has no landing data");
+        }
+        let factor =
+            self.distance_factor(inputs, inputs.temperature) * condition.landing_distance_factor();
+
+        let mut results = Vec::new();
+        let mut weight_error = None;
+        for table in &self.landing {
+            let row = match table.row(inputs.weight) {
+                Ok(row) => row,
+                Err(e) => {
+                    weight_error = Some(e);
+                    continue;
+                }
+            };
+
+            results.push(LandingResult {
+                flaps: table.flaps.clone(),
+                vref: row.vref,
+                vapp: row.vref + approach_additive(inputs.headwind, inputs.gust),
+                distances: table
+                    .autobrakes
+                    .iter()
+                    .cloned()
+                    .zip(row.distances.iter().map(|distance| distance * factor))
+                    .collect(),
+            });
+        }
+
+        match weight_error {
+            Some(e) if results.is_empty() => Err(e),
+            _ => Ok(results),
+        }
+    }
+
+    fn distance_factor(&self, inputs: &PerformanceInputs, temperature: f32) -> f32 {
+        let corrections = &self.corrections;
+        let altitude =
+            1.0 + corrections.distance_per_1000ft * inputs.pressure_altitude().max(0.0) / 1000.0;
+        // No credit is taken for temperatures below ISA
+        let isa_deviation = (temperature - inputs.isa_temperature()).max(0.0);
+        let temperature = 1.0 + corrections.distance_per_10c_above_isa * isa_deviation / 10.0;
+        let wind = if inputs.headwind >= 0.0 {
+            1.0 - corrections.distance_per_headwind_kt * inputs.headwind
+        } else {
+            1.0 - corrections.distance_per_tailwind_kt * inputs.headwind
+        };
+
+        altitude * temperature * wind
+    }
+}
+
+// Half the steady headwind plus the full gust, at least 5 and at most 20 kt
+fn approach_additive(headwind: f32, gust: f32) -> f32 {
+    (headwind.max(0.0) / 2.0 + gust).clamp(MIN_APPROACH_ADDITIVE, MAX_APPROACH_ADDITIVE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROFILE: &str = r#"
+flat_rating_temperature = 30
+max_flex_temperature = 68
+
+[[takeoff]]
+flaps = "1+F"
+rows = [
+    { weight = 50000, v1 = 120, vr = 122, v2 = 127, distance = 1300 },
+    { weight = 70000, v1 = 145, vr = 147, v2 = 151, distance = 2100 },
+]
+
+[[takeoff]]
+flaps = "2"
+rows = [
+    { weight = 50000, v1 = 116, vr = 118, v2 = 123, distance = 1200 },
+    { weight = 70000, v1 = 140, vr = 142, v2 = 146, distance = 1900 },
+]
+
+[[landing]]
+flaps = "FULL"
+autobrakes = ["LOW", "MED"]
+rows = [
+    { weight = 50000, vref = 125, distances = [1500, 1200] },
+    { weight = 66000, vref = 141, distances = [1800, 1400] },
+]
+"#;
+
+    fn inputs(runway_length: Option<f32>) -> PerformanceInputs {
+        PerformanceInputs {
+            weight: 60000.0,
+            temperature: 15.0,
+            qnh: 1013.25,
+            elevation_ft: 0.0,
+            runway: "27L".to_string(),
+            headwind: 0.0,
+            gust: 0.0,
+            runway_length,
+        }
+    }
+
+    #[test]
+    pub fn test_missing_temperatures() -> anyhow::Result<()> {
+        let without_flat_rating = PROFILE.replace("flat_rating_temperature = 30", "");
+        assert!(toml::from_str::<PerformanceProfile>(&without_flat_rating).is_err());
+        let without_max_flex = PROFILE.replace("max_flex_temperature = 68", "");
+        assert!(toml::from_str::<PerformanceProfile>(&without_max_flex).is_err());
+
+        // Landing tables alone don't need them
+        let landing_only: PerformanceProfile = toml::from_str(
+            r#"
+[[landing]]
+flaps = "FULL"
+autobrakes = ["LOW"]
+rows = [{ weight = 50000, vref = 125, distances = [1500] }]
+"#,
+        )?;
+        assert_eq!(landing_only.landing.len(), 1);
+        assert!(landing_only.takeoff.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_takeoff() -> anyhow::Result<()> {
+        let profile: PerformanceProfile = toml::from_str(PROFILE)?;
+
+        // Midway between both rows at sea level, ISA and no wind
+        let result = profile.takeoff(&inputs(None))?;
+        assert_eq!(result.flaps, "2");
+        assert_eq!(result.flex_temperature, None);
+        assert_eq!((result.v1, result.vr, result.v2), (128.0, 130.0, 134.5));
+        assert_eq!(result.required_distance, 1550.0);
+
+        // 41 °C is 26 °C above ISA, which needs 1792 m, flaps 1+F would only allow TOGA
+        let result = profile.takeoff(&inputs(Some(1800.0)))?;
+        assert_eq!(result.flaps, "2");
+        assert_eq!(result.flex_temperature, Some(41.0));
+        assert!(result.required_distance <= 1800.0);
+
+        // Only full thrust fits
+        let result = profile.takeoff(&inputs(Some(1600.0)))?;
+        assert_eq!(result.flex_temperature, None);
+
+        assert!(profile.takeoff(&inputs(Some(1500.0))).is_err());
+        assert!(
+            profile
+                .takeoff(&PerformanceInputs {
+                    weight: 80000.0,
+                    ..inputs(None)
+                })
+                .is_err()
+        );
+
+        // A flap setting that doesn't go up to the weight leaves the others
+        let light_flaps_2: PerformanceProfile = toml::from_str(&PROFILE.replace(
+            "{ weight = 70000, v1 = 140, vr = 142, v2 = 146, distance = 1900 }",
+            "{ weight = 55000, v1 = 122, vr = 124, v2 = 129, distance = 1400 }",
+        ))?;
+        assert_eq!(light_flaps_2.takeoff(&inputs(None))?.flaps, "1+F");
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_landing() -> anyhow::Result<()> {
+        let profile: PerformanceProfile = toml::from_str(PROFILE)?;
+        let inputs = PerformanceInputs {
+            weight: 58000.0,
+            headwind: 10.0,
+            gust: 8.0,
+            ..inputs(Some(1800.0))
+        };
+
+        let results = profile.landing(&inputs, RunwayCondition::Wet)?;
+        let result = &results[0];
+        assert_eq!(result.vref, 133.0);
+        // Half the headwind plus the gust
+        assert_eq!(result.vapp, 146.0);
+        // Wet runway and 5 % headwind credit
+        let distances: Vec<f32> = result
+            .distances
+            .iter()
+            .map(|(_, distance)| distance.round())
+            .collect();
+        assert_eq!(distances, vec![1881.0, 1482.0]);
+        assert_eq!(
+            result.describe(inputs.runway_length)[1],
+            (
+                "Autobrake LOW".to_string(),
+                "1881 m, exceeds the runway".to_string()
+            )
+        );
+
+        assert_eq!(approach_additive(-5.0, 0.0), 5.0);
+        assert_eq!(approach_additive(30.0, 15.0), 20.0);
+
+        Ok(())
+    }
+}
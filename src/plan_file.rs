@@ -0,0 +1,803 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, bail};
+
+use crate::{
+    flightplan::FlightPlan,
+    icao_fpl,
+    route::{Route, Waypoint, WaypointKind},
+    utils,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanFormat {
+    XPlane,
+    Msfs,
+    LittleNavmap,
+    IcaoFpl,
+}
+
+impl PlanFormat {
+    pub const ALL: [PlanFormat; 4] = [
+        PlanFormat::XPlane,
+        PlanFormat::Msfs,
+        PlanFormat::LittleNavmap,
+        PlanFormat::IcaoFpl,
+    ];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            PlanFormat::XPlane => "fms",
+            PlanFormat::Msfs => "pln",
+            PlanFormat::LittleNavmap => "lnmpln",
+            PlanFormat::IcaoFpl => "txt",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            // X-Plane 12 still reads and writes the format of X-Plane 11
+            PlanFormat::XPlane => "X-Plane 11/12",
+            PlanFormat::Msfs => "MSFS",
+            PlanFormat::LittleNavmap => "Little Navmap",
+            PlanFormat::IcaoFpl => "ICAO FPL",
+        }
+    }
+
+    pub fn render(&self, flightplan: &FlightPlan) -> String {
+        let route = Route::from_flight_plan(flightplan);
+
+        match self {
+            PlanFormat::XPlane => render_fms(&route),
+            PlanFormat::Msfs => render_pln(&route),
+            PlanFormat::LittleNavmap => render_lnmpln(&route),
+            PlanFormat::IcaoFpl => {
+                icao_fpl::IcaoFlightPlan::from_flight_plan(flightplan).message() + "\n"
+            }
+        }
+    }
+
+    // The extension of a text file says nothing, so the contents decide
+    pub fn detect(contents: &str) -> Option<PlanFormat> {
+        let contents = contents.trim_start();
+        if contents.starts_with("(FPL") {
+            Some(PlanFormat::IcaoFpl)
+        } else if contents.contains("<LittleNavmap") {
+            Some(PlanFormat::LittleNavmap)
+        } else if contents.contains("<SimBase.Document") {
+            Some(PlanFormat::Msfs)
+        } else if contents
+            .lines()
+            .any(|line| line.trim().ends_with(" Version"))
+        {
+            Some(PlanFormat::XPlane)
+        } else {
+            None
+        }
+    }
+
+    pub fn parse(&self, contents: &str) -> Result<FlightPlan> {
+        let mut route = match self {
+            PlanFormat::XPlane => parse_fms(contents)?,
+            PlanFormat::Msfs => parse_pln(contents)?,
+            PlanFormat::LittleNavmap => parse_lnmpln(contents)?,
+            PlanFormat::IcaoFpl => return icao_fpl::parse(contents),
+        };
+        if route.origin.ident.is_empty() || route.destination.ident.is_empty() {
+            bail!("the {} plan has no departure or destination", self.label());
+        }
+        route.trim_procedures();
+
+        Ok(route.to_flight_plan())
+    }
+}
+
+// Either a path to a plan file or a pasted ICAO flight plan message
+pub async fn import(input: String) -> Result<FlightPlan> {
+    let contents = if input.trim_start().starts_with("(FPL") {
+        input
+    } else {
+        let path = input.trim();
+        tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("failed to read {path}"))?
+    };
+    let Some(format) = PlanFormat::detect(&contents) else {
+        bail!("unknown flight plan format");
+    };
+
+    format
+        .parse(&contents)
+        .with_context(|| format!("failed to import the {} plan", format.label()))
+}
+
+// Named after the airports like the simulators do, e.g. EDDFEGLL.fms
+pub async fn save(
+    contents: String,
+    format: PlanFormat,
+    file_stem: String,
+    directory: PathBuf,
+) -> Result<PathBuf> {
+    tokio::fs::create_dir_all(&directory)
+        .await
+        .with_context(|| format!("failed to create {}", directory.display()))?;
+    let path = directory.join(format!("{file_stem}.{}", format.extension()));
+
+    tokio::fs::write(&path, contents)
+        .await
+        .with_context(|| format!("failed to write {}", path.display()))?;
+
+    Ok(path)
+}
+
+fn render_fms(route: &Route) -> String {
+    let mut lines = vec!["I".to_string(), "1100 Version".to_string()];
+    let mut header = |key: &str, value: &str| {
+        if !value.is_empty() {
+            lines.push(format!("{key} {value}"));
+        }
+    };
+    header("CYCLE", &route.airac);
+    header("ADEP", &route.origin.ident);
+    header("DEPRWY", &runway(&route.departure_runway));
+    header("SID", &route.sid);
+    header("ADES", &route.destination.ident);
+    header("DESRWY", &runway(&route.arrival_runway));
+    header("STAR", &route.star);
+
+    lines.push(format!("NUMENR {}", route.waypoints.len() + 2));
+    lines.push(fms_entry(&route.origin, "ADEP"));
+    lines.extend(route.waypoints.iter().map(|waypoint| {
+        let via = if waypoint.is_direct() {
+            "DRCT"
+        } else {
+            &waypoint.airway
+        };
+        fms_entry(waypoint, via)
+    }));
+    lines.push(fms_entry(&route.destination, "ADES"));
+
+    lines.join("\n") + "\n"
+}
+
+fn fms_entry(waypoint: &Waypoint, via: &str) -> String {
+    let entry_type = match waypoint.kind {
+        WaypointKind::Airport => 1,
+        WaypointKind::Ndb => 2,
+        WaypointKind::Vor => 3,
+        WaypointKind::Fix => 11,
+        WaypointKind::Coordinates => 28,
+    };
+
+    format!(
+        "{entry_type} {} {via} {:.6} {:.6} {:.6}",
+        waypoint.ident, waypoint.altitude_ft, waypoint.latitude, waypoint.longitude
+    )
+}
+
+fn render_pln(route: &Route) -> String {
+    let origin = &route.origin;
+    let destination = &route.destination;
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<SimBase.Document Type=\"AceXML\" version=\"1,0\">\n");
+    xml.push_str("    <Descr>AceXML Document</Descr>\n");
+    xml.push_str("    <FlightPlan.FlightPlan>\n");
+    for (tag, value) in [
+        (
+            "Title",
+            format!("{} to {}", origin.ident, destination.ident),
+        ),
+        ("FPType", "IFR".to_string()),
+        ("RouteType", "HighAlt".to_string()),
+        ("CruisingAlt", route.cruise_altitude_ft.to_string()),
+        ("DepartureID", origin.ident.clone()),
+        ("DepartureLLA", format_lla(origin)),
+        ("DestinationID", destination.ident.clone()),
+        ("DestinationLLA", format_lla(destination)),
+        ("Descr", format!("{}, {}", origin.ident, destination.ident)),
+        ("DepartureName", utils::escape_html(&origin.name)),
+        ("DestinationName", utils::escape_html(&destination.name)),
+    ] {
+        xml.push_str(&format!("        <{tag}>{value}</{tag}>\n"));
+    }
+
+    let waypoints = [origin]
+        .into_iter()
+        .chain(&route.waypoints)
+        .chain([destination]);
+    let last = route.waypoints.len();
+    for (index, waypoint) in waypoints.enumerate() {
+        // MSFS flies the procedures itself, it only needs to know where they end and start
+        let procedure = if index == 1 && index <= last && !route.sid.is_empty() {
+            Some(("DepartureFP", &route.sid, &route.departure_runway))
+        } else if index == last && index > 0 && !route.star.is_empty() {
+            Some(("ArrivalFP", &route.star, &route.arrival_runway))
+        } else {
+            None
+        };
+        let waypoint_type = match waypoint.kind {
+            WaypointKind::Airport => "Airport",
+            WaypointKind::Vor => "VOR",
+            WaypointKind::Ndb => "NDB",
+            WaypointKind::Fix => "Intersection",
+            WaypointKind::Coordinates => "User",
+        };
+        let ident = utils::escape_html(&waypoint.ident);
+        xml.push_str(&format!("        <ATCWaypoint id=\"{ident}\">\n"));
+        xml.push_str(&format!(
+            "            <ATCWaypointType>{waypoint_type}</ATCWaypointType>\n"
+        ));
+        xml.push_str(&format!(
+            "            <WorldPosition>{}</WorldPosition>\n",
+            format_lla(waypoint)
+        ));
+        if !waypoint.is_direct() {
+            xml.push_str(&format!(
+                "            <ATCAirway>{}</ATCAirway>\n",
+                utils::escape_html(&waypoint.airway)
+            ));
+        }
+        if let Some((tag, name, runway)) = procedure {
+            xml.push_str(&format!(
+                "            <{tag}>{}</{tag}>\n",
+                utils::escape_html(name)
+            ));
+            let (number, designator) = split_runway(runway);
+            if !number.is_empty() {
+                xml.push_str(&format!(
+                    "            <RunwayNumberFP>{number}</RunwayNumberFP>\n"
+                ));
+                xml.push_str(&format!(
+                    "            <RunwayDesignatorFP>{designator}</RunwayDesignatorFP>\n"
+                ));
+            }
+        }
+        if waypoint.kind != WaypointKind::Coordinates {
+            xml.push_str(&format!(
+                "            <ICAO>\n                <ICAOIdent>{ident}</ICAOIdent>\n            </ICAO>\n"
+            ));
+        }
+        xml.push_str("        </ATCWaypoint>\n");
+    }
+
+    xml.push_str("    </FlightPlan.FlightPlan>\n");
+    xml.push_str("</SimBase.Document>\n");
+    xml
+}
+
+// MSFS spells out the letter of parallel runways, like 25 and CENTER
+fn split_runway(runway: &str) -> (&str, &str) {
+    let (number, letter) = runway.split_at(
+        runway
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(runway.len()),
+    );
+    let designator = match letter {
+        "L" => "LEFT",
+        "R" => "RIGHT",
+        "C" => "CENTER",
+        _ => "NONE",
+    };
+
+    (number, designator)
+}
+
+// Degrees, minutes and seconds, like N50° 1' 59.90",E8° 34' 13.64",+000364.00
+fn format_lla(waypoint: &Waypoint) -> String {
+    let format = |value: f64, positive: char, negative: char| {
+        let hemisphere = if value < 0.0 { negative } else { positive };
+        let seconds = (value.abs() * 360_000.0).round() / 100.0;
+        let degrees = (seconds / 3600.0).floor();
+        let minutes = ((seconds - degrees * 3600.0) / 60.0).floor();
+        let seconds = seconds - degrees * 3600.0 - minutes * 60.0;
+
+        format!("{hemisphere}{degrees}° {minutes}' {seconds:.2}\"")
+    };
+    let altitude = waypoint.altitude_ft;
+    let sign = if altitude < 0.0 { '-' } else { '+' };
+
+    format!(
+        "{},{},{sign}{:09.2}",
+        format(waypoint.latitude, 'N', 'S'),
+        format(waypoint.longitude, 'E', 'W'),
+        altitude.abs()
+    )
+}
+
+fn render_lnmpln(route: &Route) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<LittleNavmap xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" xsi:noNamespaceSchemaLocation=\"https://www.littlenavmap.org/schema/lnmpln.xsd\">\n");
+    xml.push_str("  <Flightplan>\n");
+    xml.push_str("    <Header>\n");
+    xml.push_str("      <FlightplanType>IFR</FlightplanType>\n");
+    xml.push_str(&format!(
+        "      <CruisingAlt>{}</CruisingAlt>\n",
+        route.cruise_altitude_ft
+    ));
+    xml.push_str("      <FileVersion>1.2</FileVersion>\n");
+    xml.push_str("      <ProgramName>flypad</ProgramName>\n");
+    xml.push_str("    </Header>\n");
+
+    let procedures = [
+        ("SID", &route.sid, &route.departure_runway),
+        ("STAR", &route.star, &route.arrival_runway),
+    ];
+    if procedures.iter().any(|(_, name, _)| !name.is_empty()) {
+        xml.push_str("    <Procedures>\n");
+        for (tag, name, runway) in procedures {
+            if name.is_empty() {
+                continue;
+            }
+            xml.push_str(&format!("      <{tag}>\n"));
+            xml.push_str(&format!(
+                "        <Name>{}</Name>\n",
+                utils::escape_html(name)
+            ));
+            if !runway.is_empty() {
+                xml.push_str(&format!(
+                    "        <Runway>{}</Runway>\n",
+                    utils::escape_html(runway)
+                ));
+            }
+            xml.push_str(&format!("      </{tag}>\n"));
+        }
+        xml.push_str("    </Procedures>\n");
+    }
+
+    xml.push_str("    <Waypoints>\n");
+    let waypoints = [&route.origin]
+        .into_iter()
+        .chain(&route.waypoints)
+        .chain([&route.destination]);
+    for waypoint in waypoints {
+        let waypoint_type = match waypoint.kind {
+            WaypointKind::Airport => "AIRPORT",
+            WaypointKind::Vor => "VOR",
+            WaypointKind::Ndb => "NDB",
+            WaypointKind::Fix => "WAYPOINT",
+            WaypointKind::Coordinates => "USER",
+        };
+        xml.push_str("      <Waypoint>\n");
+        if !waypoint.name.is_empty() && waypoint.kind == WaypointKind::Airport {
+            xml.push_str(&format!(
+                "        <Name>{}</Name>\n",
+                utils::escape_html(&waypoint.name)
+            ));
+        }
+        xml.push_str(&format!(
+            "        <Ident>{}</Ident>\n",
+            utils::escape_html(&waypoint.ident)
+        ));
+        if !waypoint.is_direct() {
+            xml.push_str(&format!(
+                "        <Airway>{}</Airway>\n",
+                utils::escape_html(&waypoint.airway)
+            ));
+        }
+        xml.push_str(&format!("        <Type>{waypoint_type}</Type>\n"));
+        xml.push_str(&format!(
+            "        <Pos Lon=\"{:.6}\" Lat=\"{:.6}\" Alt=\"{:.2}\"/>\n",
+            waypoint.longitude, waypoint.latitude, waypoint.altitude_ft
+        ));
+        xml.push_str("      </Waypoint>\n");
+    }
+    xml.push_str("    </Waypoints>\n");
+    xml.push_str("  </Flightplan>\n");
+    xml.push_str("</LittleNavmap>\n");
+    xml
+}
+
+// X-Plane writes runways with a prefix, like RW25C
+fn runway(runway: &str) -> String {
+    if runway.is_empty() {
+        String::new()
+    } else {
+        format!("RW{runway}")
+    }
+}
+
+fn parse_fms(contents: &str) -> Result<Route> {
+    // Version 3 of X-Plane 10 has no airways and a different column order
+    if !contents.lines().any(|line| line.trim() == "1100 Version") {
+        bail!("only version 1100 of the X-Plane format is supported");
+    }
+
+    let mut route = Route::default();
+    for line in contents.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields.as_slice() {
+            ["CYCLE", cycle] => route.airac = cycle.to_string(),
+            ["ADEP", ident] => route.origin.ident = ident.to_string(),
+            ["DEPRWY", runway] => {
+                route.departure_runway = runway.trim_start_matches("RW").to_string()
+            }
+            ["SID", sid] => route.sid = sid.to_string(),
+            ["ADES", ident] => route.destination.ident = ident.to_string(),
+            ["DESRWY", runway] => {
+                route.arrival_runway = runway.trim_start_matches("RW").to_string()
+            }
+            ["STAR", star] => route.star = star.to_string(),
+            [entry_type, ident, via, altitude, latitude, longitude, ..] => {
+                let Ok(entry_type) = entry_type.parse::<u32>() else {
+                    continue;
+                };
+                let waypoint = Waypoint {
+                    ident: ident.to_string(),
+                    kind: match entry_type {
+                        1 => WaypointKind::Airport,
+                        2 => WaypointKind::Ndb,
+                        3 => WaypointKind::Vor,
+                        28 => WaypointKind::Coordinates,
+                        _ => WaypointKind::Fix,
+                    },
+                    latitude: parse_number(latitude)?,
+                    longitude: parse_number(longitude)?,
+                    altitude_ft: parse_number(altitude)?,
+                    airway: match *via {
+                        "DRCT" => "DCT".to_string(),
+                        via => via.to_string(),
+                    },
+                    ..Default::default()
+                };
+                match *via {
+                    "ADEP" => {
+                        route.origin = Waypoint {
+                            airway: String::new(),
+                            ..waypoint
+                        }
+                    }
+                    "ADES" => {
+                        route.destination = Waypoint {
+                            airway: String::new(),
+                            ..waypoint
+                        }
+                    }
+                    _ => route.waypoints.push(waypoint),
+                }
+            }
+            _ => (),
+        }
+    }
+    // The cruise altitude isn't part of the file, the highest waypoint is the best guess
+    route.cruise_altitude_ft = route
+        .waypoints
+        .iter()
+        .map(|waypoint| waypoint.altitude_ft.round() as u32)
+        .max()
+        .unwrap_or_default();
+
+    Ok(route)
+}
+
+fn parse_pln(contents: &str) -> Result<Route> {
+    let Some(plan) = elements(contents, "FlightPlan.FlightPlan")
+        .into_iter()
+        .next()
+    else {
+        bail!("the file has no flight plan");
+    };
+
+    let mut route = Route {
+        cruise_altitude_ft: element_text(plan, "CruisingAlt")
+            .map(|altitude| parse_number(&altitude))
+            .transpose()?
+            .unwrap_or_default()
+            .round() as u32,
+        ..Default::default()
+    };
+    for element in elements(plan, "ATCWaypoint") {
+        let ident = element_text(element, "ICAOIdent")
+            .or_else(|| attribute(element, "id"))
+            .unwrap_or_default();
+        let Some((latitude, longitude, altitude_ft)) = element_text(element, "WorldPosition")
+            .as_deref()
+            .and_then(parse_lla)
+        else {
+            bail!("{ident} has no position");
+        };
+        // Waypoints of the procedures name them, with the runway they start or end at
+        let departure = element_text(element, "DepartureFP").unwrap_or_default();
+        let arrival = element_text(element, "ArrivalFP").unwrap_or_default();
+        let runway = element_text(element, "RunwayNumberFP").map(|number| {
+            let designator = element_text(element, "RunwayDesignatorFP").unwrap_or_default();
+            match designator.as_str() {
+                "LEFT" | "RIGHT" | "CENTER" => format!("{number}{}", &designator[..1]),
+                _ => number,
+            }
+        });
+        if !departure.is_empty() {
+            route.sid = departure.clone();
+            route.departure_runway = runway.clone().unwrap_or(route.departure_runway);
+        }
+        if !arrival.is_empty() {
+            route.star = arrival.clone();
+            route.arrival_runway = runway.unwrap_or(route.arrival_runway);
+        }
+
+        route.waypoints.push(Waypoint {
+            ident,
+            kind: match element_text(element, "ATCWaypointType").as_deref() {
+                Some("Airport") => WaypointKind::Airport,
+                Some("VOR") => WaypointKind::Vor,
+                Some("NDB") => WaypointKind::Ndb,
+                Some("User") => WaypointKind::Coordinates,
+                _ => WaypointKind::Fix,
+            },
+            latitude,
+            longitude,
+            altitude_ft,
+            airway: element_text(element, "ATCAirway")
+                .or_else(|| {
+                    [departure, arrival]
+                        .into_iter()
+                        .find(|procedure| !procedure.is_empty())
+                })
+                .unwrap_or_default(),
+            ..Default::default()
+        });
+    }
+
+    let departure = element_text(plan, "DepartureID").unwrap_or_default();
+    let destination = element_text(plan, "DestinationID").unwrap_or_default();
+    let is_airport = |waypoint: Option<&Waypoint>, ident: &str| {
+        waypoint.is_some_and(|waypoint| {
+            waypoint.kind == WaypointKind::Airport && waypoint.ident == ident
+        })
+    };
+    if is_airport(route.waypoints.first(), &departure) {
+        route.origin = route.waypoints.remove(0);
+        route.origin.airway = String::new();
+    }
+    if is_airport(route.waypoints.last(), &destination) {
+        route.destination = route.waypoints.pop().unwrap_or_default();
+        route.destination.airway = String::new();
+    }
+    route.origin.name = element_text(plan, "DepartureName").unwrap_or_default();
+    route.destination.name = element_text(plan, "DestinationName").unwrap_or_default();
+
+    Ok(route)
+}
+
+fn parse_lnmpln(contents: &str) -> Result<Route> {
+    let mut route = Route::default();
+    if let Some(header) = elements(contents, "Header").into_iter().next() {
+        route.cruise_altitude_ft = element_text(header, "CruisingAlt")
+            .map(|altitude| parse_number(&altitude))
+            .transpose()?
+            .unwrap_or_default()
+            .round() as u32;
+    }
+    if let Some(procedures) = elements(contents, "Procedures").into_iter().next() {
+        for sid in elements(procedures, "SID") {
+            route.sid = element_text(sid, "Name").unwrap_or_default();
+            route.departure_runway = element_text(sid, "Runway").unwrap_or_default();
+        }
+        for star in elements(procedures, "STAR") {
+            route.star = element_text(star, "Name").unwrap_or_default();
+            route.arrival_runway = element_text(star, "Runway").unwrap_or_default();
+        }
+    }
+
+    // Alternates are listed as waypoints too, but in a section of their own
+    let Some(waypoints) = elements(contents, "Waypoints").into_iter().next() else {
+        bail!("the file has no waypoints");
+    };
+    for element in elements(waypoints, "Waypoint") {
+        let ident = element_text(element, "Ident").unwrap_or_default();
+        let Some(position) = elements(element, "Pos").into_iter().next() else {
+            bail!("{ident} has no position");
+        };
+        let coordinate = |name: &str| {
+            attribute(position, name)
+                .map(|value| parse_number(&value))
+                .transpose()
+        };
+
+        route.waypoints.push(Waypoint {
+            ident,
+            name: element_text(element, "Name").unwrap_or_default(),
+            kind: match element_text(element, "Type").as_deref() {
+                Some("AIRPORT") => WaypointKind::Airport,
+                Some("VOR") => WaypointKind::Vor,
+                Some("NDB") => WaypointKind::Ndb,
+                Some("USER") => WaypointKind::Coordinates,
+                _ => WaypointKind::Fix,
+            },
+            latitude: coordinate("Lat")?.unwrap_or_default(),
+            longitude: coordinate("Lon")?.unwrap_or_default(),
+            altitude_ft: coordinate("Alt")?.unwrap_or_default(),
+            airway: element_text(element, "Airway").unwrap_or_default(),
+        });
+    }
+
+    // The airports are the first and last waypoint
+    if route.waypoints.len() < 2 {
+        bail!("the plan needs a departure and destination");
+    }
+    route.origin = route.waypoints.remove(0);
+    route.destination = route.waypoints.pop().unwrap_or_default();
+
+    Ok(route)
+}
+
+fn parse_number(value: &str) -> Result<f64> {
+    value
+        .trim()
+        .parse()
+        .with_context(|| format!("{value} is not a number"))
+}
+
+// The reverse of format_lla
+fn parse_lla(lla: &str) -> Option<(f64, f64, f64)> {
+    let parse = |value: &str, negative: char| {
+        let value = value.trim();
+        let sign = if value.starts_with(negative) {
+            -1.0
+        } else {
+            1.0
+        };
+        let parts: Vec<f64> = value
+            .get(1..)?
+            .split(['°', '\'', '"'])
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .map(str::parse)
+            .collect::<Result<_, _>>()
+            .ok()?;
+        let [degrees, minutes, seconds] = parts.as_slice() else {
+            return None;
+        };
+
+        Some(sign * (degrees + minutes / 60.0 + seconds / 3600.0))
+    };
+
+    let mut parts = lla.split(',');
+    let latitude = parse(parts.next()?, 'S')?;
+    let longitude = parse(parts.next()?, 'W')?;
+    let altitude = parts
+        .next()
+        .map_or(Some(0.0), |altitude| altitude.trim().parse().ok())?;
+
+    Some((latitude, longitude, altitude))
+}
+
+// Every element with the tag, including the tags themselves. The simulators write simple files
+// without comments or nested elements of the same name, so searching the text is enough.
+fn elements<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+    let open = format!("<{tag}");
+    let close = format!("</{tag}>");
+    let mut elements = Vec::new();
+    let mut rest = xml;
+
+    while let Some(start) = rest.find(&open) {
+        let element = &rest[start..];
+        let after_name = element[open.len()..].chars().next();
+        if !after_name.is_some_and(|c| c == '>' || c == '/' || c.is_whitespace()) {
+            rest = &element[open.len()..];
+            continue;
+        }
+
+        let Some(tag_end) = element.find('>') else {
+            break;
+        };
+        let end = if element[..tag_end].ends_with('/') {
+            tag_end + 1
+        } else if let Some(close_start) = element.find(&close) {
+            close_start + close.len()
+        } else {
+            break;
+        };
+        elements.push(&element[..end]);
+        rest = &element[end..];
+    }
+
+    elements
+}
+
+fn element_text(xml: &str, tag: &str) -> Option<String> {
+    let element = elements(xml, tag).into_iter().next()?;
+    let start = element.find('>')? + 1;
+    let end = element.rfind("</")?;
+
+    Some(utils::unescape_html(element.get(start..end)?.trim()))
+}
+
+fn attribute(element: &str, name: &str) -> Option<String> {
+    let tag = &element[..element.find('>')?];
+    let start = tag.find(&format!(" {name}=\""))? + name.len() + 3;
+    let end = start + tag[start..].find('"')?;
+
+    Some(utils::unescape_html(&tag[start..end]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_render() -> anyhow::Result<()> {
+        let flightplan: FlightPlan =
+            serde_json::from_str(include_str!("../tests/fixtures/EDDFEGLL.json"))?;
+
+        assert_eq!(
+            PlanFormat::XPlane.render(&flightplan),
+            include_str!("../tests/fixtures/EDDFEGLL.fms")
+        );
+        assert_eq!(
+            PlanFormat::Msfs.render(&flightplan),
+            include_str!("../tests/fixtures/EDDFEGLL.pln")
+        );
+        assert_eq!(
+            PlanFormat::LittleNavmap.render(&flightplan),
+            include_str!("../tests/fixtures/EDDFEGLL.lnmpln")
+        );
+        assert_eq!(
+            PlanFormat::IcaoFpl.render(&flightplan),
+            include_str!("../tests/fixtures/EDDFEGLL.txt")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_parse() -> anyhow::Result<()> {
+        for (format, contents) in [
+            (
+                PlanFormat::XPlane,
+                include_str!("../tests/fixtures/EDDFEGLL.fms"),
+            ),
+            (
+                PlanFormat::Msfs,
+                include_str!("../tests/fixtures/EDDFEGLL.pln"),
+            ),
+            (
+                PlanFormat::LittleNavmap,
+                include_str!("../tests/fixtures/EDDFEGLL.lnmpln"),
+            ),
+            (
+                PlanFormat::IcaoFpl,
+                include_str!("../tests/fixtures/EDDFEGLL.txt"),
+            ),
+        ] {
+            assert_eq!(PlanFormat::detect(contents), Some(format));
+            let flightplan = format.parse(contents)?;
+            assert_eq!(flightplan.origin.icao_code, "EDDF");
+            assert_eq!(flightplan.destination.icao_code, "EGLL");
+            assert_eq!(flightplan.flight_information.initial_altitude, "36000");
+            assert_eq!(
+                flightplan.flight_information.route_navigraph,
+                "SOBRA3G SOBRA Y6 DIK DCT KONAN KONA1H"
+            );
+            assert_eq!(format.render(&flightplan), contents);
+        }
+
+        let flightplan =
+            PlanFormat::IcaoFpl.parse(include_str!("../tests/fixtures/EDDFEGLL.txt"))?;
+        assert_eq!(flightplan.callsign(), "DLH900");
+        assert_eq!(flightplan.aircraft.icao_code, "A20N");
+        assert_eq!(flightplan.alternate[0].icao_code, "EGKK");
+        assert_eq!(flightplan.times.sched_out, "1760785200");
+
+        // SimBrief lists the waypoints of the procedures too
+        let fms = "I\n1100 Version\nADEP EDDF\nSID SOBRA3G\nADES EGLL\nSTAR KONA1H\nNUMENR 6\n\
+                   1 EDDF ADEP 364 50.03 8.57\n11 DF152 SOBRA3G 5000 50.01 8.31\n\
+                   11 SOBRA SOBRA3G 15000 50.25 7.90\n11 KONAN KONA1H 24000 51.33 2.00\n\
+                   3 BIG KONA1H 7000 51.33 0.03\n1 EGLL ADES 83 51.47 -0.46\n";
+        let route = Route::from_flight_plan(&PlanFormat::XPlane.parse(fms)?);
+        assert_eq!(route.icao_route(), "SOBRA3G SOBRA DCT KONAN KONA1H");
+        assert_eq!(route.cruise_altitude_ft, 24000);
+
+        let fpl =
+            "(FPL-DAINA-IG\n-C172/L-S/C\n-EDFE0800\n-N0110A045 DCT 5015N00754E DCT\n-EDDS0100)";
+        let flightplan = PlanFormat::IcaoFpl.parse(fpl)?;
+        let route = Route::from_flight_plan(&flightplan);
+        assert_eq!(flightplan.callsign(), "DAINA");
+        assert_eq!(route.cruise_altitude_ft, 4500);
+        assert_eq!(route.waypoints[0].kind, WaypointKind::Coordinates);
+        assert_eq!(route.waypoints[0].latitude, 50.25);
+        assert_eq!(route.waypoints[0].longitude, 7.9);
+        assert!(PlanFormat::IcaoFpl.parse("(FPL-DAINA-IG)").is_err());
+
+        Ok(())
+    }
+}
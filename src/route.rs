@@ -0,0 +1,318 @@
+use crate::{
+    flightplan::{self, FlightPlan},
+    utils,
+};
+
+// Top of climb and descent are in the navlog but aren't places to fly to
+const PSEUDO_WAYPOINTS: [&str; 2] = ["TOC", "TOD"];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WaypointKind {
+    Airport,
+    #[default]
+    Fix,
+    Vor,
+    Ndb,
+    Coordinates,
+}
+
+impl WaypointKind {
+    fn from_navlog(fix_type: &str) -> WaypointKind {
+        match fix_type {
+            "apt" => WaypointKind::Airport,
+            "vor" => WaypointKind::Vor,
+            "ndb" => WaypointKind::Ndb,
+            "ltlg" => WaypointKind::Coordinates,
+            _ => WaypointKind::Fix,
+        }
+    }
+
+    fn navlog_type(&self) -> &'static str {
+        match self {
+            WaypointKind::Airport => "apt",
+            WaypointKind::Vor => "vor",
+            WaypointKind::Ndb => "ndb",
+            WaypointKind::Coordinates => "ltlg",
+            WaypointKind::Fix => "wpt",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Waypoint {
+    pub ident: String,
+    pub name: String,
+    pub kind: WaypointKind,
+    pub latitude: f64,
+    pub longitude: f64,
+    // The planned altitude, or the elevation for airports
+    pub altitude_ft: f64,
+    // The airway leading to the waypoint, empty when it is flown to directly
+    pub airway: String,
+}
+
+impl Waypoint {
+    pub fn is_direct(&self) -> bool {
+        self.airway.is_empty() || self.airway == "DCT"
+    }
+}
+
+// The route between the airports in the form the simulator and FMS files use. The waypoints of
+// the procedures are left to the simulator, only where the SID ends and the STAR starts is kept.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Route {
+    pub origin: Waypoint,
+    pub destination: Waypoint,
+    pub departure_runway: String,
+    pub arrival_runway: String,
+    pub sid: String,
+    pub star: String,
+    pub waypoints: Vec<Waypoint>,
+    pub cruise_altitude_ft: u32,
+    pub airac: String,
+}
+
+impl Route {
+    pub fn from_flight_plan(flightplan: &FlightPlan) -> Route {
+        let information = &flightplan.flight_information;
+        let sid = utils::known(&information.sid_ident).to_string();
+        let star = utils::known(&information.star_ident).to_string();
+
+        let fixes: Vec<&flightplan::Fix> = flightplan
+            .navlog
+            .fix
+            .iter()
+            .filter(|fix| !PSEUDO_WAYPOINTS.contains(&fix.ident.as_str()))
+            .filter(|fix| {
+                fix.ident != flightplan.origin.icao_code
+                    && fix.ident != flightplan.destination.icao_code
+            })
+            .collect();
+        let is_procedure =
+            |index: usize| fixes.get(index).is_some_and(|fix| fix.is_sid_star == "1");
+        let enroute_start = fixes
+            .iter()
+            .position(|fix| fix.is_sid_star != "1")
+            .unwrap_or(fixes.len());
+
+        let waypoints = fixes
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| {
+                let sid_exit = *index < enroute_start && !is_procedure(index + 1);
+                let star_entry =
+                    *index >= enroute_start && !index.checked_sub(1).is_some_and(&is_procedure);
+                !is_procedure(*index) || sid_exit || star_entry
+            })
+            .map(|(index, fix)| {
+                let mut waypoint = Waypoint {
+                    ident: fix.ident.clone(),
+                    name: utils::known(&fix.name).to_string(),
+                    kind: WaypointKind::from_navlog(&fix.fix_type),
+                    latitude: fix.pos_lat.parse().unwrap_or_default(),
+                    longitude: fix.pos_long.parse().unwrap_or_default(),
+                    altitude_ft: fix.altitude_feet.parse().unwrap_or_default(),
+                    airway: utils::known(&fix.via).to_string(),
+                };
+                // The SID leads to the first waypoint and the STAR starts at the last one
+                if index < enroute_start {
+                    waypoint.airway = String::new();
+                } else if waypoint.airway.is_empty() || waypoint.airway == star {
+                    waypoint.airway = "DCT".to_string();
+                }
+                waypoint
+            })
+            .collect();
+
+        Route {
+            origin: airport_waypoint(&flightplan.origin),
+            destination: airport_waypoint(&flightplan.destination),
+            departure_runway: utils::known(&flightplan.origin.plan_rwy).to_string(),
+            arrival_runway: utils::known(&flightplan.destination.plan_rwy).to_string(),
+            sid,
+            star,
+            waypoints,
+            cruise_altitude_ft: information.initial_altitude.parse().unwrap_or_default(),
+            airac: utils::known(&flightplan.params.airac).to_string(),
+        }
+    }
+
+    // The route as it is filed, like "SOBRA3G SOBRA Y6 DIK DCT KONAN KONA1H". Consecutive
+    // waypoints on the same airway are left out.
+    pub fn icao_route(&self) -> String {
+        let mut items: Vec<String> = Vec::new();
+        if !self.sid.is_empty() {
+            items.push(self.sid.clone());
+        }
+
+        for (index, waypoint) in self.waypoints.iter().enumerate() {
+            let next_airway = self
+                .waypoints
+                .get(index + 1)
+                .map(|next| next.airway.as_str());
+            if index == 0 {
+                items.push(waypoint.ident.clone());
+            } else if waypoint.is_direct() {
+                items.extend(["DCT".to_string(), waypoint.ident.clone()]);
+            } else if next_airway != Some(waypoint.airway.as_str()) {
+                items.extend([waypoint.airway.clone(), waypoint.ident.clone()]);
+            }
+        }
+
+        if !self.star.is_empty() {
+            items.push(self.star.clone());
+        }
+
+        items.join(" ")
+    }
+
+    // Imported files may list every waypoint of the procedures, like SimBrief plans only where
+    // the SID ends and the STAR starts is kept
+    pub fn trim_procedures(&mut self) {
+        let on = |waypoint: Option<&Waypoint>, procedure: &str| {
+            !procedure.is_empty() && waypoint.is_some_and(|waypoint| waypoint.airway == procedure)
+        };
+        let waypoints = std::mem::take(&mut self.waypoints);
+
+        self.waypoints = waypoints
+            .iter()
+            .enumerate()
+            .filter(|(index, waypoint)| {
+                let in_sid =
+                    on(Some(waypoint), &self.sid) && on(waypoints.get(index + 1), &self.sid);
+                let in_star = on(Some(waypoint), &self.star)
+                    && index
+                        .checked_sub(1)
+                        .is_some_and(|previous| on(waypoints.get(previous), &self.star));
+                !in_sid && !in_star
+            })
+            .map(|(_, waypoint)| {
+                let mut waypoint = waypoint.clone();
+                if on(Some(&waypoint), &self.sid) {
+                    waypoint.airway = String::new();
+                } else if on(Some(&waypoint), &self.star) {
+                    waypoint.airway = "DCT".to_string();
+                }
+                waypoint
+            })
+            .collect();
+    }
+
+    // A flight plan for routes that weren't planned with SimBrief. The waypoints become the
+    // navlog with the SID ending at the first one. Marking where the STAR starts as well would
+    // hide the first waypoint when there is nothing in between.
+    pub fn to_flight_plan(&self) -> FlightPlan {
+        let fix = self
+            .waypoints
+            .iter()
+            .enumerate()
+            .map(|(index, waypoint)| {
+                let sid_exit = index == 0 && !self.sid.is_empty();
+                let via = if sid_exit {
+                    self.sid.clone()
+                } else if waypoint.airway.is_empty() {
+                    "DCT".to_string()
+                } else {
+                    waypoint.airway.clone()
+                };
+
+                flightplan::Fix {
+                    ident: waypoint.ident.clone(),
+                    name: waypoint.name.clone(),
+                    fix_type: waypoint.kind.navlog_type().to_string(),
+                    pos_lat: format!("{:.6}", waypoint.latitude),
+                    pos_long: format!("{:.6}", waypoint.longitude),
+                    via,
+                    is_sid_star: if sid_exit { "1" } else { "0" }.to_string(),
+                    altitude_feet: format!("{:.0}", waypoint.altitude_ft),
+                }
+            })
+            .collect();
+        let route = self.icao_route();
+
+        FlightPlan {
+            origin: flight_plan_airport(&self.origin, &self.departure_runway),
+            destination: flight_plan_airport(&self.destination, &self.arrival_runway),
+            flight_information: flightplan::FlightOverview {
+                initial_altitude: self.cruise_altitude_ft.to_string(),
+                route_ifps: route.clone(),
+                route_navigraph: route,
+                sid_ident: self.sid.clone(),
+                star_ident: self.star.clone(),
+                ..Default::default()
+            },
+            params: flightplan::Params {
+                airac: self.airac.clone(),
+                ..Default::default()
+            },
+            navlog: flightplan::Navlog { fix },
+            ..Default::default()
+        }
+    }
+}
+
+fn airport_waypoint(airport: &flightplan::Airport) -> Waypoint {
+    Waypoint {
+        ident: airport.icao_code.clone(),
+        name: utils::known(&airport.name).to_string(),
+        kind: WaypointKind::Airport,
+        latitude: airport.pos_lat.parse().unwrap_or_default(),
+        longitude: airport.pos_long.parse().unwrap_or_default(),
+        altitude_ft: airport.elevation.parse().unwrap_or_default(),
+        airway: String::new(),
+    }
+}
+
+// ICAO messages don't locate the airports, leaving their position empty lets the airport
+// database fill it in
+fn flight_plan_airport(waypoint: &Waypoint, runway: &str) -> flightplan::Airport {
+    let located = waypoint.latitude != 0.0 || waypoint.longitude != 0.0;
+    let coordinate = |value: f64| {
+        if located {
+            format!("{value:.6}")
+        } else {
+            String::new()
+        }
+    };
+
+    flightplan::Airport {
+        icao_code: waypoint.ident.clone(),
+        name: waypoint.name.clone(),
+        plan_rwy: runway.to_string(),
+        pos_lat: coordinate(waypoint.latitude),
+        pos_long: coordinate(waypoint.longitude),
+        elevation: format!("{:.0}", waypoint.altitude_ft),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_route() -> anyhow::Result<()> {
+        let flightplan: FlightPlan =
+            serde_json::from_str(include_str!("../tests/fixtures/EDDFEGLL.json"))?;
+        let route = Route::from_flight_plan(&flightplan);
+
+        let idents: Vec<&str> = route
+            .waypoints
+            .iter()
+            .map(|waypoint| waypoint.ident.as_str())
+            .collect();
+        assert_eq!(idents, vec!["SOBRA", "KOPAG", "DIK", "KONAN"]);
+        assert_eq!(route.waypoints[2].kind, WaypointKind::Vor);
+        assert_eq!(route.origin.altitude_ft, 364.0);
+        assert_eq!(route.cruise_altitude_ft, 36000);
+        assert_eq!(route.icao_route(), "SOBRA3G SOBRA Y6 DIK DCT KONAN KONA1H");
+
+        let enroute = Route {
+            waypoints: route.waypoints[1..3].to_vec(),
+            ..Default::default()
+        };
+        assert_eq!(enroute.icao_route(), "KOPAG Y6 DIK");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,212 @@
+use std::fmt::Display;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RunwayCondition {
+    Dry,
+    Wet,
+    Snow,
+    Slush,
+    Ice,
+}
+
+impl Display for RunwayCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            RunwayCondition::Dry => "dry",
+            RunwayCondition::Wet => "wet",
+            RunwayCondition::Snow => "snow",
+            RunwayCondition::Slush => "slush",
+            RunwayCondition::Ice => "ice",
+        };
+
+        write!(f, "{label}")
+    }
+}
+
+impl RunwayCondition {
+    // Rough factors on the dry landing distance, the braking action reports of real operations
+    // are a lot more detailed than this
+    pub fn landing_distance_factor(&self) -> f32 {
+        match self {
+            RunwayCondition::Dry => 1.0,
+            RunwayCondition::Wet => 1.2,
+            RunwayCondition::Snow => 1.6,
+            RunwayCondition::Slush => 1.8,
+            RunwayCondition::Ice => 3.0,
+        }
+    }
+
+    // First digit of the runway state group
+    fn from_deposit(deposit: char) -> Option<RunwayCondition> {
+        match deposit {
+            '0' => Some(RunwayCondition::Dry),
+            // Damp, wet or water patches and frost
+            '1' | '2' | '3' => Some(RunwayCondition::Wet),
+            // Dry snow, compacted snow and frozen ruts
+            '4' | '8' | '9' => Some(RunwayCondition::Snow),
+            // Wet snow and slush
+            '5' | '6' => Some(RunwayCondition::Slush),
+            '7' => Some(RunwayCondition::Ice),
+            _ => None,
+        }
+    }
+}
+
+// Uses the runway state groups of the METAR, like R27L/290150 or R88/CLRD//. Without a report
+// the runway is assumed wet in rain, covered in snow when snow, ice pellets or hail fall and
+// icy with freezing rain or drizzle. The worst report or weather for the runway wins.
+pub fn from_metar(metar: &str, runway: &str) -> RunwayCondition {
+    let tokens = observation_tokens(metar);
+
+    let reported = tokens
+        .iter()
+        .filter_map(|token| parse_runway_state(token))
+        .filter(|(designator, _)| designator == "88" || designator.eq_ignore_ascii_case(runway))
+        .map(|(_, condition)| condition)
+        .max();
+
+    reported.unwrap_or_else(|| {
+        tokens
+            .iter()
+            .filter_map(|token| precipitation_condition(token))
+            .max()
+            .unwrap_or(RunwayCondition::Dry)
+    })
+}
+
+// The tokens between the observation time and the trend or remarks
+fn observation_tokens(metar: &str) -> Vec<&str> {
+    metar
+        .split_whitespace()
+        .skip_while(|token| !(token.len() == 7 && token.ends_with('Z')))
+        .skip(1)
+        .take_while(|token| !matches!(*token, "RMK" | "TEMPO" | "BECMG" | "NOSIG"))
+        .collect()
+}
+
+fn parse_runway_state(token: &str) -> Option<(String, RunwayCondition)> {
+    let (designator, state) = token.strip_prefix('R')?.split_once('/')?;
+    if designator.len() < 2 || !designator[..2].chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let condition = if state.starts_with("CLRD") {
+        RunwayCondition::Dry
+    } else {
+        // Six digits for deposit, extent, depth and friction, which tells it apart from the
+        // visual range groups like R27L/P1500N or R27L/2400FT
+        if state.len() != 6 || !state.chars().all(|c| c.is_ascii_digit() || c == '/') {
+            return None;
+        }
+        RunwayCondition::from_deposit(state.chars().next()?)?
+    };
+
+    Some((normalize_designator(designator), condition))
+}
+
+// The old format adds 50 to the number of right hand runways, so 77 is 27R
+fn normalize_designator(designator: &str) -> String {
+    match designator.parse::<u32>() {
+        Ok(number @ 51..=86) => format!("{:02}R", number - 50),
+        _ => designator.to_uppercase(),
+    }
+}
+
+// The condition a weather group like -SHRA, FZDZ or +TSRAGS leaves on the runway, or None when
+// it isn't precipitation
+fn precipitation_condition(token: &str) -> Option<RunwayCondition> {
+    const DESCRIPTORS: [&str; 5] = ["SH", "TS", "FZ", "RE", "VC"];
+    const PRECIPITATION: [&str; 8] = ["DZ", "RA", "SN", "SG", "PL", "GR", "GS", "UP"];
+    const FROZEN: [&str; 5] = ["SN", "SG", "PL", "GR", "GS"];
+
+    let token = token.trim_start_matches(['+', '-']);
+    if token.is_empty() || !token.len().is_multiple_of(2) {
+        return None;
+    }
+
+    let codes: Vec<&str> = (0..token.len())
+        .step_by(2)
+        .filter_map(|index| token.get(index..index + 2))
+        .collect();
+    let is_precipitation = codes
+        .iter()
+        .all(|code| DESCRIPTORS.contains(code) || PRECIPITATION.contains(code))
+        && codes.iter().any(|code| PRECIPITATION.contains(code));
+    if !is_precipitation {
+        return None;
+    }
+
+    let freezing = codes.contains(&"FZ") && codes.iter().any(|code| matches!(*code, "RA" | "DZ"));
+    if freezing {
+        Some(RunwayCondition::Ice)
+    } else if codes.iter().any(|code| FROZEN.contains(code)) {
+        Some(RunwayCondition::Snow)
+    } else {
+        Some(RunwayCondition::Wet)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_runway_condition() {
+        let metar = "METAR ESSA 181150Z 36012KT 9999 -SN BKN012 M02/M04 Q1002 R01L/590235 R19R/490240 R88/CLRD//";
+        assert_eq!(from_metar(metar, "01L"), RunwayCondition::Slush);
+        assert_eq!(from_metar(metar, "19R"), RunwayCondition::Snow);
+        // Only the report for all runways applies, which is clear
+        assert_eq!(from_metar(metar, "08"), RunwayCondition::Dry);
+
+        assert_eq!(
+            from_metar("LRAR 181150Z 27010KT 9999 FEW030 12/05 Q1015", "27"),
+            RunwayCondition::Dry
+        );
+        assert_eq!(
+            from_metar("EGLL 181150Z 27010KT 6000 -SHRA BKN012 12/10 Q1005", "27L"),
+            RunwayCondition::Wet
+        );
+        assert_eq!(
+            from_metar(
+                "EGLL 181150Z 27010KT 9999 R27L/2400FT FEW030 12/05 Q1015 RMK RA",
+                "27L"
+            ),
+            RunwayCondition::Dry
+        );
+        assert_eq!(
+            from_metar(
+                "UUEE 181150Z 25005MPS 9999 OVC010 M05/M07 Q1010 R74/710140",
+                "24R"
+            ),
+            RunwayCondition::Ice
+        );
+
+        // Without a runway state group the kind of precipitation decides
+        assert_eq!(
+            from_metar("EFHK 181150Z 22008KT 2000 -SN OVC008 M03/M04 Q0998", "22L"),
+            RunwayCondition::Snow
+        );
+        assert_eq!(
+            from_metar(
+                "CYUL 181150Z 06010KT 3000 -FZRA PL BR OVC006 M01/M02 A2990",
+                "06L"
+            ),
+            RunwayCondition::Ice
+        );
+        assert_eq!(
+            from_metar(
+                "KORD 181150Z 27010KT 5SM -FZDZ BR OVC008 M01/M02 A2995",
+                "27L"
+            ),
+            RunwayCondition::Ice
+        );
+        assert_eq!(
+            from_metar("LIRF 181150Z 25012KT 6000 TSGS FEW020CB 14/10 Q1008", "25"),
+            RunwayCondition::Snow
+        );
+        assert_eq!(
+            from_metar("EDDF 181150Z 25012KT 4000 RASN BKN010 01/00 Q1002", "25C"),
+            RunwayCondition::Snow
+        );
+    }
+}
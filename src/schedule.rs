@@ -0,0 +1,237 @@
+use chrono::{DateTime, FixedOffset, NaiveTime, Offset, TimeDelta, Utc};
+
+use crate::{flightplan::Times, utils};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Milestone {
+    OffBlock,
+    Takeoff,
+    Landing,
+    OnBlock,
+}
+
+impl Milestone {
+    pub const ALL: [Milestone; 4] = [
+        Milestone::OffBlock,
+        Milestone::Takeoff,
+        Milestone::Landing,
+        Milestone::OnBlock,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Milestone::OffBlock => "Off Block",
+            Milestone::Takeoff => "Takeoff",
+            Milestone::Landing => "Landing",
+            Milestone::OnBlock => "On Block",
+        }
+    }
+
+    // Off block and takeoff happen at the departure airport, so they use its local time
+    fn at_departure(&self) -> bool {
+        matches!(self, Milestone::OffBlock | Milestone::Takeoff)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlockTimes {
+    pub off_block: Option<DateTime<Utc>>,
+    pub takeoff: Option<DateTime<Utc>>,
+    pub landing: Option<DateTime<Utc>>,
+    pub on_block: Option<DateTime<Utc>>,
+}
+
+impl BlockTimes {
+    pub fn get(&self, milestone: Milestone) -> Option<DateTime<Utc>> {
+        match milestone {
+            Milestone::OffBlock => self.off_block,
+            Milestone::Takeoff => self.takeoff,
+            Milestone::Landing => self.landing,
+            Milestone::OnBlock => self.on_block,
+        }
+    }
+
+    pub fn block_time(&self) -> Option<TimeDelta> {
+        Some(self.on_block? - self.off_block?)
+    }
+
+    pub fn air_time(&self) -> Option<TimeDelta> {
+        Some(self.landing? - self.takeoff?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schedule {
+    pub scheduled: BlockTimes,
+    pub estimated: BlockTimes,
+    departure_offset: FixedOffset,
+    arrival_offset: FixedOffset,
+}
+
+impl Schedule {
+    pub fn from_times(times: &Times) -> Schedule {
+        Schedule {
+            scheduled: BlockTimes {
+                off_block: times.scheduled_out(),
+                takeoff: times.scheduled_off(),
+                landing: times.scheduled_on(),
+                on_block: times.scheduled_in(),
+            },
+            estimated: BlockTimes {
+                off_block: times.estimated_out(),
+                takeoff: times.estimated_off(),
+                landing: times.estimated_on(),
+                on_block: times.estimated_in(),
+            },
+            departure_offset: parse_timezone_offset(&times.orig_timezone).unwrap_or(Utc.fix()),
+            arrival_offset: parse_timezone_offset(&times.dest_timezone).unwrap_or(Utc.fix()),
+        }
+    }
+
+    // Moves the estimate to the actual off block time and keeps the planned taxi and flight times
+    pub fn expected(&self, actual_off_block: Option<DateTime<Utc>>) -> BlockTimes {
+        let (Some(actual), Some(planned)) = (actual_off_block, self.estimated.off_block) else {
+            return self.estimated;
+        };
+        let delay = actual - planned;
+        let shift = |time: Option<DateTime<Utc>>| time.map(|time| time + delay);
+
+        BlockTimes {
+            off_block: Some(actual),
+            takeoff: shift(self.estimated.takeoff),
+            landing: shift(self.estimated.landing),
+            on_block: shift(self.estimated.on_block),
+        }
+    }
+
+    // The time the pilot typed in, on the day of the planned off block
+    pub fn actual_off_block(&self, input: &str) -> Option<DateTime<Utc>> {
+        self.scheduled
+            .off_block
+            .or(self.estimated.off_block)
+            .and_then(|reference| parse_time_of_day(input, reference))
+    }
+
+    pub fn offset(&self, milestone: Milestone) -> FixedOffset {
+        if milestone.at_departure() {
+            self.departure_offset
+        } else {
+            self.arrival_offset
+        }
+    }
+
+    // Like "1205Z 1305 (UTC+01:00)"
+    pub fn format(&self, milestone: Milestone, time: Option<DateTime<Utc>>) -> String {
+        let Some(time) = time else {
+            return String::new();
+        };
+        let offset = self.offset(milestone);
+
+        format!(
+            "{} {} (UTC{offset})",
+            time.format("%H%MZ"),
+            time.with_timezone(&offset).format("%H%M")
+        )
+    }
+}
+
+pub fn parse_timezone_offset(hours: &str) -> Option<FixedOffset> {
+    let hours: f32 = hours.trim().parse().ok()?;
+
+    FixedOffset::east_opt((hours * 3600.0).round() as i32)
+}
+
+// Accepts 1234, 12:34 or 1234Z and picks the day closest to the reference, so an off block time
+// just after midnight still belongs to a flight planned for the evening before
+pub fn parse_time_of_day(input: &str, reference: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let input = input.trim().trim_end_matches(['Z', 'z']).replace(':', "");
+    if input.len() != 4 {
+        return None;
+    }
+    let time = NaiveTime::parse_from_str(&input, "%H%M").ok()?;
+    let candidate = reference.date_naive().and_time(time).and_utc();
+
+    [
+        candidate - TimeDelta::days(1),
+        candidate,
+        candidate + TimeDelta::days(1),
+    ]
+    .into_iter()
+    .min_by_key(|candidate| (*candidate - reference).abs())
+}
+
+pub fn describe_countdown(label: &str, until: TimeDelta) -> String {
+    let duration = utils::format_duration(until.abs());
+
+    if until >= TimeDelta::zero() {
+        format!("{label} in {duration}")
+    } else {
+        format!("{label} {duration} ago")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_schedule() -> anyhow::Result<()> {
+        let times: Times = serde_json::from_str(
+            r#"{
+                "sched_out": "1760785200",
+                "sched_off": "1760786100",
+                "sched_on": "1760791500",
+                "sched_in": "1760792100",
+                "est_out": "1760785200",
+                "est_off": "1760786400",
+                "est_on": "1760791800",
+                "est_in": "1760792400",
+                "orig_timezone": "2",
+                "dest_timezone": "-4.5"
+            }"#,
+        )?;
+        let schedule = Schedule::from_times(&times);
+
+        assert_eq!(
+            schedule.scheduled.block_time().map(utils::format_duration),
+            Some("1:55".to_string())
+        );
+        assert_eq!(
+            schedule.estimated.air_time().map(utils::format_duration),
+            Some("1:30".to_string())
+        );
+        assert_eq!(
+            schedule.format(Milestone::OffBlock, schedule.scheduled.off_block),
+            "1100Z 1300 (UTC+02:00)"
+        );
+        assert_eq!(
+            schedule.format(Milestone::OnBlock, schedule.scheduled.on_block),
+            "1255Z 0825 (UTC-04:30)"
+        );
+
+        // Pushing back 20 minutes late moves everything after it
+        let actual = parse_time_of_day("11:20", schedule.scheduled.off_block.unwrap());
+        let expected = schedule.expected(actual);
+        assert_eq!(
+            expected
+                .on_block
+                .map(|time| time.format("%H%M").to_string()),
+            Some("1320".to_string())
+        );
+        assert_eq!(expected.block_time(), schedule.estimated.block_time());
+
+        let reference = DateTime::from_timestamp(1760831400, 0).unwrap();
+        assert_eq!(
+            parse_time_of_day("0010Z", reference).map(|time| time.to_rfc3339()),
+            Some("2025-10-19T00:10:00+00:00".to_string())
+        );
+        assert_eq!(parse_time_of_day("2460", reference), None);
+
+        assert_eq!(
+            describe_countdown("Off block", TimeDelta::minutes(-75)),
+            "Off block 1:15 ago"
+        );
+
+        Ok(())
+    }
+}
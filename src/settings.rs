@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+// user.json used to only contain the SimBrief id, this is still accepted when loading
+#[derive(Deserialize, Serialize)]
+pub struct User(pub String);
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Settings {
+    #[serde(default)]
+    pub simbrief_id: String,
+    #[serde(default)]
+    pub hoppie_logon: String,
+    #[serde(default)]
+    pub watchlist: Vec<String>,
+    // Where the OFP files and the exported flight plans are saved, the working directory when
+    // empty
+    #[serde(default)]
+    pub download_directory: String,
+}
+
+impl Settings {
+    pub fn from_json(json: &str) -> Option<Settings> {
+        serde_json::from_str(json).ok().or_else(|| {
+            serde_json::from_str(json)
+                .ok()
+                .map(|User(simbrief_id)| Settings {
+                    simbrief_id,
+                    ..Default::default()
+                })
+        })
+    }
+}
@@ -0,0 +1,212 @@
+use std::{f64::consts::PI, fmt::Display};
+
+use chrono::{DateTime, Datelike, NaiveDate, TimeDelta, Timelike, Utc};
+
+// The centre of the sun is a bit below the horizon at sunrise because of refraction
+const SUNRISE_ZENITH: f64 = 90.833;
+const CIVIL_TWILIGHT_ZENITH: f64 = 96.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Daylight {
+    Day,
+    CivilTwilight,
+    Night,
+}
+
+impl Display for Daylight {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            Daylight::Day => "by day",
+            // Night only starts at the end of the evening civil twilight, for VFR and logging
+            Daylight::CivilTwilight => "in civil twilight (day)",
+            Daylight::Night => "at night",
+        };
+
+        write!(f, "{label}")
+    }
+}
+
+impl Daylight {
+    pub fn at(time: DateTime<Utc>, latitude: f64, longitude: f64) -> Daylight {
+        let zenith = 90.0 - solar_elevation(time, latitude, longitude);
+
+        if zenith < SUNRISE_ZENITH {
+            Daylight::Day
+        } else if zenith < CIVIL_TWILIGHT_ZENITH {
+            Daylight::CivilTwilight
+        } else {
+            Daylight::Night
+        }
+    }
+
+    pub fn is_night(&self) -> bool {
+        *self == Daylight::Night
+    }
+}
+
+// None when the sun doesn't rise or set on that day, which happens close to the poles
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SunTimes {
+    pub civil_dawn: Option<DateTime<Utc>>,
+    pub sunrise: Option<DateTime<Utc>>,
+    pub sunset: Option<DateTime<Utc>>,
+    pub civil_dusk: Option<DateTime<Utc>>,
+    // Tells a polar day from a polar night when there is no sunrise or sunset
+    pub midnight_sun: bool,
+}
+
+impl SunTimes {
+    // Uses the solar day at the location, so the times are on the local day of the given time
+    pub fn around(time: DateTime<Utc>, latitude: f64, longitude: f64) -> SunTimes {
+        let local_time = time + TimeDelta::seconds((longitude * 240.0) as i64);
+
+        SunTimes::on(local_time.date_naive(), latitude, longitude)
+    }
+
+    pub fn on(date: NaiveDate, latitude: f64, longitude: f64) -> SunTimes {
+        let noon = date.and_hms_opt(12, 0, 0).unwrap_or_default().and_utc();
+        let position = SolarPosition::at(noon);
+        let midnight = date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+
+        // Minutes after midnight UTC, before noon for the morning and after it for the evening
+        let event = |zenith: f64, morning: bool| {
+            let hour_angle = position.hour_angle(latitude, zenith)?;
+            let hour_angle = if morning { hour_angle } else { -hour_angle };
+            let minutes = 720.0 - 4.0 * (longitude + hour_angle) - position.equation_of_time;
+
+            Some(midnight + TimeDelta::seconds((minutes * 60.0).round() as i64))
+        };
+
+        let sunrise = event(SUNRISE_ZENITH, true);
+        let solar_noon = noon - TimeDelta::seconds((longitude * 240.0) as i64);
+        let midnight_sun = sunrise.is_none()
+            && 90.0 - solar_elevation(solar_noon, latitude, longitude) < SUNRISE_ZENITH;
+
+        SunTimes {
+            civil_dawn: event(CIVIL_TWILIGHT_ZENITH, true),
+            sunrise,
+            sunset: event(SUNRISE_ZENITH, false),
+            civil_dusk: event(CIVIL_TWILIGHT_ZENITH, false),
+            midnight_sun,
+        }
+    }
+}
+
+// Degrees above the horizon, negative below it
+pub fn solar_elevation(time: DateTime<Utc>, latitude: f64, longitude: f64) -> f64 {
+    let position = SolarPosition::at(time);
+    let minutes = time.num_seconds_from_midnight() as f64 / 60.0;
+    let true_solar_time = minutes + position.equation_of_time + 4.0 * longitude;
+    let hour_angle = (true_solar_time / 4.0 - 180.0).to_radians();
+    let latitude = latitude.to_radians();
+
+    let cos_zenith = latitude.sin() * position.declination.sin()
+        + latitude.cos() * position.declination.cos() * hour_angle.cos();
+
+    90.0 - cos_zenith.clamp(-1.0, 1.0).acos().to_degrees()
+}
+
+// The approximation of the NOAA Global Monitoring Laboratory, good to a minute or two
+struct SolarPosition {
+    // Minutes
+    equation_of_time: f64,
+    // Radians
+    declination: f64,
+}
+
+impl SolarPosition {
+    fn at(time: DateTime<Utc>) -> SolarPosition {
+        let days_in_year = if NaiveDate::from_yo_opt(time.year(), 366).is_some() {
+            366.0
+        } else {
+            365.0
+        };
+        let hour = time.num_seconds_from_midnight() as f64 / 3600.0;
+        let fractional_year =
+            2.0 * PI / days_in_year * (time.ordinal0() as f64 + (hour - 12.0) / 24.0);
+        let (sin1, cos1) = fractional_year.sin_cos();
+        let (sin2, cos2) = (2.0 * fractional_year).sin_cos();
+        let (sin3, cos3) = (3.0 * fractional_year).sin_cos();
+
+        SolarPosition {
+            equation_of_time: 229.18
+                * (0.000075 + 0.001868 * cos1
+                    - 0.032077 * sin1
+                    - 0.014615 * cos2
+                    - 0.040849 * sin2),
+            declination: 0.006918 - 0.399912 * cos1 + 0.070257 * sin1 - 0.006758 * cos2
+                + 0.000907 * sin2
+                - 0.002697 * cos3
+                + 0.00148 * sin3,
+        }
+    }
+
+    // Degrees, None if the sun never reaches the zenith angle that day
+    fn hour_angle(&self, latitude: f64, zenith: f64) -> Option<f64> {
+        let latitude = latitude.to_radians();
+        let cos_hour_angle = zenith.to_radians().cos() / (latitude.cos() * self.declination.cos())
+            - latitude.tan() * self.declination.tan();
+
+        (-1.0..=1.0)
+            .contains(&cos_hour_angle)
+            .then(|| cos_hour_angle.acos().to_degrees())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_sun_times() -> anyhow::Result<()> {
+        // Within the couple of minutes the approximation is good for
+        let assert_close = |time: Option<DateTime<Utc>>, expected: &str| -> anyhow::Result<()> {
+            let expected = DateTime::parse_from_rfc3339(expected)?.to_utc();
+            let difference = time.map(|time| (time - expected).abs());
+            assert!(difference.is_some_and(|difference| difference <= TimeDelta::minutes(2)));
+            Ok(())
+        };
+
+        // London Heathrow on midsummer, published as 0443 and 2121 local time
+        let date = NaiveDate::from_ymd_opt(2025, 6, 21).unwrap();
+        let heathrow = SunTimes::on(date, 51.4706, -0.4619);
+        assert_close(heathrow.sunrise, "2025-06-21T03:43:00Z")?;
+        assert_close(heathrow.sunset, "2025-06-21T20:21:00Z")?;
+        assert!(heathrow.civil_dawn < heathrow.sunrise);
+        assert!(heathrow.civil_dusk > heathrow.sunset);
+
+        // The sun doesn't set in Longyearbyen in June
+        let svalbard = SunTimes::on(date, 78.2461, 15.4656);
+        assert_eq!(svalbard.sunset, None);
+        assert!(svalbard.midnight_sun);
+        assert!(!heathrow.midnight_sun);
+
+        // Nor does it rise in December
+        let date = NaiveDate::from_ymd_opt(2025, 12, 21).unwrap();
+        let svalbard = SunTimes::on(date, 78.2461, 15.4656);
+        assert_eq!(svalbard.sunrise, None);
+        assert!(!svalbard.midnight_sun);
+
+        // Sydney is ahead of UTC, the sunrise of the local day is on the day before in UTC
+        let time = DateTime::parse_from_rfc3339("2025-06-21T22:00:00Z")?.to_utc();
+        let sydney = SunTimes::around(time, -33.9461, 151.1772);
+        // 0700 local time on the 22nd
+        assert_close(sydney.sunrise, "2025-06-21T21:00:00Z")?;
+
+        let heathrow_time = |time: &str| -> anyhow::Result<Daylight> {
+            Ok(Daylight::at(
+                DateTime::parse_from_rfc3339(time)?.to_utc(),
+                51.4706,
+                -0.4619,
+            ))
+        };
+        assert_eq!(heathrow_time("2025-06-21T12:00:00Z")?, Daylight::Day);
+        assert_eq!(
+            heathrow_time("2025-06-21T20:40:00Z")?,
+            Daylight::CivilTwilight
+        );
+        assert!(heathrow_time("2025-12-21T20:00:00Z")?.is_night());
+
+        Ok(())
+    }
+}
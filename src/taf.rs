@@ -0,0 +1,339 @@
+use std::fmt::Display;
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ChangeKind {
+    Base,
+    From,
+    Becoming,
+    Temporary,
+    Probability(u8),
+    ProbabilityTemporary(u8),
+}
+
+impl Display for ChangeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChangeKind::Base => write!(f, "Initially"),
+            ChangeKind::From => write!(f, "From"),
+            ChangeKind::Becoming => write!(f, "Becoming"),
+            ChangeKind::Temporary => write!(f, "Temporarily"),
+            ChangeKind::Probability(probability) => write!(f, "{probability}% probability"),
+            ChangeKind::ProbabilityTemporary(probability) => {
+                write!(f, "{probability}% probability temporarily")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Wind {
+    // None for variable winds
+    pub direction: Option<u32>,
+    pub speed: u32,
+    pub gust: Option<u32>,
+    pub unit: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Cloud {
+    pub cover: String,
+    // Hundreds of feet above the ground
+    pub base: Option<u32>,
+    pub kind: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TafGroup {
+    pub kind: ChangeKind,
+    pub period: String,
+    pub wind: Option<Wind>,
+    pub visibility: Option<String>,
+    pub weather: Vec<String>,
+    pub clouds: Vec<Cloud>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Taf {
+    pub station: String,
+    pub issued: String,
+    pub groups: Vec<TafGroup>,
+}
+
+impl TafGroup {
+    fn new(kind: ChangeKind) -> Self {
+        Self {
+            kind,
+            period: String::new(),
+            wind: None,
+            visibility: None,
+            weather: Vec::new(),
+            clouds: Vec::new(),
+        }
+    }
+
+    fn decode_token(&mut self, token: &str) {
+        if self.period.is_empty() && is_period(token) {
+            self.period = token.to_string();
+        } else if let Some(wind) = parse_wind(token) {
+            self.wind = Some(wind);
+        } else if let Some(visibility) = parse_visibility(token) {
+            self.visibility = Some(visibility);
+        } else if let Some(cloud) = parse_cloud(token) {
+            self.clouds.push(cloud);
+        } else if is_weather(token) {
+            self.weather.push(token.to_string());
+        }
+    }
+}
+
+impl Display for TafGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.kind, format_period(&self.period))?;
+
+        if let Some(wind) = &self.wind {
+            let direction = wind
+                .direction
+                .map(|direction| format!("{direction:03}°"))
+                .unwrap_or("variable".to_string());
+            write!(f, ", wind {direction} {} {}", wind.speed, wind.unit)?;
+            if let Some(gust) = wind.gust {
+                write!(f, " gusting {gust} {}", wind.unit)?;
+            }
+        }
+        if let Some(visibility) = &self.visibility {
+            write!(f, ", visibility {visibility}")?;
+        }
+        if !self.weather.is_empty() {
+            write!(f, ", {}", self.weather.join(" "))?;
+        }
+        for cloud in &self.clouds {
+            match cloud.base {
+                Some(base) => write!(f, ", {} at {} ft", cloud.cover, base * 100)?,
+                None => write!(f, ", {}", cloud.cover)?,
+            }
+            if let Some(kind) = &cloud.kind {
+                write!(f, " {kind}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Taf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "TAF {} issued {}", self.station, self.issued)?;
+        for group in &self.groups {
+            writeln!(f, "  {group}")?;
+        }
+
+        Ok(())
+    }
+}
+
+// Splits the TAF into its change groups (FM, BECMG, TEMPO, PROB) and decodes the wind,
+// visibility, weather and clouds of each one
+pub fn decode_taf(raw: &str) -> Option<Taf> {
+    let mut tokens = raw
+        .split_whitespace()
+        .map(|token| token.trim_end_matches('='))
+        .filter(|token| !matches!(*token, "TAF" | "AMD" | "COR" | "RTD"))
+        .peekable();
+
+    let station = tokens.next()?.to_string();
+    let issued = tokens
+        .next_if(|token| token.ends_with('Z'))
+        .unwrap_or_default()
+        .to_string();
+
+    let mut groups = vec![TafGroup::new(ChangeKind::Base)];
+
+    while let Some(token) = tokens.next() {
+        let kind = if let Some(time) = token.strip_prefix("FM").filter(|time| time.len() >= 6) {
+            Some((ChangeKind::From, Some(time.to_string())))
+        } else if token == "BECMG" {
+            Some((ChangeKind::Becoming, None))
+        } else if token == "TEMPO" {
+            Some((ChangeKind::Temporary, None))
+        } else if let Some(probability) = token.strip_prefix("PROB") {
+            let probability = probability.parse().unwrap_or_default();
+            if tokens.next_if_eq(&"TEMPO").is_some() {
+                Some((ChangeKind::ProbabilityTemporary(probability), None))
+            } else {
+                Some((ChangeKind::Probability(probability), None))
+            }
+        } else {
+            None
+        };
+
+        match kind {
+            Some((kind, period)) => {
+                let mut group = TafGroup::new(kind);
+                group.period = period.unwrap_or_default();
+                groups.push(group);
+            }
+            None => {
+                if let Some(group) = groups.last_mut() {
+                    group.decode_token(token);
+                }
+            }
+        }
+    }
+
+    Some(Taf {
+        station,
+        issued,
+        groups,
+    })
+}
+
+fn is_period(token: &str) -> bool {
+    token.len() == 9
+        && token.as_bytes()[4] == b'/'
+        && token
+            .chars()
+            .filter(|c| *c != '/')
+            .all(|c| c.is_ascii_digit())
+}
+
+fn format_period(period: &str) -> String {
+    match period.split_once('/') {
+        Some((from, to)) if from.len() == 4 && to.len() == 4 => format!(
+            "day {} {}00Z to day {} {}00Z",
+            &from[..2],
+            &from[2..],
+            &to[..2],
+            &to[2..]
+        ),
+        _ if period.len() >= 6 => format!("day {} {}Z", &period[..2], &period[2..6]),
+        _ => period.to_string(),
+    }
+}
+
+fn parse_wind(token: &str) -> Option<Wind> {
+    let unit = ["KT", "MPS", "KMH"]
+        .into_iter()
+        .find(|unit| token.ends_with(unit))?;
+    let value = token.strip_suffix(unit)?;
+    if value.len() < 5 {
+        return None;
+    }
+
+    let direction = match &value[..3] {
+        "VRB" => None,
+        direction => Some(direction.parse().ok()?),
+    };
+    let (speed, gust) = match value[3..].split_once('G') {
+        Some((speed, gust)) => (speed.parse().ok()?, Some(gust.parse().ok()?)),
+        None => (value[3..].parse().ok()?, None),
+    };
+
+    Some(Wind {
+        direction,
+        speed,
+        gust,
+        unit: unit.to_lowercase(),
+    })
+}
+
+fn parse_visibility(token: &str) -> Option<String> {
+    if token == "CAVOK" {
+        return Some("10 km or more, no significant clouds".to_string());
+    }
+    if token.len() == 4 && token.chars().all(|c| c.is_ascii_digit()) {
+        return Some(match token {
+            "9999" => "10 km or more".to_string(),
+            meters => format!("{} m", meters.parse::<u32>().unwrap_or_default()),
+        });
+    }
+
+    token
+        .strip_suffix("SM")
+        .map(|miles| format!("{} statute miles", miles.replace('P', "more than ")))
+}
+
+fn parse_cloud(token: &str) -> Option<Cloud> {
+    if matches!(token, "NSC" | "SKC" | "CLR" | "NCD") {
+        return Some(Cloud {
+            cover: token.to_string(),
+            base: None,
+            kind: None,
+        });
+    }
+
+    let cover = ["FEW", "SCT", "BKN", "OVC", "VV"]
+        .into_iter()
+        .find(|cover| token.starts_with(cover))?;
+    let rest = &token[cover.len()..];
+    let base = rest.get(..3)?.parse().ok();
+    let kind = rest.get(3..).filter(|kind| !kind.is_empty());
+
+    Some(Cloud {
+        cover: cover.to_string(),
+        base,
+        kind: kind.map(str::to_string),
+    })
+}
+
+fn is_weather(token: &str) -> bool {
+    const PHENOMENA: [&str; 28] = [
+        "DZ", "RA", "SN", "SG", "PL", "GR", "GS", "UP", "BR", "FG", "FU", "VA", "DU", "SA", "HZ",
+        "PO", "SQ", "FC", "SS", "DS", "TS", "SH", "FZ", "BL", "DR", "MI", "BC", "NSW",
+    ];
+
+    let token = token
+        .trim_start_matches(['+', '-'])
+        .trim_start_matches("VC");
+    !token.is_empty()
+        && PHENOMENA
+            .iter()
+            .any(|phenomenon| token.contains(phenomenon))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_decode_taf() {
+        let taf = decode_taf(
+            "TAF EGLL 181058Z 1812/1918 24012G22KT 9999 SCT035 \
+             TEMPO 1812/1816 -SHRA BKN014 \
+             PROB30 TEMPO 1900/1906 4000 BR \
+             FM190900 VRB03KT CAVOK",
+        )
+        .unwrap();
+
+        assert_eq!(taf.station, "EGLL");
+        assert_eq!(taf.issued, "181058Z");
+        assert_eq!(taf.groups.len(), 4);
+
+        let base = &taf.groups[0];
+        assert_eq!(base.period, "1812/1918");
+        assert_eq!(
+            base.wind,
+            Some(Wind {
+                direction: Some(240),
+                speed: 12,
+                gust: Some(22),
+                unit: "kt".to_string()
+            })
+        );
+        assert_eq!(base.visibility.as_deref(), Some("10 km or more"));
+        assert_eq!(base.clouds[0].base, Some(35));
+
+        let tempo = &taf.groups[1];
+        assert_eq!(tempo.kind, ChangeKind::Temporary);
+        assert_eq!(tempo.weather, vec!["-SHRA".to_string()]);
+
+        assert_eq!(taf.groups[2].kind, ChangeKind::ProbabilityTemporary(30));
+        assert_eq!(taf.groups[2].visibility.as_deref(), Some("4000 m"));
+
+        let from = &taf.groups[3];
+        assert_eq!(from.kind, ChangeKind::From);
+        assert_eq!(from.period, "190900");
+        assert_eq!(from.wind.as_ref().unwrap().direction, None);
+    }
+}
@@ -0,0 +1,260 @@
+// Many countries publish the transition level as a table depending on the current QNH, so the
+// value SimBrief has planned with is only valid for the QNH it expected. These rules let me
+// recompute the level whenever new weather arrives.
+
+// Tables are usually built around the integer standard pressure
+const STANDARD_PRESSURE_HPA: f32 = 1013.0;
+// Rule of thumb used by most published tables, slightly conservative close to the surface
+const FEET_PER_HPA: f32 = 30.0;
+
+pub enum TransitionLevelRule {
+    // A single published value, independent of the QNH
+    Fixed(u32),
+    // The lowest flight level, in steps of `level_step` flight levels, which still leaves at
+    // least `min_layer` feet above the transition altitude
+    QnhDependent { min_layer: u32, level_step: u32 },
+}
+
+pub struct TransitionRule {
+    pub country: &'static str,
+    pub icao_prefixes: &'static [&'static str],
+    // Only set when the whole country shares a transition altitude, otherwise the OFP value is used
+    pub transition_altitude: Option<u32>,
+    pub level: TransitionLevelRule,
+}
+
+pub const TRANSITION_RULES: &[TransitionRule] = &[
+    TransitionRule {
+        country: "United States",
+        icao_prefixes: &["K", "PA", "PH"],
+        transition_altitude: Some(18000),
+        level: TransitionLevelRule::QnhDependent {
+            min_layer: 0,
+            level_step: 10,
+        },
+    },
+    TransitionRule {
+        country: "Canada",
+        icao_prefixes: &["C"],
+        transition_altitude: Some(18000),
+        level: TransitionLevelRule::QnhDependent {
+            min_layer: 0,
+            level_step: 10,
+        },
+    },
+    TransitionRule {
+        country: "Australia",
+        icao_prefixes: &["Y"],
+        transition_altitude: Some(10000),
+        level: TransitionLevelRule::QnhDependent {
+            min_layer: 1000,
+            level_step: 5,
+        },
+    },
+    TransitionRule {
+        country: "United Kingdom",
+        icao_prefixes: &["EG"],
+        transition_altitude: None,
+        level: TransitionLevelRule::QnhDependent {
+            min_layer: 1000,
+            level_step: 5,
+        },
+    },
+    TransitionRule {
+        country: "Germany",
+        icao_prefixes: &["ED", "ET"],
+        transition_altitude: Some(5000),
+        level: TransitionLevelRule::QnhDependent {
+            min_layer: 1000,
+            level_step: 10,
+        },
+    },
+    TransitionRule {
+        country: "France",
+        icao_prefixes: &["LF"],
+        transition_altitude: None,
+        level: TransitionLevelRule::QnhDependent {
+            min_layer: 1000,
+            level_step: 10,
+        },
+    },
+    TransitionRule {
+        country: "Netherlands",
+        icao_prefixes: &["EH"],
+        transition_altitude: Some(3000),
+        level: TransitionLevelRule::QnhDependent {
+            min_layer: 1000,
+            level_step: 5,
+        },
+    },
+    TransitionRule {
+        country: "Spain",
+        icao_prefixes: &["LE", "GC"],
+        transition_altitude: None,
+        level: TransitionLevelRule::QnhDependent {
+            min_layer: 1000,
+            level_step: 5,
+        },
+    },
+    TransitionRule {
+        country: "Italy",
+        icao_prefixes: &["LI"],
+        transition_altitude: None,
+        level: TransitionLevelRule::QnhDependent {
+            min_layer: 1000,
+            level_step: 10,
+        },
+    },
+    TransitionRule {
+        country: "Switzerland",
+        icao_prefixes: &["LS"],
+        transition_altitude: None,
+        level: TransitionLevelRule::QnhDependent {
+            min_layer: 1000,
+            level_step: 10,
+        },
+    },
+    TransitionRule {
+        country: "South Korea",
+        icao_prefixes: &["RK"],
+        transition_altitude: Some(14000),
+        level: TransitionLevelRule::Fixed(15000),
+    },
+    TransitionRule {
+        country: "Japan",
+        icao_prefixes: &["RJ", "RO"],
+        transition_altitude: Some(14000),
+        level: TransitionLevelRule::Fixed(14000),
+    },
+];
+
+pub fn find_rule(icao: &str) -> Option<&'static TransitionRule> {
+    let icao = icao.to_uppercase();
+
+    // The longest matching prefix wins so that two letter prefixes can override one letter ones
+    TRANSITION_RULES
+        .iter()
+        .flat_map(|rule| rule.icao_prefixes.iter().map(move |prefix| (rule, *prefix)))
+        .filter(|(_, prefix)| icao.starts_with(prefix))
+        .max_by_key(|(_, prefix)| prefix.len())
+        .map(|(rule, _)| rule)
+}
+
+// The OFP value is preferred as transition altitudes are often airport specific
+pub fn transition_altitude(icao: &str, flight_plan_altitude: Option<u32>) -> Option<u32> {
+    flight_plan_altitude.or_else(|| find_rule(icao).and_then(|rule| rule.transition_altitude))
+}
+
+// Returns the transition level in feet. `current_level` is kept whenever the rules do not allow
+// recomputing it, e.g. when no QNH is available yet or the country is not in the table.
+pub fn transition_level(
+    icao: &str,
+    transition_altitude: Option<u32>,
+    qnh: f32,
+    current_level: Option<u32>,
+) -> Option<u32> {
+    match find_rule(icao).map(|rule| &rule.level) {
+        Some(TransitionLevelRule::Fixed(level)) => current_level.or(Some(*level)),
+        Some(TransitionLevelRule::QnhDependent {
+            min_layer,
+            level_step,
+        }) if qnh > 0.0 => transition_altitude
+            .map(|altitude| lowest_usable_level(altitude, qnh, *min_layer, *level_step))
+            .or(current_level),
+        _ => current_level,
+    }
+}
+
+pub fn lowest_usable_level(
+    transition_altitude: u32,
+    qnh: f32,
+    min_layer: u32,
+    level_step: u32,
+) -> u32 {
+    let pressure_altitude =
+        transition_altitude as f32 + (STANDARD_PRESSURE_HPA - qnh) * FEET_PER_HPA;
+    let step = (level_step * 100) as f32;
+
+    (((pressure_altitude + min_layer as f32) / step).ceil() * step) as u32
+}
+
+pub fn format_altitude(altitude: Option<u32>) -> String {
+    match altitude {
+        Some(altitude) => format!("{altitude} ft"),
+        None => "-".to_string(),
+    }
+}
+
+pub fn format_level(level: Option<u32>) -> String {
+    match level {
+        Some(level) => format!("FL{:03}", level / 100),
+        None => "-".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_find_rule() {
+        assert_eq!(
+            find_rule("EGLL").map(|rule| rule.country),
+            Some("United Kingdom")
+        );
+        assert_eq!(
+            find_rule("kjfk").map(|rule| rule.country),
+            Some("United States")
+        );
+        assert!(find_rule("ZBAA").is_none());
+    }
+
+    #[test]
+    pub fn test_united_states_lowest_usable_level() {
+        // 29.92 inHg keeps FL180 usable, anything lower pushes it to FL190
+        assert_eq!(
+            transition_level("KJFK", Some(18000), 1013.2, None),
+            Some(18000)
+        );
+        assert_eq!(
+            transition_level("KJFK", Some(18000), 1012.9, None),
+            Some(19000)
+        );
+        assert_eq!(
+            transition_level("KJFK", Some(18000), 978.0, None),
+            Some(20000)
+        );
+    }
+
+    #[test]
+    pub fn test_australia_table() {
+        assert_eq!(
+            transition_level("YSSY", Some(10000), 1020.0, None),
+            Some(11000)
+        );
+        assert_eq!(
+            transition_level("YSSY", Some(10000), 1005.0, None),
+            Some(11500)
+        );
+        assert_eq!(
+            transition_level("YSSY", Some(10000), 990.0, None),
+            Some(12000)
+        );
+    }
+
+    #[test]
+    pub fn test_keeps_current_level_without_qnh() {
+        assert_eq!(
+            transition_level("EDDF", Some(5000), 0.0, Some(7000)),
+            Some(7000)
+        );
+        assert_eq!(
+            transition_level("ZBAA", Some(9800), 1013.0, Some(11800)),
+            Some(11800)
+        );
+        assert_eq!(
+            transition_level("RKSI", Some(14000), 1013.0, None),
+            Some(15000)
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
+
+use crate::weather::Weather;
+
+pub const HISTORY_HOURS: [u32; 4] = [3, 6, 12, 24];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrendSeries {
+    Wind,
+    Qnh,
+    Spread,
+    Visibility,
+}
+
+impl TrendSeries {
+    pub const ALL: [TrendSeries; 4] = [
+        TrendSeries::Wind,
+        TrendSeries::Qnh,
+        TrendSeries::Spread,
+        TrendSeries::Visibility,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TrendSeries::Wind => "Wind (kt)",
+            TrendSeries::Qnh => "QNH (hPa)",
+            TrendSeries::Spread => "Temperature/dew point spread (°C)",
+            TrendSeries::Visibility => "Visibility (SM)",
+        }
+    }
+
+    pub fn value(&self, weather: &Weather) -> Option<f32> {
+        match self {
+            TrendSeries::Wind => Some(weather.wind_speed().max(weather.wind_gust())),
+            // A missing altimeter setting reads as 0, which would flatten the chart
+            TrendSeries::Qnh => Some(weather.altimeter()).filter(|qnh| *qnh > 0.0),
+            TrendSeries::Spread => Some(weather.temperature() - weather.dew_point()),
+            TrendSeries::Visibility => weather.visibility_miles(),
+        }
+    }
+
+    // Changes smaller than this are treated as noise
+    fn threshold(&self) -> f32 {
+        match self {
+            TrendSeries::Wind => 5.0,
+            TrendSeries::Qnh => 2.0,
+            TrendSeries::Spread => 1.0,
+            TrendSeries::Visibility => 1.0,
+        }
+    }
+
+    // Stronger wind is worse, a rising QNH, a wider spread (less chance of fog) and better
+    // visibility are all better
+    fn rising_is_better(&self) -> bool {
+        !matches!(self, TrendSeries::Wind)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Assessment {
+    Improving,
+    Steady,
+    Deteriorating,
+}
+
+impl Display for Assessment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            Assessment::Improving => "improving",
+            Assessment::Steady => "steady",
+            Assessment::Deteriorating => "deteriorating",
+        };
+
+        write!(f, "{label}")
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Trend {
+    pub station: String,
+    // Oldest observation first
+    pub observations: Vec<Weather>,
+}
+
+impl Trend {
+    pub fn new(station: &str, mut observations: Vec<Weather>) -> Self {
+        observations.sort_by_key(Weather::observation_time);
+
+        Self {
+            station: station.to_string(),
+            observations,
+        }
+    }
+
+    pub fn points(&self, series: TrendSeries) -> Vec<(DateTime<Utc>, f32)> {
+        self.observations
+            .iter()
+            .filter_map(|weather| Some((weather.observation_time()?, series.value(weather)?)))
+            .collect()
+    }
+
+    // Difference between the latest and the oldest observation
+    pub fn change(&self, series: TrendSeries) -> Option<f32> {
+        let points = self.points(series);
+        let (_, first) = points.first()?;
+        let (_, last) = points.last()?;
+
+        Some(last - first)
+    }
+
+    // Each series that changed noticeably votes for better or worse conditions
+    pub fn assessment(&self) -> Assessment {
+        let score: i32 = TrendSeries::ALL
+            .into_iter()
+            .filter_map(|series| {
+                let change = self.change(series)?;
+                if change.abs() < series.threshold() {
+                    return None;
+                }
+
+                let rising = change > 0.0;
+                Some(if rising == series.rising_is_better() {
+                    1
+                } else {
+                    -1
+                })
+            })
+            .sum();
+
+        match score {
+            1.. => Assessment::Improving,
+            0 => Assessment::Steady,
+            _ => Assessment::Deteriorating,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_assessment() {
+        // Newest first, as returned by the API
+        let observations: Vec<Weather> = serde_json::from_str(
+            r#"[
+                {"obsTime": 1760788800, "wspd": 22, "altim": 1004, "temp": 8, "dewp": 7, "visib": "2", "rawOb": ""},
+                {"obsTime": 1760785200, "wspd": 15, "altim": 1008, "temp": 9, "dewp": 6, "visib": "4", "rawOb": ""},
+                {"obsTime": 1760781600, "wspd": 10, "altim": 1012, "temp": 10, "dewp": 5, "visib": "6+", "rawOb": ""}
+            ]"#,
+        )
+        .unwrap();
+        let trend = Trend::new("EGLL", observations);
+
+        assert_eq!(trend.points(TrendSeries::Wind)[0].1, 10.0);
+        assert_eq!(trend.change(TrendSeries::Qnh), Some(-8.0));
+        assert_eq!(trend.change(TrendSeries::Spread), Some(-4.0));
+        assert_eq!(trend.assessment(), Assessment::Deteriorating);
+    }
+}
@@ -1,16 +1,145 @@
+use std::{sync::LazyLock, time::Duration};
+
 use anyhow::{Context, Result};
-use serde::{Deserialize, Deserializer};
+use chrono::{DateTime, TimeDelta, Utc};
+use reqwest::{Client, Method, RequestBuilder, Response};
+use serde::{Deserialize, Deserializer, de::DeserializeOwned};
 use serde_json::Value;
 
+const USER_AGENT: &str = concat!("flypad/", env!("CARGO_PKG_VERSION"));
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+// A stalled download fails long before the whole request times out
+const READ_TIMEOUT: Duration = Duration::from_secs(15);
+const MAX_RETRIES: u32 = 3;
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+// Sharing the client keeps connections alive between requests to the same server
+static HTTP_CLIENT: LazyLock<Client> = LazyLock::new(|| {
+    Client::builder()
+        .user_agent(USER_AGENT)
+        .connect_timeout(CONNECT_TIMEOUT)
+        .timeout(REQUEST_TIMEOUT)
+        .read_timeout(READ_TIMEOUT)
+        .build()
+        .expect("failed to build the HTTP client")
+});
+
+pub fn http_client() -> &'static Client {
+    &HTTP_CLIENT
+}
+
+// Server errors and timeouts are retried, waiting twice as long before every attempt. Anything
+// else, like no network at all, fails straight away so the cached data can be shown instead.
+// The status isn't checked here as callers like the cache need to see a not modified response.
+// Only GETs are retried, a repeated POST could send the same telex or CPDLC message twice.
+pub async fn send_with_retry(request: RequestBuilder) -> Result<Response> {
+    let retryable = request
+        .try_clone()
+        .and_then(|request| request.build().ok())
+        .is_some_and(|request| request.method() == Method::GET);
+    let mut attempt = 0;
+
+    loop {
+        let result = request
+            .try_clone()
+            .context("request can't be retried")?
+            .send()
+            .await;
+        let should_retry = retryable
+            && match &result {
+                Ok(response) => response.status().is_server_error(),
+                Err(e) => e.is_timeout(),
+            };
+
+        if !should_retry || attempt == MAX_RETRIES {
+            return result.context("unable to send request");
+        }
+
+        tokio::time::sleep(RETRY_DELAY * 2u32.pow(attempt)).await;
+        attempt += 1;
+    }
+}
+
 pub async fn fetch_url_data(url: &str) -> Result<String> {
-    reqwest::get(url)
-        .await
+    send_with_retry(http_client().get(url))
+        .await?
+        .error_for_status()
         .context("unable to fetch url")?
         .text()
         .await
         .context("failed to get text from response")
 }
 
+pub async fn post_form_data(url: &str, params: &[(&str, &str)]) -> Result<String> {
+    http_client()
+        .post(url)
+        .form(params)
+        .send()
+        .await
+        .context("unable to send request")?
+        .error_for_status()
+        .context("unable to post form")?
+        .text()
+        .await
+        .context("failed to get text from response")
+}
+
+// Splits a single CSV line, handling quoted fields as used by the OurAirports data files
+pub fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.trim_end_matches(['\r', '\n']).chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+
+    fields
+}
+
+// Word wraps text for fixed width output, words longer than the width are split
+pub fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+
+    for word in text.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+
+        while word.len() > width {
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            lines.push(word.drain(..width).collect());
+        }
+
+        let word: String = word.into_iter().collect();
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(&word);
+    }
+
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+
+    lines
+}
+
 // Simbrief has decided to return empty objects instead of strings for some reason
 // This function allows me to avoid having the untagged StringOrEmptyObject enum in my structs
 pub fn deserialize_flight_plan_string<'de, D>(deserializer: D) -> Result<String, D::Error>
@@ -25,6 +154,14 @@ where
     }
 }
 
+// The placeholder above becomes an empty value again wherever the text is written out
+pub fn known(value: &str) -> &str {
+    match value.trim() {
+        "No Value" => "",
+        value => value,
+    }
+}
+
 // AviationWeather.gov occasionally returns the visibility as a number instead of as a string.
 // This handles that
 pub fn deserialize_optional_string<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
@@ -39,3 +176,178 @@ where
         _ => Ok(None),
     }
 }
+
+// AviationWeather.gov gives the wind direction as "VRB" when it is variable, which has no
+// direction, so anything but a number becomes None
+pub fn deserialize_optional_number<'de, D>(deserializer: D) -> Result<Option<f32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value: Value = Deserialize::deserialize(deserializer)?;
+
+    match value {
+        Value::Number(n) => Ok(n.as_f64().map(|n| n as f32)),
+        Value::String(s) => Ok(s.trim().parse().ok()),
+        _ => Ok(None),
+    }
+}
+
+// Simbrief converts its XML to JSON, so lists with a single entry become a plain object and
+// empty lists may be missing or an empty string.
+pub fn deserialize_one_or_many<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let value: Value = Deserialize::deserialize(deserializer)?;
+
+    match value {
+        Value::Array(values) => Ok(values
+            .into_iter()
+            .filter_map(|value| serde_json::from_value(value).ok())
+            .collect()),
+        Value::Object(_) => Ok(serde_json::from_value(value).into_iter().collect()),
+        _ => Ok(Vec::new()),
+    }
+}
+
+// Hours and minutes, like 1:05
+pub fn format_duration(duration: TimeDelta) -> String {
+    format!(
+        "{}:{:02}",
+        duration.num_hours(),
+        duration.num_minutes() % 60
+    )
+}
+
+// Sections Simbrief didn't calculate are an empty string instead of an object
+pub fn deserialize_optional_object<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let value: Value = Deserialize::deserialize(deserializer)?;
+
+    Ok(serde_json::from_value(value).ok())
+}
+
+// Also used for the XML of the flight plan files
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn unescape_html(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+// Simbrief provides its times as unix timestamps stored in strings
+pub fn parse_unix_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(value.trim().parse().ok()?, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    pub async fn test_retry_server_error() -> anyhow::Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}/", listener.local_addr()?);
+
+        let server = tokio::spawn(async move {
+            let mut user_agents = Vec::new();
+            for reply in [
+                "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok",
+            ] {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buffer = [0; 1024];
+                let read = stream.read(&mut buffer).await.unwrap();
+                let request = String::from_utf8_lossy(&buffer[..read]).to_lowercase();
+                user_agents.push(request.contains(&format!("user-agent: {USER_AGENT}")));
+                stream.write_all(reply.as_bytes()).await.unwrap();
+            }
+            user_agents
+        });
+
+        assert_eq!(fetch_url_data(&url).await?, "ok");
+        assert_eq!(server.await?, vec![true, true]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn test_post_is_not_retried() -> anyhow::Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}/", listener.local_addr()?);
+
+        let server = tokio::spawn(async move {
+            let mut requests = 0;
+            // A retry would arrive well within the timeout
+            while let Ok(Ok((mut stream, _))) =
+                tokio::time::timeout(Duration::from_secs(2), listener.accept()).await
+            {
+                let mut buffer = [0; 1024];
+                let read = stream.read(&mut buffer).await.unwrap();
+                assert!(read > 0);
+                stream
+                    .write_all(b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                    .await
+                    .unwrap();
+                requests += 1;
+            }
+            requests
+        });
+
+        assert!(post_form_data(&url, &[("packet", "hello")]).await.is_err());
+        assert!(
+            send_with_retry(http_client().post(&url))
+                .await?
+                .status()
+                .is_server_error()
+        );
+        assert_eq!(server.await?, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_wrap_text() {
+        assert_eq!(wrap_text("", 10), vec![""]);
+        assert_eq!(
+            wrap_text("the quick brown fox", 9),
+            vec!["the quick", "brown fox"]
+        );
+        assert_eq!(
+            wrap_text("the quick brown fox", 8),
+            vec!["the", "quick", "brown", "fox"]
+        );
+        assert_eq!(
+            wrap_text("xy ABCDEFGHIJ", 4),
+            vec!["xy", "ABCD", "EFGH", "IJ"]
+        );
+        assert_eq!(wrap_text("  spaced   out  ", 20), vec!["spaced out"]);
+    }
+
+    #[test]
+    pub fn test_escape_html() {
+        let text = "<a href=\"x\">R&D</a>";
+        assert_eq!(
+            escape_html(text),
+            "&lt;a href=&quot;x&quot;&gt;R&amp;D&lt;/a&gt;"
+        );
+        assert_eq!(unescape_html(&escape_html(text)), text);
+    }
+}
@@ -0,0 +1,140 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::cache::{self, Source};
+
+const VATSIM_DATA_URL: &str = "https://data.vatsim.net/v3/vatsim-data.json";
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Controller {
+    pub callsign: String,
+    pub frequency: String,
+    #[serde(default)]
+    pub atis_code: Option<String>,
+    #[serde(default)]
+    pub text_atis: Option<Vec<String>>,
+}
+
+impl Controller {
+    // Callsigns look like EDDM_TWR or EDDM_N_APP, the first part being the station
+    pub fn station(&self) -> &str {
+        self.callsign.split('_').next().unwrap_or_default()
+    }
+
+    pub fn position(&self) -> &str {
+        self.callsign.rsplit('_').next().unwrap_or_default()
+    }
+
+    // US airports are usually staffed without the leading K, e.g. JFK_TWR for KJFK
+    pub fn serves_airport(&self, icao: &str) -> bool {
+        let station = self.station();
+
+        station.eq_ignore_ascii_case(icao)
+            || (icao.len() == 4
+                && icao.to_uppercase().starts_with('K')
+                && station.eq_ignore_ascii_case(&icao[1..]))
+    }
+
+    // Centre callsigns start with the FIR, like EGTT_CTR or KZNY_CTR, which shares the first two
+    // letters with the airports in it. Canada, the US and Australia only share the first one.
+    pub fn in_region(&self, icao: &str) -> bool {
+        let station = self.station().to_uppercase();
+        let icao = icao.to_uppercase();
+        let letters = if icao.starts_with(['C', 'K', 'Y']) {
+            1
+        } else {
+            2
+        };
+
+        station.len() == 4
+            && icao.len() == 4
+            && station.get(..letters).is_some()
+            && station.get(..letters) == icao.get(..letters)
+    }
+
+    pub fn atis_text(&self) -> String {
+        self.text_atis.clone().unwrap_or_default().join(" ")
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct VatsimData {
+    #[serde(default)]
+    pub controllers: Vec<Controller>,
+    #[serde(default)]
+    pub atis: Vec<Controller>,
+}
+
+impl VatsimData {
+    pub async fn fetch() -> Result<VatsimData> {
+        let response = cache::fetch(VATSIM_DATA_URL, Source::Vatsim).await?;
+
+        serde_json::from_str(&response.body).context("failed to deserialize VATSIM data")
+    }
+
+    pub fn online_controllers(&self) -> impl Iterator<Item = &Controller> {
+        self.controllers.iter().chain(self.atis.iter())
+    }
+
+    pub fn atis_for(&self, icao: &str) -> Vec<&Controller> {
+        self.atis
+            .iter()
+            .filter(|atis| atis.serves_airport(icao))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn controller(callsign: &str) -> Controller {
+        Controller {
+            callsign: callsign.to_string(),
+            frequency: "118.700".to_string(),
+            atis_code: None,
+            text_atis: None,
+        }
+    }
+
+    #[test]
+    pub fn test_serves_airport() {
+        assert!(controller("EDDM_TWR").serves_airport("EDDM"));
+        assert!(controller("eddm_n_app").serves_airport("EDDM"));
+        assert!(!controller("EDDM_TWR").serves_airport("EDDF"));
+
+        // The K of US airports is left out, but only for US airports
+        assert!(controller("JFK_TWR").serves_airport("KJFK"));
+        assert!(controller("KJFK_TWR").serves_airport("kjfk"));
+        assert!(!controller("JFK_TWR").serves_airport("EJFK"));
+        assert!(!controller("FK_TWR").serves_airport("KFK"));
+        assert!(!controller("LAX_TWR").serves_airport("KJFK"));
+    }
+
+    #[test]
+    pub fn test_in_region() {
+        assert!(controller("EDGG_CTR").in_region("EDDF"));
+        assert!(controller("egtt_ctr").in_region("EGLL"));
+        assert!(controller("KZNY_CTR").in_region("KJFK"));
+        assert!(!controller("EDGG_CTR").in_region("EGLL"));
+        assert!(!controller("EDGG_CTR").in_region("ETNL"));
+        // Without the FIR the region is unknown
+        assert!(!controller("NY_CTR").in_region("KJFK"));
+        assert!(!controller("LON_S_CTR").in_region("EGLL"));
+    }
+
+    #[test]
+    pub fn test_atis_for() {
+        let data = VatsimData {
+            controllers: vec![controller("JFK_TWR")],
+            atis: vec![controller("KJFK_ATIS"), controller("EDDM_ATIS")],
+        };
+
+        let atis: Vec<&str> = data
+            .atis_for("KJFK")
+            .into_iter()
+            .map(|atis| atis.callsign.as_str())
+            .collect();
+        assert_eq!(atis, vec!["KJFK_ATIS"]);
+    }
+}
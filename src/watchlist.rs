@@ -0,0 +1,221 @@
+use std::cmp::Ordering;
+
+use crate::weather::Weather;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortColumn {
+    Station,
+    FlightCategory,
+    Wind,
+    Qnh,
+}
+
+impl SortColumn {
+    pub const ALL: [SortColumn; 4] = [
+        SortColumn::Station,
+        SortColumn::FlightCategory,
+        SortColumn::Wind,
+        SortColumn::Qnh,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SortColumn::Station => "Station",
+            SortColumn::FlightCategory => "Category",
+            SortColumn::Wind => "Wind",
+            SortColumn::Qnh => "QNH",
+        }
+    }
+}
+
+pub struct WatchlistRow<'a> {
+    pub station: &'a str,
+    pub weather: Option<&'a Weather>,
+}
+
+// Airports watched on top of the departure and arrival, like en-route alternates or the home
+// base. The stations are kept in the settings so the list survives a restart.
+#[derive(Debug, Clone)]
+pub struct Watchlist {
+    stations: Vec<String>,
+    weather: Vec<Weather>,
+    sort_column: SortColumn,
+    descending: bool,
+}
+
+impl Default for Watchlist {
+    fn default() -> Self {
+        Self {
+            stations: Vec::new(),
+            weather: Vec::new(),
+            sort_column: SortColumn::Station,
+            descending: false,
+        }
+    }
+}
+
+impl Watchlist {
+    pub fn new(stations: Vec<String>) -> Self {
+        let mut watchlist = Self::default();
+        for station in stations {
+            watchlist.add(&station);
+        }
+        watchlist
+    }
+
+    pub fn stations(&self) -> &[String] {
+        &self.stations
+    }
+
+    // Returns false when the code isn't an ICAO code or is already watched
+    pub fn add(&mut self, icao: &str) -> bool {
+        let icao = icao.trim().to_uppercase();
+        let is_icao = icao.len() == 4 && icao.chars().all(|c| c.is_ascii_alphanumeric());
+
+        if !is_icao || self.stations.contains(&icao) {
+            return false;
+        }
+
+        self.stations.push(icao);
+        true
+    }
+
+    pub fn remove(&mut self, icao: &str) {
+        self.stations.retain(|station| station != icao);
+        self.weather.retain(|weather| weather.station != icao);
+    }
+
+    pub fn set_weather(&mut self, weather: Vec<Weather>) {
+        self.weather = weather;
+    }
+
+    pub fn sort_column(&self) -> SortColumn {
+        self.sort_column
+    }
+
+    pub fn is_descending(&self) -> bool {
+        self.descending
+    }
+
+    // Choosing the current column again reverses the order
+    pub fn sort_by(&mut self, column: SortColumn) {
+        if self.sort_column == column {
+            self.descending = !self.descending;
+        } else {
+            self.sort_column = column;
+            self.descending = false;
+        }
+    }
+
+    pub fn rows(&self) -> Vec<WatchlistRow<'_>> {
+        let mut rows: Vec<WatchlistRow> = self
+            .stations
+            .iter()
+            .map(|station| WatchlistRow {
+                station,
+                weather: self
+                    .weather
+                    .iter()
+                    .find(|weather| &weather.station == station),
+            })
+            .collect();
+
+        rows.sort_by(|a, b| {
+            let ordering = match self.sort_column {
+                SortColumn::Station => a.station.cmp(b.station),
+                SortColumn::FlightCategory => {
+                    category_rank(a.weather).cmp(&category_rank(b.weather))
+                }
+                SortColumn::Wind => compare_values(a.weather, b.weather, Weather::wind_speed),
+                SortColumn::Qnh => compare_values(a.weather, b.weather, Weather::altimeter),
+            };
+
+            if self.descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+
+        rows
+    }
+}
+
+// From the best to the worst conditions, stations without a report come last
+fn category_rank(weather: Option<&Weather>) -> u8 {
+    match weather.map(Weather::flight_category) {
+        Some("VFR") => 0,
+        Some("MVFR") => 1,
+        Some("IFR") => 2,
+        Some("LIFR") => 3,
+        _ => 4,
+    }
+}
+
+fn compare_values(
+    a: Option<&Weather>,
+    b: Option<&Weather>,
+    value: fn(&Weather) -> f32,
+) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => value(a).total_cmp(&value(b)),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+pub fn format_wind(weather: &Weather) -> String {
+    if weather.wind_gust() > weather.wind_speed() {
+        format!(
+            "{:03.0}° {:.0}G{:.0} kt",
+            weather.wind_direction(),
+            weather.wind_speed(),
+            weather.wind_gust()
+        )
+    } else {
+        format!(
+            "{:03.0}° {:.0} kt",
+            weather.wind_direction(),
+            weather.wind_speed()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_sort_rows() {
+        let mut watchlist = Watchlist::new(vec!["egll".to_string(), "EDDF".to_string()]);
+        assert!(!watchlist.add("EGLL"));
+        assert!(!watchlist.add("EGL"));
+        assert!(watchlist.add("KJFK"));
+
+        let weather: Vec<Weather> = serde_json::from_str(
+            r#"[
+                {"icaoId": "EGLL", "fltCat": "IFR", "wspd": 12, "altim": 1003, "visib": "2", "rawOb": ""},
+                {"icaoId": "EDDF", "fltCat": "VFR", "wspd": 4, "altim": 1021, "visib": "6+", "rawOb": ""}
+            ]"#,
+        )
+        .unwrap();
+        watchlist.set_weather(weather);
+
+        let stations = |watchlist: &Watchlist| {
+            watchlist
+                .rows()
+                .iter()
+                .map(|row| row.station.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(stations(&watchlist), vec!["EDDF", "EGLL", "KJFK"]);
+        watchlist.sort_by(SortColumn::FlightCategory);
+        assert_eq!(stations(&watchlist), vec!["EDDF", "EGLL", "KJFK"]);
+        watchlist.sort_by(SortColumn::FlightCategory);
+        assert_eq!(stations(&watchlist), vec!["KJFK", "EGLL", "EDDF"]);
+        watchlist.sort_by(SortColumn::Qnh);
+        assert_eq!(stations(&watchlist), vec!["EGLL", "EDDF", "KJFK"]);
+    }
+}
@@ -1,18 +1,30 @@
+use std::collections::HashMap;
+
 use anyhow::{Context, Result};
-use serde::{de::Error, Deserialize};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-use crate::utils;
+use crate::{
+    cache::{self, Freshness, Source},
+    utils,
+};
 
 
 // This provides getters to the fields instead of making these public as I have found out that
 // the responses may contain null values, therefore I have decided to avoid checking if the value
 // exists within the application, and instead I just return the default value if needed.
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct Weather {
+    #[serde(default)]
+    #[serde(rename = "icaoId")]
+    pub station: String,
     #[serde(rename = "temp")]
     temperature: Option<f32>,
     #[serde(rename = "dewp")]
     dew_point: Option<f32>,
+    #[serde(default)]
+    #[serde(deserialize_with = "utils::deserialize_optional_number")]
     #[serde(rename = "wdir")]
     wind_direction: Option<f32>,
     #[serde(rename = "wspd")]
@@ -24,10 +36,16 @@ pub struct Weather {
     visibility: Option<String>,
     #[serde(rename = "altim")]
     altimeter: Option<f32>,
+    #[serde(rename = "fltCat")]
+    flight_category: Option<String>,
+    #[serde(rename = "obsTime")]
+    observation_time: Option<i64>,
     #[serde(rename = "rawOb")]
     pub metar: String,
     #[serde(rename = "rawTaf")]
     pub taf: Option<String>,
+    #[serde(skip)]
+    pub freshness: Option<Freshness>,
 }
 
 impl Weather {
@@ -35,6 +53,11 @@ impl Weather {
         self.temperature.unwrap_or_default()
     }
 
+    // For the performance, where assuming 0 °C would be wrong rather than just look odd
+    pub fn reported_temperature(&self) -> Option<f32> {
+        self.temperature
+    }
+
     pub fn dew_point(&self) -> f32 {
         self.dew_point.unwrap_or_default()
     }
@@ -62,23 +85,143 @@ impl Weather {
         self.altimeter.unwrap_or_default()
     }
 
+    // VFR, MVFR, IFR or LIFR
+    pub fn flight_category(&self) -> &str {
+        self.flight_category.as_deref().unwrap_or_default()
+    }
+
+    pub fn observation_time(&self) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp(self.observation_time?, 0)
+    }
+
+    // Visibility in statute miles, the API reports values like "6+", "3" or "1 1/2"
+    pub fn visibility_miles(&self) -> Option<f32> {
+        let visibility = self.visibility().trim_end_matches('+');
+        if visibility.trim().is_empty() {
+            return None;
+        }
+
+        visibility
+            .split_whitespace()
+            .map(|part| match part.split_once('/') {
+                Some((numerator, denominator)) => {
+                    Some(numerator.parse::<f32>().ok()? / denominator.parse::<f32>().ok()?)
+                }
+                None => part.parse().ok(),
+            })
+            .sum()
+    }
+
     pub async fn fetch(icao: &str, should_fetch_taf: bool) -> Result<Weather> {
+        let mut weather = Self::fetch_many(&[icao.to_string()], should_fetch_taf).await?;
+
+        weather
+            .reports
+            .remove(&icao.trim().to_uppercase())
+            .context("invalid weather response received")
+    }
+
+    // Requests every station at once and maps the reports back to the stations, so callers
+    // don't rely on the order of the response
+    pub async fn fetch_many(icaos: &[String], should_fetch_taf: bool) -> Result<StationWeather> {
+        let mut stations: Vec<String> = Vec::new();
+        for icao in icaos {
+            let icao = icao.trim().to_uppercase();
+            if !icao.is_empty() && !stations.contains(&icao) {
+                stations.push(icao);
+            }
+        }
+
+        if stations.is_empty() {
+            return Ok(StationWeather::default());
+        }
+
+        let reports = Self::fetch_reports(&stations, should_fetch_taf).await?;
+
+        Ok(StationWeather::new(&stations, reports))
+    }
+
+    // The API takes a comma separated list of stations, so any number of airports only needs a
+    // single request
+    async fn fetch_reports(stations: &[String], should_fetch_taf: bool) -> Result<Vec<Weather>> {
         let url = format!(
-            "https://aviationweather.gov/api/data/metar?ids={icao}&format=json&taf={should_fetch_taf}"
+            "https://aviationweather.gov/api/data/metar?ids={}&format=json&taf={should_fetch_taf}",
+            stations.join(",")
         );
 
-        let body = utils::fetch_url_data(&url).await?;
+        Self::fetch_url(&url).await
+    }
+
+    // Every observation of the last hours, oldest first
+    pub async fn fetch_history(icao: &str, hours: u32) -> Result<Vec<Weather>> {
+        let url = format!(
+            "https://aviationweather.gov/api/data/metar?ids={}&format=json&hours={hours}",
+            icao.trim().to_uppercase()
+        );
 
-        // This is being done as the weather is provided as an array
-        let mut weather: Vec<Weather> =
-            serde_json::from_str(&body).context("failed to deserialize weather")?;
+        let mut weather = Self::fetch_url(&url).await?;
+        weather.sort_by_key(|weather| weather.observation_time);
 
-        if weather.is_empty() {
-            Err(serde_json::Error::custom("empty weather response"))
-                .context("invalid weather response received")?;
+        Ok(weather)
+    }
+
+    async fn fetch_url(url: &str) -> Result<Vec<Weather>> {
+        let response = cache::fetch(url, Source::Weather).await?;
+
+        // Nothing is returned when none of the stations has a report
+        if response.body.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut weather = Self::parse_reports(&response.body)?;
+        for weather in &mut weather {
+            weather.freshness = Some(response.freshness);
         }
 
-        Ok(weather.remove(0))
+        Ok(weather)
+    }
+
+    // The weather is provided as an array. A report that can't be read is left out, so a single
+    // station doesn't take the weather of every other airport with it.
+    fn parse_reports(body: &str) -> Result<Vec<Weather>> {
+        let reports: Vec<Value> =
+            serde_json::from_str(body).context("failed to deserialize weather")?;
+
+        Ok(reports
+            .into_iter()
+            .filter_map(|report| match serde_json::from_value(report) {
+                Ok(weather) => Some(weather),
+                Err(e) => {
+                    eprintln!("Skipping a weather report: {e}");
+                    None
+                }
+            })
+            .collect())
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct StationWeather {
+    pub reports: HashMap<String, Weather>,
+    // Stations that were requested but have no report
+    pub missing: Vec<String>,
+}
+
+impl StationWeather {
+    fn new(stations: &[String], weather: Vec<Weather>) -> Self {
+        let mut reports = HashMap::new();
+        // The newest report comes first if a station has several
+        for weather in weather {
+            reports.entry(weather.station.clone()).or_insert(weather);
+        }
+
+        let missing = stations
+            .iter()
+            .filter(|station| !reports.contains_key(*station))
+            .cloned()
+            .collect();
+
+        Self { reports, missing }
     }
 }
 
@@ -86,6 +229,56 @@ impl Weather {
 mod tests {
     use super::*;
 
+    #[test]
+    pub fn test_station_weather() {
+        let weather: Vec<Weather> = serde_json::from_str(
+            r#"[
+                {"icaoId": "EDDF", "visib": "6+", "rawOb": "EDDF 181020Z"},
+                {"icaoId": "EGLL", "visib": "6+", "rawOb": "EGLL 181050Z"},
+                {"icaoId": "EGLL", "visib": "6+", "rawOb": "EGLL 181020Z"}
+            ]"#,
+        )
+        .unwrap();
+        let stations = ["EGLL", "EDDF", "ZZZZ"].map(str::to_string);
+
+        let station_weather = StationWeather::new(&stations, weather);
+
+        assert_eq!(station_weather.reports.len(), 2);
+        assert_eq!(station_weather.reports["EGLL"].metar, "EGLL 181050Z");
+        assert_eq!(station_weather.missing, vec!["ZZZZ".to_string()]);
+    }
+
+    #[test]
+    pub fn test_parse_reports() {
+        let weather = Weather::parse_reports(
+            r#"[
+                {"icaoId": "EDDF", "wdir": "VRB", "wspd": 2, "visib": "6+", "rawOb": "EDDF 181020Z"},
+                {"icaoId": "EGLL", "wdir": 240, "visib": "6+", "rawOb": "EGLL 181050Z"},
+                {"icaoId": "LFPG", "wspd": "calm", "rawOb": "LFPG 181030Z"}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(weather.len(), 2);
+        assert_eq!(weather[0].wind_direction, None);
+        assert_eq!(weather[0].wind_speed(), 2.0);
+        assert_eq!(weather[1].wind_direction(), 240.0);
+    }
+
+    #[test]
+    pub fn test_visibility_miles() {
+        let visibility = |visib: &str| {
+            let weather: Weather =
+                serde_json::from_str(&format!(r#"{{"visib": "{visib}", "rawOb": ""}}"#)).unwrap();
+            weather.visibility_miles()
+        };
+
+        assert_eq!(visibility("6+"), Some(6.0));
+        assert_eq!(visibility("1 1/2"), Some(1.5));
+        assert_eq!(visibility("3/4"), Some(0.75));
+        assert_eq!(visibility(""), None);
+    }
+
     #[tokio::test]
     pub async fn test_fetch() -> anyhow::Result<()> {
         let weather = Weather::fetch("RKSI", true).await?;
@@ -0,0 +1,152 @@
+use serde::Serialize;
+
+use crate::weather::Weather;
+
+// Runway headings are derived from the designator, which is close enough for wind components
+// even though the METAR wind is true and runways are magnetic.
+#[derive(Debug, Clone, Serialize)]
+pub struct RunwayWind {
+    pub runway: String,
+    pub runway_heading: f32,
+    // Negative values are a tailwind
+    pub headwind: f32,
+    // Positive values are from the right
+    pub crosswind: f32,
+    pub gust_headwind: Option<f32>,
+    pub gust_crosswind: Option<f32>,
+}
+
+impl RunwayWind {
+    pub fn compute(
+        runway: &str,
+        wind_direction: f32,
+        wind_speed: f32,
+        wind_gust: f32,
+    ) -> Option<RunwayWind> {
+        let runway_heading = runway_heading(runway)?;
+        let angle = (wind_direction - runway_heading).to_radians();
+        let (gust_headwind, gust_crosswind) = if wind_gust > wind_speed {
+            (Some(wind_gust * angle.cos()), Some(wind_gust * angle.sin()))
+        } else {
+            (None, None)
+        };
+
+        Some(RunwayWind {
+            runway: runway.trim().to_uppercase(),
+            runway_heading,
+            headwind: wind_speed * angle.cos(),
+            crosswind: wind_speed * angle.sin(),
+            gust_headwind,
+            gust_crosswind,
+        })
+    }
+
+    pub fn from_weather(runway: &str, weather: &Weather) -> Option<RunwayWind> {
+        Self::compute(
+            runway,
+            weather.wind_direction(),
+            weather.wind_speed(),
+            weather.wind_gust(),
+        )
+    }
+
+    pub fn max_crosswind(&self) -> f32 {
+        self.gust_crosswind
+            .unwrap_or(self.crosswind)
+            .abs()
+            .max(self.crosswind.abs())
+    }
+
+    pub fn tailwind(&self) -> f32 {
+        (-self.headwind).max(0.0)
+    }
+
+    pub fn describe(&self) -> String {
+        let longitudinal = if self.headwind >= 0.0 {
+            format!("{:.0} kt headwind", self.headwind)
+        } else {
+            format!("{:.0} kt tailwind", -self.headwind)
+        };
+        let side = if self.crosswind >= 0.0 {
+            "right"
+        } else {
+            "left"
+        };
+        let mut description = format!(
+            "{}: {longitudinal}, {:.0} kt crosswind from the {side}",
+            self.runway,
+            self.crosswind.abs()
+        );
+
+        if let Some(gust_crosswind) = self.gust_crosswind {
+            description.push_str(&format!(" ({:.0} kt in gusts)", gust_crosswind.abs()));
+        }
+
+        description
+    }
+}
+
+// "27L" becomes 270°, "09" becomes 90°
+pub fn runway_heading(runway: &str) -> Option<f32> {
+    let number: String = runway
+        .trim()
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    let number: u32 = number.parse().ok()?;
+
+    (1..=36).contains(&number).then_some((number * 10) as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_headwind() {
+        let wind = RunwayWind::compute("27", 270.0, 15.0, 0.0).unwrap();
+        assert!((wind.headwind - 15.0).abs() < 0.01);
+        assert!(wind.crosswind.abs() < 0.01);
+        assert_eq!(wind.tailwind(), 0.0);
+        assert!(wind.gust_headwind.is_none());
+    }
+
+    #[test]
+    pub fn test_tailwind() {
+        let wind = RunwayWind::compute("09", 250.0, 10.0, 0.0).unwrap();
+        assert!((wind.headwind + 9.40).abs() < 0.01);
+        assert!((wind.tailwind() - 9.40).abs() < 0.01);
+        assert_eq!(
+            wind.describe(),
+            "09: 9 kt tailwind, 3 kt crosswind from the right"
+        );
+    }
+
+    #[test]
+    pub fn test_crosswind() {
+        let from_right = RunwayWind::compute("27", 300.0, 10.0, 20.0).unwrap();
+        assert!((from_right.headwind - 8.66).abs() < 0.01);
+        assert!((from_right.crosswind - 5.0).abs() < 0.01);
+        assert!((from_right.gust_crosswind.unwrap() - 10.0).abs() < 0.01);
+        assert!((from_right.max_crosswind() - 10.0).abs() < 0.01);
+        assert_eq!(
+            from_right.describe(),
+            "27: 9 kt headwind, 5 kt crosswind from the right (10 kt in gusts)"
+        );
+
+        let from_left = RunwayWind::compute("27L", 240.0, 10.0, 0.0).unwrap();
+        assert!((from_left.crosswind + 5.0).abs() < 0.01);
+        assert_eq!(
+            from_left.describe(),
+            "27L: 9 kt headwind, 5 kt crosswind from the left"
+        );
+    }
+
+    #[test]
+    pub fn test_runway_heading() {
+        assert_eq!(runway_heading("27L"), Some(270.0));
+        assert_eq!(runway_heading("09"), Some(90.0));
+        assert_eq!(runway_heading("37"), None);
+        assert_eq!(runway_heading("XX"), None);
+    }
+}